@@ -3,7 +3,7 @@
 
 use mcafee::{
     crypto::{
-        vdf::temporal::TemporalVDF,
+        vdf::typestate::{Lifecycle, Step, Vdf},
         sharing::ThreePartySecretSharing,
         utils::padding,
     },
@@ -20,7 +20,7 @@ struct ProtectedImage {
     bits_per_pixel: usize,
     modality: ImageModality,
     shares: Vec<Vec<u8>>,
-    vdf_states: Vec<TemporalVDF>,
+    vdf_states: Vec<Option<Lifecycle>>,
     processed_shares: Vec<bool>,
     share_padded: bool,
 }
@@ -28,11 +28,24 @@ struct ProtectedImage {
 #[derive(Debug, Clone, Copy)]
 enum ImageModality {
     XRay,
-    MRI,
-    CT,
+    Mri,
+    Ct,
     Ultrasound,
 }
 
+impl ImageModality {
+    /// Parses a modality name given on the command line
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "xray" | "x-ray" => Some(ImageModality::XRay),
+            "mri" => Some(ImageModality::Mri),
+            "ct" => Some(ImageModality::Ct),
+            "ultrasound" => Some(ImageModality::Ultrasound),
+            _ => None,
+        }
+    }
+}
+
 impl ProtectedImage {
     fn new(data: &[u8], dimensions: (usize, usize),
            bits_per_pixel: usize, modality: ImageModality) -> CryptoResult<Self> {
@@ -64,9 +77,12 @@ impl ProtectedImage {
             enforce_timing: true,
             memory_size: padded_data.len(),
             verification_steps: 4,
+            ..Default::default()
         };
 
-        let vdf_states: Vec<_> = (0..3).map(|_| TemporalVDF::new(vdf_config.clone())).collect();
+        let vdf_states: Vec<_> = (0..3)
+            .map(|_| Some(Lifecycle::Uninitialized(Vdf::new(vdf_config.clone()))))
+            .collect();
         let processed_shares = vec![false; 3];
 
         Ok(Self {
@@ -85,23 +101,26 @@ impl ProtectedImage {
 
         // Find the next unprocessed share
         if let Some(share_idx) = self.processed_shares.iter().position(|&x| !x) {
-            let vdf = &mut self.vdf_states[share_idx];
-
-            // Initialize VDF if needed
-            if !vdf.is_initialized() {
-                debug!("Initializing VDF with share {}", share_idx);
-                vdf.initialize(&self.shares[share_idx])?;
-            }
+            let running = match self.vdf_states[share_idx].take() {
+                Some(Lifecycle::Uninitialized(vdf)) => {
+                    debug!("Initializing VDF with share {}", share_idx);
+                    vdf.initialize(&self.shares[share_idx])?
+                }
+                Some(Lifecycle::Running(vdf)) => vdf,
+                _ => return Err(mcafee::error::CryptoError::InvalidState(
+                    format!("No pending VDF for share {}", share_idx)
+                )),
+            };
 
             // Perform iteration
             debug!("Performing VDF iteration for share {}", share_idx);
-            vdf.iterate()?;
-
-            // Check if share processing is complete
-            if vdf.is_complete() {
-                let processed_share = vdf.get_output()?;
-                self.shares[share_idx] = processed_share;
-                self.processed_shares[share_idx] = true;
+            match running.iterate()? {
+                Step::Running(vdf) => self.vdf_states[share_idx] = Some(Lifecycle::Running(vdf)),
+                Step::Complete(vdf) => {
+                    self.shares[share_idx] = vdf.get_output()?;
+                    self.processed_shares[share_idx] = true;
+                    self.vdf_states[share_idx] = Some(Lifecycle::Complete(vdf));
+                }
             }
         }
 
@@ -133,24 +152,38 @@ impl ProtectedImage {
 
         // First reconstruct the padded data
         let padded = sharing.reconstruct(&shares)?;
+        if !self.share_padded {
+            return Ok(padded);
+        }
 
         // Then unpad to get original data
         padding::unpad_data(&padded)
     }
+
+    /// Size in bytes of the unprotected image
+    fn image_size(&self) -> usize {
+        self.dimensions.0 * self.dimensions.1 * (self.bits_per_pixel / 8)
+    }
 }
 
 fn main() -> CryptoResult<()> {
     // Initialize logging
     tracing_subscriber::fmt::init();
 
-    // Simulate a medical image (e.g., chest X-ray)
+    // Simulate a medical image, an X-ray unless another modality is named
+    let modality = match std::env::args().nth(1) {
+        Some(name) => ImageModality::from_name(&name).ok_or_else(|| {
+            mcafee::error::CryptoError::InvalidInput(format!("Unknown modality {}", name))
+        })?,
+        None => ImageModality::XRay,
+    };
     let width = 2048;
     let height = 2048;
     let bits_per_pixel = 16;
     let image_size = width * height * (bits_per_pixel / 8);
 
-    info!("Creating simulated X-ray image ({} x {}, {} bits)",
-         width, height, bits_per_pixel);
+    info!("Creating simulated {:?} image ({} x {}, {} bits)",
+         modality, width, height, bits_per_pixel);
 
     // Create realistic-looking image data
     let mut image_data = Vec::with_capacity(image_size);
@@ -173,10 +206,10 @@ fn main() -> CryptoResult<()> {
         &image_data,
         (width, height),
         bits_per_pixel,
-        ImageModality::XRay
+        modality
     )?;
 
-    info!("Image protected in {:?}", start.elapsed());
+    info!(modality = ?protected.modality, "Image protected in {:?}", start.elapsed());
 
     // Complete all iterations for all shares
     let total_iterations = 12; // 4 iterations for each of 3 shares
@@ -194,7 +227,7 @@ fn main() -> CryptoResult<()> {
     info!("Reconstructed in {:?}", start.elapsed());
 
     // Verify reconstruction
-    assert_eq!(reconstructed.len(), image_data.len(),
+    assert_eq!(reconstructed.len(), protected.image_size(),
                "Reconstructed size mismatch");
     assert_eq!(&reconstructed[..], &image_data[..],
                "Image data mismatch");
@@ -3,11 +3,12 @@
 
 use mcafee::{
     crypto::{
-        vdf::temporal::TemporalVDF,
+        vdf::{temporal::TemporalVDF, ProgressEvent},
         sharing::ThreePartySecretSharing,
     },
     error::CryptoResult,
 };
+use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
 /// Simulates a medical image training session with privacy controls
@@ -16,6 +17,7 @@ struct SecureMedicalTraining {
     sharing: ThreePartySecretSharing,
     training_duration: Duration,
     current_phase: TrainingPhase,
+    progress: mpsc::Receiver<ProgressEvent>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -34,6 +36,7 @@ impl SecureMedicalTraining {
             enforce_timing: true,
             memory_size: 1024 * 1024, // 1MB working memory
             verification_steps: 4,
+            ..Default::default()
         };
 
         let sharing_config = mcafee::crypto::sharing::SharingConfig {
@@ -42,11 +45,17 @@ impl SecureMedicalTraining {
             block_size: 1024 * 16,  // 16KB blocks
         };
 
+        // Receive VDF progress events alongside the phase updates
+        let (sender, progress) = mpsc::channel();
+        let mut vdf = TemporalVDF::new(vdf_config);
+        vdf.add_observer(sender);
+
         Self {
-            vdf: TemporalVDF::new(vdf_config),
+            vdf,
             sharing: ThreePartySecretSharing::new(sharing_config),
             training_duration,
             current_phase: TrainingPhase::DataPreparation,
+            progress,
         }
     }
 
@@ -66,7 +75,7 @@ impl SecureMedicalTraining {
                 self.vdf.initialize(share.data())?;
 
                 // Progress through VDF iterations
-                for _ in 0..4 {
+                while !self.vdf.is_complete() {
                     self.vdf.iterate()?;
                     self.check_training_progress(start)?;
                 }
@@ -76,8 +85,15 @@ impl SecureMedicalTraining {
         Ok(())
     }
 
-    /// Update training phase based on time elapsed
+    /// Update training phase based on time elapsed and report VDF progress
     fn check_training_progress(&mut self, start: Instant) -> CryptoResult<()> {
+        for event in self.progress.try_iter() {
+            if let ProgressEvent::Iteration { iteration, total, estimated_remaining, .. } = event {
+                println!("Share iteration {}/{} - release in {}",
+                         iteration, total, format_remaining(estimated_remaining));
+            }
+        }
+
         let elapsed = start.elapsed();
         let progress = elapsed.as_secs_f32() / self.training_duration.as_secs_f32();
 
@@ -96,6 +112,16 @@ impl SecureMedicalTraining {
     }
 }
 
+/// Formats a duration as hours and minutes, e.g. "3h12m"
+fn format_remaining(remaining: Duration) -> String {
+    let secs = remaining.as_secs();
+    match (secs / 3600, (secs % 3600) / 60) {
+        (0, 0) => format!("{}ms", remaining.as_millis()),
+        (0, minutes) => format!("{}m{}s", minutes, secs % 60),
+        (hours, minutes) => format!("{}h{}m", hours, minutes),
+    }
+}
+
 fn main() -> CryptoResult<()> {
     // Configure training session
     let mut training = SecureMedicalTraining::new(Duration::from_secs(60));
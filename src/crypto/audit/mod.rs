@@ -0,0 +1,443 @@
+//! Hash-chained audit log of share and proof operations
+//! Location: src/crypto/audit/mod.rs
+//!
+//! Each record carries the SHA-256 hash of the record before it, so editing
+//! or removing a record breaks every later link. The hashes are unkeyed:
+//! anyone who can write the store can rewrite the whole chain consistently.
+//! The log is therefore tamper-evident only against a head published
+//! outside the writer's reach and checked with `verify_chain_against`, which
+//! also catches records removed from the end.
+
+pub mod store;
+
+pub use store::{AuditStore, FileAuditStore, MemoryAuditStore};
+
+use crate::crypto::protocol::SecretId;
+use crate::crypto::utils::codec::{Decoder, Encoder};
+use crate::error::{CryptoError, CryptoResult};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Domain separator for record hashes
+const HASH_DOMAIN: &[u8] = b"mcafee/audit/v1";
+/// Previous hash of the first record
+pub const GENESIS_HASH: [u8; 32] = [0; 32];
+
+/// Operation recorded in the audit log
+///
+/// Events name shares by their hash, never by content, so the log can be
+/// handed to an auditor without exposing secrets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditEvent {
+    /// A secret was split into shares with these hashes
+    ///
+    /// `secret` is `None` when the caller split without naming the secret.
+    Split { secret: Option<SecretId>, share_hashes: Vec<[u8; 32]> },
+    /// A custodian acknowledged its share of a secret
+    ShareDelivered { secret: SecretId, share_id: u8 },
+    /// A share failed its integrity check
+    VerifyFailed { secret: Option<SecretId>, share_id: u8 },
+    /// A secret was rebuilt from shares with these hashes
+    Reconstruct { secret: Option<SecretId>, share_hashes: Vec<[u8; 32]> },
+    /// A VDF computation produced a proof
+    VdfProofGenerated {
+        initial_state_hash: [u8; 32],
+        final_state_hash: [u8; 32],
+        iterations: u64,
+    },
+}
+
+impl AuditEvent {
+    /// Short name of the event type
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AuditEvent::Split { .. } => "split",
+            AuditEvent::ShareDelivered { .. } => "share-delivered",
+            AuditEvent::VerifyFailed { .. } => "verify-failed",
+            AuditEvent::Reconstruct { .. } => "reconstruct",
+            AuditEvent::VdfProofGenerated { .. } => "vdf-proof-generated",
+        }
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        match self {
+            AuditEvent::Split { secret, share_hashes } => {
+                encoder.put_u32(1);
+                put_secret(encoder, secret);
+                put_hashes(encoder, share_hashes);
+            }
+            AuditEvent::ShareDelivered { secret, share_id } => {
+                encoder.put_u32(2).put_raw(secret.as_bytes()).put_raw(&[*share_id]);
+            }
+            AuditEvent::VerifyFailed { secret, share_id } => {
+                encoder.put_u32(3);
+                put_secret(encoder, secret);
+                encoder.put_raw(&[*share_id]);
+            }
+            AuditEvent::Reconstruct { secret, share_hashes } => {
+                encoder.put_u32(4);
+                put_secret(encoder, secret);
+                put_hashes(encoder, share_hashes);
+            }
+            AuditEvent::VdfProofGenerated { initial_state_hash, final_state_hash, iterations } => {
+                encoder
+                    .put_u32(5)
+                    .put_raw(initial_state_hash)
+                    .put_raw(final_state_hash)
+                    .put_u64(*iterations);
+            }
+        }
+    }
+
+    fn decode(decoder: &mut Decoder<'_>) -> CryptoResult<Self> {
+        Ok(match decoder.get_u32()? {
+            1 => AuditEvent::Split {
+                secret: get_optional_secret(decoder)?,
+                share_hashes: get_hashes(decoder)?,
+            },
+            2 => AuditEvent::ShareDelivered {
+                secret: get_secret(decoder)?,
+                share_id: decoder.get_raw(1)?[0],
+            },
+            3 => AuditEvent::VerifyFailed {
+                secret: get_optional_secret(decoder)?,
+                share_id: decoder.get_raw(1)?[0],
+            },
+            4 => AuditEvent::Reconstruct {
+                secret: get_optional_secret(decoder)?,
+                share_hashes: get_hashes(decoder)?,
+            },
+            5 => AuditEvent::VdfProofGenerated {
+                initial_state_hash: decoder.get_array32()?,
+                final_state_hash: decoder.get_array32()?,
+                iterations: decoder.get_u64()?,
+            },
+            tag => return Err(CryptoError::InvalidInput(format!("Unknown audit event tag {}", tag))),
+        })
+    }
+}
+
+fn put_secret(encoder: &mut Encoder, secret: &Option<SecretId>) {
+    match secret {
+        Some(secret) => encoder.put_raw(&[1]).put_raw(secret.as_bytes()),
+        None => encoder.put_raw(&[0]),
+    };
+}
+
+fn get_secret(decoder: &mut Decoder<'_>) -> CryptoResult<SecretId> {
+    let mut secret = [0u8; 16];
+    secret.copy_from_slice(decoder.get_raw(16)?);
+    Ok(SecretId::from_bytes(secret))
+}
+
+fn get_optional_secret(decoder: &mut Decoder<'_>) -> CryptoResult<Option<SecretId>> {
+    match decoder.get_raw(1)?[0] {
+        0 => Ok(None),
+        1 => get_secret(decoder).map(Some),
+        flag => Err(CryptoError::InvalidInput(format!("Invalid audit secret flag {}", flag))),
+    }
+}
+
+fn put_hashes(encoder: &mut Encoder, hashes: &[[u8; 32]]) {
+    encoder.put_u64(hashes.len() as u64);
+    for hash in hashes {
+        encoder.put_raw(hash);
+    }
+}
+
+fn get_hashes(decoder: &mut Decoder<'_>) -> CryptoResult<Vec<[u8; 32]>> {
+    (0..decoder.get_u64()?).map(|_| decoder.get_array32()).collect()
+}
+
+/// One link in the audit chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditRecord {
+    /// Position in the log, starting at zero
+    pub sequence: u64,
+    /// When the event was recorded, to the microsecond
+    pub timestamp: DateTime<Utc>,
+    /// Who performed the operation
+    pub actor: String,
+    pub event: AuditEvent,
+    /// Hash of the previous record, `GENESIS_HASH` for the first
+    pub prev_hash: [u8; 32],
+    /// Hash over every field above
+    pub hash: [u8; 32],
+}
+
+impl AuditRecord {
+    /// Recomputes the hash this record should carry
+    pub fn compute_hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(HASH_DOMAIN);
+        hasher.update(self.encode_body());
+        hasher.finalize().into()
+    }
+
+    fn encode_body(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new();
+        encoder
+            .put_u64(self.sequence)
+            .put_u64(self.timestamp.timestamp_micros() as u64)
+            .put_bytes(self.actor.as_bytes());
+        self.event.encode(&mut encoder);
+        encoder.put_raw(&self.prev_hash);
+        encoder.finish()
+    }
+
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut encoded = self.encode_body();
+        encoded.extend_from_slice(&self.hash);
+        encoded
+    }
+
+    pub(crate) fn decode(data: &[u8]) -> CryptoResult<Self> {
+        let mut decoder = Decoder::new(data);
+        let sequence = decoder.get_u64()?;
+        let timestamp = DateTime::from_timestamp_micros(decoder.get_u64()? as i64)
+            .ok_or_else(|| CryptoError::InvalidInput("Audit timestamp out of range".into()))?;
+        let actor = String::from_utf8(decoder.get_bytes()?.to_vec())
+            .map_err(|_| CryptoError::InvalidInput("Audit actor is not UTF-8".into()))?;
+        let event = AuditEvent::decode(&mut decoder)?;
+        let prev_hash = decoder.get_array32()?;
+        let hash = decoder.get_array32()?;
+        decoder.finish()?;
+        Ok(Self { sequence, timestamp, actor, event, prev_hash, hash })
+    }
+}
+
+/// Length and last hash of a chain, for anchoring it outside the log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditHead {
+    /// Number of records
+    pub length: u64,
+    /// Hash of the last record, `GENESIS_HASH` when empty
+    pub hash: [u8; 32],
+}
+
+impl Default for AuditHead {
+    fn default() -> Self {
+        Self { length: 0, hash: GENESIS_HASH }
+    }
+}
+
+/// Checks that records form an unbroken chain from the genesis hash
+///
+/// Detects modified, reordered and deleted records, except records deleted
+/// from the end; use `verify_chain_against` with a saved head for that.
+pub fn verify_chain(records: &[AuditRecord]) -> CryptoResult<AuditHead> {
+    let mut head = AuditHead::default();
+    for record in records {
+        if record.sequence != head.length {
+            return Err(CryptoError::VerificationFailed(
+                format!("Audit record {} found where record {} belongs", record.sequence, head.length)
+            ));
+        }
+        if record.prev_hash != head.hash {
+            return Err(CryptoError::VerificationFailed(
+                format!("Audit record {} does not follow the record before it", record.sequence)
+            ));
+        }
+        if record.compute_hash() != record.hash {
+            return Err(CryptoError::VerificationFailed(
+                format!("Audit record {} has been modified", record.sequence)
+            ));
+        }
+        head = AuditHead { length: head.length + 1, hash: record.hash };
+    }
+    Ok(head)
+}
+
+/// Checks the chain and that it still contains a previously saved head
+pub fn verify_chain_against(records: &[AuditRecord], anchor: &AuditHead) -> CryptoResult<AuditHead> {
+    let head = verify_chain(records)?;
+    if head.length < anchor.length {
+        return Err(CryptoError::VerificationFailed(
+            format!("Audit log has {} records but {} were anchored", head.length, anchor.length)
+        ));
+    }
+    let anchored = match anchor.length {
+        0 => GENESIS_HASH,
+        length => records[length as usize - 1].hash,
+    };
+    if anchored != anchor.hash {
+        return Err(CryptoError::VerificationFailed("Audit log diverges from the anchored head".into()));
+    }
+    Ok(head)
+}
+
+/// Chain state behind the log's lock
+#[derive(Debug)]
+struct Chain {
+    store: Box<dyn AuditStore>,
+    head: AuditHead,
+}
+
+/// Append-only, hash-chained log of audit events
+///
+/// Shared behind an `Arc` by every component that records events.
+#[derive(Debug)]
+pub struct AuditLog {
+    actor: String,
+    chain: Mutex<Chain>,
+}
+
+impl AuditLog {
+    /// Opens a log over `store`, continuing its chain after verifying it
+    pub fn new(actor: impl Into<String>, store: Box<dyn AuditStore>) -> CryptoResult<Self> {
+        let head = verify_chain(&store.records()?)?;
+        Ok(Self {
+            actor: actor.into(),
+            chain: Mutex::new(Chain { store, head }),
+        })
+    }
+
+    /// Log held in memory, lost on restart
+    pub fn in_memory(actor: impl Into<String>) -> Self {
+        Self {
+            actor: actor.into(),
+            chain: Mutex::new(Chain {
+                store: Box::new(MemoryAuditStore::new()),
+                head: AuditHead::default(),
+            }),
+        }
+    }
+
+    /// Identity recorded as the actor of every event
+    pub fn actor(&self) -> &str {
+        &self.actor
+    }
+
+    /// Appends an event, returning the stored record
+    pub fn record(&self, event: AuditEvent) -> CryptoResult<AuditRecord> {
+        let mut chain = self.chain.lock()
+            .map_err(|_| CryptoError::InvalidState("Audit log lock poisoned".into()))?;
+        let now = Utc::now();
+        let mut record = AuditRecord {
+            sequence: chain.head.length,
+            timestamp: DateTime::from_timestamp_micros(now.timestamp_micros()).unwrap_or(now),
+            actor: self.actor.clone(),
+            event,
+            prev_hash: chain.head.hash,
+            hash: GENESIS_HASH,
+        };
+        record.hash = record.compute_hash();
+
+        chain.store.append(&record)?;
+        chain.head = AuditHead { length: record.sequence + 1, hash: record.hash };
+        debug!(sequence = record.sequence, event = record.event.kind(), "Audit event recorded");
+        Ok(record)
+    }
+
+    /// Every record, oldest first
+    pub fn records(&self) -> CryptoResult<Vec<AuditRecord>> {
+        let chain = self.chain.lock()
+            .map_err(|_| CryptoError::InvalidState("Audit log lock poisoned".into()))?;
+        chain.store.records()
+    }
+
+    /// Current length and last hash, to be published as an anchor
+    pub fn head(&self) -> CryptoResult<AuditHead> {
+        let chain = self.chain.lock()
+            .map_err(|_| CryptoError::InvalidState("Audit log lock poisoned".into()))?;
+        Ok(chain.head)
+    }
+
+    /// Re-reads the stored records and checks they still match the chain written
+    pub fn verify(&self) -> CryptoResult<AuditHead> {
+        let head = self.head()?;
+        let result = verify_chain_against(&self.records()?, &head);
+        if let Err(err) = &result {
+            warn!(%err, "Audit log verification failed");
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::sharing::ThreePartySecretSharing;
+    use crate::crypto::vdf::temporal::{TemporalConfig, TemporalVDF};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn sample_log() -> CryptoResult<Vec<AuditRecord>> {
+        let log = AuditLog::in_memory("dealer");
+        let secret = SecretId::random();
+        log.record(AuditEvent::Split { secret: Some(secret), share_hashes: vec![[1; 32], [2; 32], [3; 32]] })?;
+        log.record(AuditEvent::ShareDelivered { secret, share_id: 1 })?;
+        log.record(AuditEvent::VerifyFailed { secret: None, share_id: 2 })?;
+        log.record(AuditEvent::Reconstruct { secret: Some(secret), share_hashes: vec![[1; 32], [2; 32], [3; 32]] })?;
+        log.records()
+    }
+
+    #[test]
+    fn test_chain_detects_modification_and_deletion() -> CryptoResult<()> {
+        let records = sample_log()?;
+        let head = verify_chain(&records)?;
+        assert_eq!(head.length, 4);
+
+        let mut modified = records.clone();
+        modified[1].event = AuditEvent::ShareDelivered { secret: SecretId::random(), share_id: 1 };
+        assert!(matches!(verify_chain(&modified), Err(CryptoError::VerificationFailed(_))));
+
+        // Re-hashing the edited record still breaks the next link
+        modified[1].hash = modified[1].compute_hash();
+        assert!(matches!(verify_chain(&modified), Err(CryptoError::VerificationFailed(_))));
+
+        let mut deleted = records.clone();
+        deleted.remove(2);
+        assert!(matches!(verify_chain(&deleted), Err(CryptoError::VerificationFailed(_))));
+
+        // Truncation leaves a valid chain but falls short of the anchor
+        let truncated = &records[..3];
+        assert!(verify_chain(truncated).is_ok());
+        assert!(matches!(verify_chain_against(truncated, &head), Err(CryptoError::VerificationFailed(_))));
+        assert_eq!(verify_chain_against(&records, &head)?, head);
+
+        for record in &records {
+            assert_eq!(&AuditRecord::decode(&record.encode())?, record);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sharing_and_vdf_hooks() -> CryptoResult<()> {
+        let log = Arc::new(AuditLog::in_memory("node-a"));
+        let mut sharing = ThreePartySecretSharing::default().with_audit_log(Arc::clone(&log));
+
+        let shares = sharing.split(b"audited secret")?;
+        assert_eq!(sharing.reconstruct(&shares)?, b"audited secret");
+
+        let mut tampered = shares.clone();
+        tampered[2] = crate::crypto::sharing::Share::from_parts(vec![0; shares[2].data().len()], 2, *shares[2].hash());
+        assert!(sharing.reconstruct(&tampered).is_err());
+
+        let config = TemporalConfig {
+            min_iteration_time: Duration::from_millis(1),
+            enforce_timing: false,
+            ..Default::default()
+        };
+        let mut vdf = TemporalVDF::new(config).with_audit_log(Arc::clone(&log));
+        vdf.initialize(b"input")?;
+        vdf.run_to_completion()?;
+        let proof = vdf.generate_proof()?;
+
+        let records = log.records()?;
+        let kinds: Vec<_> = records.iter().map(|r| r.event.kind()).collect();
+        assert_eq!(kinds, ["split", "reconstruct", "verify-failed", "vdf-proof-generated"]);
+        let hashes: Vec<_> = shares.iter().map(|s| *s.hash()).collect();
+        assert_eq!(records[0].event, AuditEvent::Split { secret: None, share_hashes: hashes });
+        assert_eq!(records[3].event, AuditEvent::VdfProofGenerated {
+            initial_state_hash: proof.initial_state_hash(),
+            final_state_hash: proof.final_state_hash(),
+            iterations: proof.iteration_count() as u64,
+        });
+        assert!(records.iter().all(|r| r.actor == "node-a"));
+        assert_eq!(log.verify()?.length, 4);
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+//! Storage backends for audit records
+//! Location: src/crypto/audit/store.rs
+
+use super::AuditRecord;
+use crate::crypto::utils::codec::{Decoder, Encoder};
+use crate::error::{CryptoError, CryptoResult};
+use std::fmt::Debug;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Audit log file magic
+const LOG_MAGIC: &[u8; 8] = b"MCAUDITL";
+/// Audit log file format version
+const LOG_VERSION: u32 = 2;
+/// Length of the file header
+const HEADER_LEN: usize = LOG_MAGIC.len() + 4;
+
+/// Append-only storage for audit records
+pub trait AuditStore: Send + Debug {
+    /// Durably appends a record after the existing ones
+    fn append(&mut self, record: &AuditRecord) -> CryptoResult<()>;
+
+    /// Every stored record, oldest first
+    fn records(&self) -> CryptoResult<Vec<AuditRecord>>;
+}
+
+/// Audit records held in memory, lost on restart
+#[derive(Debug, Default)]
+pub struct MemoryAuditStore {
+    records: Vec<AuditRecord>,
+}
+
+impl MemoryAuditStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl AuditStore for MemoryAuditStore {
+    fn append(&mut self, record: &AuditRecord) -> CryptoResult<()> {
+        self.records.push(record.clone());
+        Ok(())
+    }
+
+    fn records(&self) -> CryptoResult<Vec<AuditRecord>> {
+        Ok(self.records.clone())
+    }
+}
+
+/// Audit records appended to a file, synced after every record
+///
+/// The file is only ever opened for appending; each record is written as a
+/// u64 length prefix followed by the encoded record.
+#[derive(Debug)]
+pub struct FileAuditStore {
+    path: PathBuf,
+    file: File,
+}
+
+impl FileAuditStore {
+    /// Opens a log file, creating it with owner-only permissions if needed
+    pub fn open(path: impl AsRef<Path>) -> CryptoResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut options = OpenOptions::new();
+        options.append(true).create(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&path)?;
+
+        if file.metadata()?.len() == 0 {
+            let mut header = Encoder::new();
+            header.put_raw(LOG_MAGIC).put_u32(LOG_VERSION);
+            file.write_all(&header.finish())?;
+            file.sync_all()?;
+        } else {
+            check_header(&fs::read(&path)?)?;
+        }
+
+        debug!(path = %path.display(), "Opened audit log");
+        Ok(Self { path, file })
+    }
+
+    /// Location of the log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AuditStore for FileAuditStore {
+    fn append(&mut self, record: &AuditRecord) -> CryptoResult<()> {
+        let mut frame = Encoder::new();
+        frame.put_bytes(&record.encode());
+        self.file.write_all(&frame.finish())?;
+        self.file.sync_data()?;
+        Ok(())
+    }
+
+    fn records(&self) -> CryptoResult<Vec<AuditRecord>> {
+        let data = fs::read(&self.path)?;
+        check_header(&data)?;
+
+        let mut decoder = Decoder::new(&data[HEADER_LEN..]);
+        let mut records = Vec::new();
+        while !decoder.is_exhausted() {
+            records.push(AuditRecord::decode(decoder.get_bytes()?)?);
+        }
+        Ok(records)
+    }
+}
+
+fn check_header(data: &[u8]) -> CryptoResult<()> {
+    let mut decoder = Decoder::new(data);
+    if decoder.get_raw(LOG_MAGIC.len())? != LOG_MAGIC {
+        return Err(CryptoError::InvalidInput("Not an audit log".into()));
+    }
+    let version = decoder.get_u32()?;
+    if version != LOG_VERSION {
+        return Err(CryptoError::InvalidInput(format!("Unsupported audit log version {}", version)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::audit::{AuditEvent, AuditLog};
+    use crate::crypto::protocol::SecretId;
+    use crate::crypto::utils::temp::TempDir;
+
+    #[test]
+    fn test_file_log_continues_and_detects_tampering() -> CryptoResult<()> {
+        let dir = TempDir::new("audit")?;
+        let path = dir.join("audit.log");
+
+        let log = AuditLog::new("combiner", Box::new(FileAuditStore::open(&path)?))?;
+        log.record(AuditEvent::VerifyFailed { secret: None, share_id: 0 })?;
+        log.record(AuditEvent::Reconstruct { secret: Some(SecretId::random()), share_hashes: vec![[7; 32]; 3] })?;
+        drop(log);
+
+        let log = AuditLog::new("combiner", Box::new(FileAuditStore::open(&path)?))?;
+        let record = log.record(AuditEvent::VerifyFailed { secret: None, share_id: 1 })?;
+        assert_eq!(record.sequence, 2);
+        assert_eq!(log.verify()?.length, 3);
+
+        // Change the share id in the first record: frame length, sequence,
+        // timestamp, actor length, actor, event tag, secret flag
+        let mut data = fs::read(&path)?;
+        let share_id = HEADER_LEN + 8 + 8 + 8 + 8 + "combiner".len() + 4 + 1;
+        data[share_id] ^= 1;
+        fs::write(&path, &data)?;
+
+        assert!(matches!(log.verify(), Err(CryptoError::VerificationFailed(_))));
+        assert!(matches!(
+            AuditLog::new("combiner", Box::new(FileAuditStore::open(&path)?)),
+            Err(CryptoError::VerificationFailed(_))
+        ));
+        Ok(())
+    }
+}
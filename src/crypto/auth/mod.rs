@@ -0,0 +1,308 @@
+//! Three-party challenge–response authentication
+//! Location: src/crypto/auth/mod.rs
+//!
+//! A verifier holding every party's token issues a timestamped challenge;
+//! each party answers with an HMAC of the challenge under its own token, and
+//! the verifier accepts only when all three answers check out and the
+//! challenge is still fresh and has not been used before.
+
+pub mod replay;
+
+pub use replay::{FileNonceCache, MemoryNonceCache, NonceCache, ReplayGuard};
+
+use crate::error::{CryptoError, CryptoResult};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Number of parties that must answer a challenge
+pub const PARTY_COUNT: usize = 3;
+
+/// Domain tag for challenge responses
+const RESPONSE_DOMAIN: &[u8] = b"mcafee/auth/response/v1";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// A party's secret authentication token
+#[derive(Clone, PartialEq, Eq)]
+pub struct PartyToken([u8; 32]);
+
+impl PartyToken {
+    /// Draws a fresh random token
+    pub fn generate() -> Self {
+        Self(rand::random())
+    }
+
+    /// Wraps existing token material
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Raw token material
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for PartyToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PartyToken(..)")
+    }
+}
+
+/// Challenge every party must answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthChallenge {
+    message: Vec<u8>,
+    nonce: [u8; 32],
+    issued_at: DateTime<Utc>,
+}
+
+impl AuthChallenge {
+    /// Creates a challenge for a message with a fresh nonce
+    pub fn new(message: impl AsRef<[u8]>, issued_at: DateTime<Utc>) -> Self {
+        Self {
+            message: message.as_ref().to_vec(),
+            nonce: rand::random(),
+            issued_at,
+        }
+    }
+
+    /// Message being authenticated
+    pub fn message(&self) -> &[u8] {
+        &self.message
+    }
+
+    /// Random nonce making each challenge unique
+    pub fn nonce(&self) -> &[u8; 32] {
+        &self.nonce
+    }
+
+    /// When the challenge was issued
+    pub fn issued_at(&self) -> DateTime<Utc> {
+        self.issued_at
+    }
+}
+
+/// A party's answer to a challenge
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthResponse {
+    pub party: u8,
+    pub tag: [u8; 32],
+}
+
+/// Answers a challenge as `party` using that party's token
+pub fn respond(challenge: &AuthChallenge, party: u8, token: &PartyToken) -> CryptoResult<AuthResponse> {
+    check_party(party)?;
+    Ok(AuthResponse {
+        party,
+        tag: response_mac(challenge, party, token).finalize().into_bytes().into(),
+    })
+}
+
+/// Verifier side of three-party authentication
+#[derive(Debug, Clone)]
+pub struct ThreePartyAuthentication {
+    tokens: [PartyToken; PARTY_COUNT],
+    guard: Arc<ReplayGuard>,
+}
+
+impl ThreePartyAuthentication {
+    /// Creates a verifier for the given party tokens, indexed by party
+    pub fn new(tokens: [PartyToken; PARTY_COUNT]) -> Self {
+        Self {
+            tokens,
+            guard: Arc::new(ReplayGuard::default()),
+        }
+    }
+
+    /// Sets how long a challenge stays valid after issue, with a fresh in-memory nonce cache
+    pub fn with_max_age(self, max_age: Duration) -> Self {
+        self.with_replay_guard(ReplayGuard::new(max_age, Box::new(MemoryNonceCache::new())))
+    }
+
+    /// Uses a configured replay guard, e.g. one backed by a nonce file
+    pub fn with_replay_guard(mut self, guard: ReplayGuard) -> Self {
+        self.guard = Arc::new(guard);
+        self
+    }
+
+    /// How long a challenge stays valid after issue
+    pub fn max_age(&self) -> Duration {
+        self.guard.window()
+    }
+
+    /// Replay guard checking freshness and nonce reuse
+    pub fn replay_guard(&self) -> &ReplayGuard {
+        &self.guard
+    }
+
+    /// Issues a challenge for a message, stamped with the current time
+    pub fn issue(&self, message: impl AsRef<[u8]>) -> AuthChallenge {
+        let challenge = AuthChallenge::new(message, Utc::now());
+        debug!(issued_at = %challenge.issued_at, "Issued authentication challenge");
+        challenge
+    }
+
+    /// Checks all three responses against a challenge at the current time
+    pub fn verify(&self, challenge: &AuthChallenge, responses: &[AuthResponse]) -> CryptoResult<()> {
+        self.verify_at(challenge, responses, Utc::now())
+    }
+
+    /// Checks all three responses against a challenge as of `now`
+    ///
+    /// A challenge is accepted at most once; the nonce is recorded only after
+    /// every response verifies, so forged responses cannot burn it.
+    pub fn verify_at(
+        &self,
+        challenge: &AuthChallenge,
+        responses: &[AuthResponse],
+        now: DateTime<Utc>,
+    ) -> CryptoResult<()> {
+        self.guard.check_fresh(challenge.issued_at, now)?;
+
+        if responses.len() != PARTY_COUNT {
+            return Err(CryptoError::InvalidInput(
+                format!("Expected {} responses, got {}", PARTY_COUNT, responses.len())
+            ));
+        }
+
+        let mut answered = [false; PARTY_COUNT];
+        for response in responses {
+            check_party(response.party)?;
+            let party = response.party as usize;
+            if std::mem::replace(&mut answered[party], true) {
+                return Err(fail(response.party, "duplicate response"));
+            }
+
+            if response_mac(challenge, response.party, &self.tokens[party])
+                .verify_slice(&response.tag)
+                .is_err()
+            {
+                return Err(fail(response.party, "response does not match token"));
+            }
+        }
+
+        self.guard.accept(&challenge.nonce, challenge.issued_at, now)?;
+        info!(issued_at = %challenge.issued_at, "Authentication challenge verified");
+        Ok(())
+    }
+}
+
+fn check_party(party: u8) -> CryptoResult<()> {
+    if party as usize >= PARTY_COUNT {
+        return Err(CryptoError::InvalidInput(format!("No party {}", party)));
+    }
+    Ok(())
+}
+
+fn fail(party: u8, reason: &str) -> CryptoError {
+    warn!(party, reason, "Authentication failed");
+    CryptoError::AuthenticationFailed { party, reason: reason.into() }
+}
+
+fn response_mac(challenge: &AuthChallenge, party: u8, token: &PartyToken) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(&token.0).expect("HMAC accepts any key length");
+    mac.update(RESPONSE_DOMAIN);
+    mac.update(&[party]);
+    mac.update(&challenge.nonce);
+    mac.update(&challenge.issued_at.timestamp_micros().to_le_bytes());
+    mac.update(&(challenge.message.len() as u64).to_le_bytes());
+    mac.update(&challenge.message);
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> ([PartyToken; PARTY_COUNT], ThreePartyAuthentication) {
+        let tokens = [PartyToken::generate(), PartyToken::generate(), PartyToken::generate()];
+        let auth = ThreePartyAuthentication::new(tokens.clone());
+        (tokens, auth)
+    }
+
+    fn answer_all(challenge: &AuthChallenge, tokens: &[PartyToken]) -> Vec<AuthResponse> {
+        tokens.iter()
+            .enumerate()
+            .map(|(party, token)| respond(challenge, party as u8, token).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_issue_respond_verify() -> CryptoResult<()> {
+        let (tokens, auth) = setup();
+        let challenge = auth.issue("Request for access to secure resource");
+        let responses = answer_all(&challenge, &tokens);
+        auth.verify(&challenge, &responses)
+    }
+
+    #[test]
+    fn test_wrong_token_rejected() {
+        let (mut tokens, auth) = setup();
+        let challenge = auth.issue("message");
+        tokens[1] = PartyToken::generate();
+        let responses = answer_all(&challenge, &tokens);
+
+        assert!(matches!(
+            auth.verify(&challenge, &responses),
+            Err(CryptoError::AuthenticationFailed { party: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_response_bound_to_challenge() {
+        let (tokens, auth) = setup();
+        let challenge = auth.issue("message");
+        let other = auth.issue("message");
+        let responses = answer_all(&other, &tokens);
+        assert!(auth.verify(&challenge, &responses).is_err());
+
+        let mut duplicated = answer_all(&challenge, &tokens);
+        duplicated[2] = duplicated[0];
+        assert!(matches!(
+            auth.verify(&challenge, &duplicated),
+            Err(CryptoError::AuthenticationFailed { party: 0, .. })
+        ));
+        assert!(auth.verify(&challenge, &duplicated[..2]).is_err());
+    }
+
+    #[test]
+    fn test_timestamps_checked() {
+        let (tokens, auth) = setup();
+        let auth = auth.with_max_age(Duration::from_secs(30));
+        let challenge = auth.issue("message");
+        let responses = answer_all(&challenge, &tokens);
+        let issued = challenge.issued_at();
+
+        assert!(matches!(
+            auth.verify_at(&challenge, &responses, issued + chrono::Duration::seconds(31)),
+            Err(CryptoError::TimingViolation { .. })
+        ));
+        assert!(matches!(
+            auth.verify_at(&challenge, &responses, issued - chrono::Duration::seconds(1)),
+            Err(CryptoError::TimingViolation { .. })
+        ));
+        assert!(auth.verify_at(&challenge, &responses, issued + chrono::Duration::seconds(30)).is_ok());
+    }
+
+    #[test]
+    fn test_replayed_challenge_rejected() -> CryptoResult<()> {
+        let (tokens, auth) = setup();
+        let challenge = auth.issue("message");
+        let responses = answer_all(&challenge, &tokens);
+
+        // A forged attempt does not use up the challenge
+        let mut forged = responses.clone();
+        forged[0].tag[0] ^= 1;
+        assert!(auth.verify(&challenge, &forged).is_err());
+
+        auth.verify(&challenge, &responses)?;
+        assert!(matches!(auth.verify(&challenge, &responses), Err(CryptoError::ReplayDetected(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,290 @@
+//! Replay protection for authentication challenges
+//! Location: src/crypto/auth/replay.rs
+//!
+//! A challenge is accepted once, and only while it is inside the acceptance
+//! window. Nonces are remembered until their challenge would be stale anyway,
+//! so the cache stays bounded by the issue rate times the window.
+
+use crate::crypto::utils::{self, codec::{self, Decoder, Encoder}};
+use crate::error::{CryptoError, CryptoResult};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Default time a challenge stays acceptable after issue
+pub const DEFAULT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Nonce cache file magic
+const CACHE_MAGIC: &[u8; 8] = b"MCNONCES";
+/// Nonce cache file format version
+const CACHE_VERSION: u32 = 1;
+
+/// Store of nonces that have already been accepted
+pub trait NonceCache: Send + Debug {
+    /// Records a nonce until `expires_at`; returns false if it was already present
+    fn insert(&mut self, nonce: [u8; 32], expires_at: DateTime<Utc>) -> CryptoResult<bool>;
+
+    /// Forgets nonces that expired before `now`
+    fn prune(&mut self, now: DateTime<Utc>) -> CryptoResult<()>;
+
+    /// Number of remembered nonces
+    fn len(&self) -> usize;
+
+    /// Returns whether no nonces are remembered
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Nonce cache held in memory, lost on restart
+#[derive(Debug, Default)]
+pub struct MemoryNonceCache {
+    entries: HashMap<[u8; 32], DateTime<Utc>>,
+}
+
+impl MemoryNonceCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NonceCache for MemoryNonceCache {
+    fn insert(&mut self, nonce: [u8; 32], expires_at: DateTime<Utc>) -> CryptoResult<bool> {
+        if self.entries.contains_key(&nonce) {
+            return Ok(false);
+        }
+        self.entries.insert(nonce, expires_at);
+        Ok(true)
+    }
+
+    fn prune(&mut self, now: DateTime<Utc>) -> CryptoResult<()> {
+        self.entries.retain(|_, expires_at| *expires_at >= now);
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// Nonce cache persisted to a file so replays are caught across restarts
+///
+/// Every change rewrites the file atomically.
+#[derive(Debug)]
+pub struct FileNonceCache {
+    path: PathBuf,
+    memory: MemoryNonceCache,
+}
+
+impl FileNonceCache {
+    /// Opens a cache file, starting empty if it does not exist yet
+    pub fn open(path: impl AsRef<Path>) -> CryptoResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let memory = match std::fs::read(&path) {
+            Ok(sealed) => decode_entries(&sealed)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => MemoryNonceCache::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        debug!(path = %path.display(), nonces = memory.len(), "Opened nonce cache");
+        Ok(Self { path, memory })
+    }
+
+    /// Location of the cache file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn persist(&self) -> CryptoResult<()> {
+        let mut encoder = Encoder::new();
+        encoder
+            .put_raw(CACHE_MAGIC)
+            .put_u32(CACHE_VERSION)
+            .put_u64(self.memory.entries.len() as u64);
+        for (nonce, expires_at) in &self.memory.entries {
+            encoder.put_raw(nonce).put_u64(expires_at.timestamp_micros() as u64);
+        }
+
+        utils::write_atomic(&self.path, &codec::seal(encoder.finish()))?;
+        Ok(())
+    }
+}
+
+impl NonceCache for FileNonceCache {
+    fn insert(&mut self, nonce: [u8; 32], expires_at: DateTime<Utc>) -> CryptoResult<bool> {
+        let inserted = self.memory.insert(nonce, expires_at)?;
+        if inserted {
+            self.persist()?;
+        }
+        Ok(inserted)
+    }
+
+    fn prune(&mut self, now: DateTime<Utc>) -> CryptoResult<()> {
+        let before = self.memory.len();
+        self.memory.prune(now)?;
+        if self.memory.len() != before {
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        self.memory.len()
+    }
+}
+
+fn decode_entries(sealed: &[u8]) -> CryptoResult<MemoryNonceCache> {
+    let mut decoder = Decoder::new(codec::unseal(sealed)?);
+    if decoder.get_raw(CACHE_MAGIC.len())? != CACHE_MAGIC {
+        return Err(CryptoError::InvalidInput("Not a nonce cache".into()));
+    }
+    let version = decoder.get_u32()?;
+    if version != CACHE_VERSION {
+        return Err(CryptoError::InvalidInput(format!("Unsupported nonce cache version {}", version)));
+    }
+
+    let mut cache = MemoryNonceCache::new();
+    for _ in 0..decoder.get_u64()? {
+        let nonce = decoder.get_array32()?;
+        let expires_at = DateTime::from_timestamp_micros(decoder.get_u64()? as i64)
+            .ok_or_else(|| CryptoError::InvalidInput("Nonce expiry out of range".into()))?;
+        cache.entries.insert(nonce, expires_at);
+    }
+    decoder.finish()?;
+    Ok(cache)
+}
+
+/// Acceptance window, clock-skew tolerance and nonce cache for challenges
+#[derive(Debug)]
+pub struct ReplayGuard {
+    window: Duration,
+    skew: Duration,
+    cache: Mutex<Box<dyn NonceCache>>,
+}
+
+impl Default for ReplayGuard {
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW, Box::new(MemoryNonceCache::new()))
+    }
+}
+
+impl ReplayGuard {
+    /// Creates a guard accepting challenges up to `window` old
+    pub fn new(window: Duration, cache: Box<dyn NonceCache>) -> Self {
+        Self {
+            window,
+            skew: Duration::ZERO,
+            cache: Mutex::new(cache),
+        }
+    }
+
+    /// Tolerates issuer clocks up to `skew` ahead of or behind ours
+    pub fn with_skew(mut self, skew: Duration) -> Self {
+        self.skew = skew;
+        self
+    }
+
+    /// Time a challenge stays acceptable after issue
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Tolerated clock difference with the issuer
+    pub fn skew(&self) -> Duration {
+        self.skew
+    }
+
+    /// Rejects challenges issued too far in the future or the past
+    pub fn check_fresh(&self, issued_at: DateTime<Utc>, now: DateTime<Utc>) -> CryptoResult<()> {
+        match (now - issued_at).to_std() {
+            Ok(age) if age <= self.window + self.skew => Ok(()),
+            Ok(age) => Err(CryptoError::TimingViolation {
+                expected: self.window + self.skew,
+                actual: age,
+            }),
+            Err(_) => {
+                let ahead = (issued_at - now).to_std().unwrap_or_default();
+                if ahead <= self.skew {
+                    Ok(())
+                } else {
+                    Err(CryptoError::TimingViolation { expected: self.skew, actual: ahead })
+                }
+            }
+        }
+    }
+
+    /// Checks freshness and records the nonce, rejecting any second use
+    pub fn accept(&self, nonce: &[u8; 32], issued_at: DateTime<Utc>, now: DateTime<Utc>) -> CryptoResult<()> {
+        self.check_fresh(issued_at, now)?;
+
+        let expires_at = issued_at + chrono::Duration::from_std(self.window + self.skew)
+            .map_err(|_| CryptoError::InvalidInput("Acceptance window too large".into()))?;
+        let mut cache = self.cache.lock()
+            .map_err(|_| CryptoError::InvalidState("Nonce cache lock poisoned".into()))?;
+        cache.prune(now)?;
+
+        if !cache.insert(*nonce, expires_at)? {
+            warn!(%issued_at, "Rejected replayed challenge");
+            return Err(CryptoError::ReplayDetected(format!("challenge issued at {} was already used", issued_at)));
+        }
+        Ok(())
+    }
+
+    /// Number of nonces currently remembered
+    pub fn remembered(&self) -> usize {
+        self.cache.lock().map(|cache| cache.len()).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::utils::temp::TempDir;
+
+    #[test]
+    fn test_window_skew_and_duplicates() -> CryptoResult<()> {
+        let guard = ReplayGuard::new(Duration::from_secs(30), Box::new(MemoryNonceCache::new()))
+            .with_skew(Duration::from_secs(2));
+        let issued = Utc::now();
+        let seconds = chrono::Duration::seconds;
+
+        assert!(guard.check_fresh(issued, issued + seconds(32)).is_ok());
+        assert!(matches!(guard.check_fresh(issued, issued + seconds(33)), Err(CryptoError::TimingViolation { .. })));
+        assert!(guard.check_fresh(issued, issued - seconds(2)).is_ok());
+        assert!(matches!(guard.check_fresh(issued, issued - seconds(3)), Err(CryptoError::TimingViolation { .. })));
+
+        guard.accept(&[1; 32], issued, issued)?;
+        assert!(matches!(guard.accept(&[1; 32], issued, issued + seconds(1)), Err(CryptoError::ReplayDetected(_))));
+        guard.accept(&[2; 32], issued, issued)?;
+        assert_eq!(guard.remembered(), 2);
+
+        // Expired nonces are dropped once their challenges are stale anyway
+        guard.accept(&[3; 32], issued + seconds(40), issued + seconds(40))?;
+        assert_eq!(guard.remembered(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_cache_survives_restart() -> CryptoResult<()> {
+        let dir = TempDir::new("nonces")?;
+        let path = dir.join("nonces.bin");
+        let issued = Utc::now();
+
+        let guard = ReplayGuard::new(DEFAULT_WINDOW, Box::new(FileNonceCache::open(&path)?));
+        guard.accept(&[7; 32], issued, issued)?;
+        drop(guard);
+
+        let reopened = FileNonceCache::open(&path)?;
+        assert_eq!(reopened.len(), 1);
+        let guard = ReplayGuard::new(DEFAULT_WINDOW, Box::new(reopened));
+        assert!(matches!(guard.accept(&[7; 32], issued, issued), Err(CryptoError::ReplayDetected(_))));
+
+        std::fs::write(&path, b"garbage")?;
+        assert!(FileNonceCache::open(&path).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,598 @@
+//! Three-party randomness beacon built from commit–reveal and a VDF
+//! Location: src/crypto/beacon/mod.rs
+//!
+//! Each round, every party commits to a random contribution and reveals it
+//! once all three commitments are in. The XOR of the contributions, chained to
+//! the previous round's output, seeds a hash-chain VDF whose output is the
+//! round's randomness. Reveals must arrive within `reveal_window`, which must
+//! be shorter than the fastest possible VDF evaluation, so the last party to
+//! reveal cannot learn the output in time to decide whether withholding would
+//! bias it. A round whose window closes with reveals missing is aborted and
+//! the withholding parties are reported; the round number is then retried
+//! with fresh commitments.
+
+use crate::crypto::vdf::calibration::{Backend, CalibrationProfile, DEFAULT_ATTACKER_SPEEDUP};
+use crate::crypto::vdf::hashchain::{HashChainProof, HashChainVDF, HashChainVerifier};
+use crate::crypto::vdf::temporal::TemporalConfig;
+use crate::crypto::vdf::Verdict;
+use crate::error::{CryptoError, CryptoResult};
+use sha2::{Sha256, Digest};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Number of parties contributing to each round
+pub const PARTY_COUNT: usize = 3;
+
+/// Sequential SHA-256 rate assumed for the fastest evaluator without calibration
+const ASSUMED_MAX_HASH_RATE: f64 = 1e8;
+/// How many reveal windows the fastest VDF evaluation must span
+const DELAY_MARGIN: u32 = 2;
+/// Hash-chain length of the default configuration
+const DEFAULT_ITERATIONS: usize = 1 << 16;
+
+/// Domain tag for contribution commitments
+const COMMIT_DOMAIN: &[u8] = b"mcafee/beacon/commit/v1";
+/// Domain tag for the VDF seed
+const SEED_DOMAIN: &[u8] = b"mcafee/beacon/seed/v1";
+/// Domain tag for the round output
+const OUTPUT_DOMAIN: &[u8] = b"mcafee/beacon/output/v1";
+
+/// A party's secret contribution to a round
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contribution {
+    /// Contributing party (0, 1 or 2)
+    party: u8,
+    /// Random value mixed into the round
+    value: [u8; 32],
+    /// Blinding salt for the commitment
+    salt: [u8; 32],
+}
+
+impl Contribution {
+    /// Draws a fresh random contribution for a party
+    pub fn random(party: u8) -> Self {
+        Self::new(party, rand::random(), rand::random())
+    }
+
+    /// Creates a contribution from explicit value and salt
+    pub fn new(party: u8, value: [u8; 32], salt: [u8; 32]) -> Self {
+        Self { party, value, salt }
+    }
+
+    /// Contributing party
+    pub fn party(&self) -> u8 {
+        self.party
+    }
+
+    /// Random value mixed into the round
+    pub fn value(&self) -> &[u8; 32] {
+        &self.value
+    }
+
+    /// Commitment to publish for a round chained to `previous_output`
+    pub fn commitment(&self, round: u64, previous_output: &[u8; 32]) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(COMMIT_DOMAIN)
+            .chain_update(round.to_le_bytes())
+            .chain_update(previous_output)
+            .chain_update([self.party])
+            .chain_update(self.salt)
+            .chain_update(self.value)
+            .finalize()
+            .into()
+    }
+}
+
+/// Configuration for the beacon
+#[derive(Debug, Clone)]
+pub struct BeaconConfig {
+    /// Hash-chain VDF settings; `iterations` sets the delay
+    pub vdf: TemporalConfig,
+    /// Time allowed for reveals once all commitments are in
+    pub reveal_window: Duration,
+    /// Least time in which anyone can evaluate the VDF, such as the
+    /// `attacker_delay` of a calibrated difficulty
+    pub min_vdf_delay: Duration,
+}
+
+impl BeaconConfig {
+    /// Creates a configuration, checking the reveal window against the delay
+    pub fn new(vdf: TemporalConfig, reveal_window: Duration, min_vdf_delay: Duration) -> CryptoResult<Self> {
+        let config = Self { vdf, reveal_window, min_vdf_delay };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Sizes the VDF from a hash-chain calibration profile
+    ///
+    /// The iteration count is chosen so an attacker `DEFAULT_ATTACKER_SPEEDUP`
+    /// times faster than the fastest sample still needs twice the reveal
+    /// window.
+    pub fn calibrated(profile: &CalibrationProfile, reveal_window: Duration) -> CryptoResult<Self> {
+        if profile.backend() != Backend::HashChain {
+            return Err(CryptoError::InvalidInput("The beacon needs a hash-chain calibration profile".into()));
+        }
+
+        let fastest = profile.max_rate() * DEFAULT_ATTACKER_SPEEDUP / profile.mean_rate();
+        let target = (reveal_window * DELAY_MARGIN).mul_f64(fastest);
+        let difficulty = profile.difficulty_for(target, DEFAULT_ATTACKER_SPEEDUP)?;
+        let iterations = usize::try_from(difficulty.iterations)
+            .map_err(|_| CryptoError::InvalidInput("Iteration count does not fit this platform".into()))?;
+
+        let vdf = TemporalConfig { iterations, ..Default::default() };
+        Self::new(vdf, reveal_window, difficulty.attacker_delay)
+    }
+
+    /// Checks that reveals close before anyone can evaluate the VDF
+    pub fn validate(&self) -> CryptoResult<()> {
+        self.vdf.validate()?;
+        if self.reveal_window.is_zero() {
+            return Err(CryptoError::InvalidInput("Reveal window must be positive".into()));
+        }
+        if self.reveal_window >= self.min_vdf_delay {
+            return Err(CryptoError::InvalidInput(format!(
+                "Reveal window {:?} must be shorter than the VDF delay {:?}",
+                self.reveal_window, self.min_vdf_delay
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for BeaconConfig {
+    /// A short chain for tests and demos
+    ///
+    /// `DEFAULT_ITERATIONS` hashes take under a millisecond at
+    /// `ASSUMED_MAX_HASH_RATE`, so the reveal window is half that and only
+    /// parties in the same process can meet it. For production, calibrate a
+    /// hash-chain profile on the evaluating hardware and size the beacon with
+    /// `BeaconConfig::calibrated` for the reveal window the network needs.
+    fn default() -> Self {
+        let min_vdf_delay = Duration::from_secs_f64(DEFAULT_ITERATIONS as f64 / ASSUMED_MAX_HASH_RATE);
+        Self {
+            vdf: TemporalConfig {
+                iterations: DEFAULT_ITERATIONS,
+                ..Default::default()
+            },
+            reveal_window: min_vdf_delay / DELAY_MARGIN,
+            min_vdf_delay,
+        }
+    }
+}
+
+/// One finished round, with everything needed to check it
+#[derive(Debug, Clone)]
+pub struct BeaconOutput {
+    /// Round number, starting at 0
+    pub round: u64,
+    /// Output of the previous round, or the genesis value
+    pub previous_output: [u8; 32],
+    /// Published commitments, indexed by party
+    pub commitments: [[u8; 32]; PARTY_COUNT],
+    /// Revealed contributions, indexed by party
+    pub contributions: [Contribution; PARTY_COUNT],
+    /// VDF input derived from the contributions
+    pub seed: [u8; 32],
+    /// Proof of the VDF evaluation
+    pub vdf_proof: HashChainProof,
+    /// Randomness produced by the round
+    pub output: [u8; 32],
+}
+
+/// A round in progress, collecting commitments and then reveals
+#[derive(Debug)]
+pub struct BeaconRound {
+    round: u64,
+    previous_output: [u8; 32],
+    commitments: [Option<[u8; 32]>; PARTY_COUNT],
+    reveals: [Option<Contribution>; PARTY_COUNT],
+    /// When the last commitment arrived and the reveal window opened
+    reveal_opened: Option<Instant>,
+}
+
+impl BeaconRound {
+    /// Round number
+    pub fn round(&self) -> u64 {
+        self.round
+    }
+
+    /// Output of the previous round this one is chained to
+    pub fn previous_output(&self) -> &[u8; 32] {
+        &self.previous_output
+    }
+
+    /// Records a party's commitment
+    ///
+    /// Commitments cannot be changed once made, and the reveal window opens
+    /// on the configured clock when the third one arrives.
+    pub fn commit(&mut self, party: u8, commitment: [u8; 32], config: &BeaconConfig) -> CryptoResult<()> {
+        let slot = self.commitments
+            .get_mut(party as usize)
+            .ok_or_else(|| CryptoError::InvalidInput(format!("No party {}", party)))?;
+        if slot.is_some() {
+            return Err(CryptoError::InvalidState(format!("Party {} already committed", party)));
+        }
+        *slot = Some(commitment);
+
+        if self.commitments.iter().all(Option::is_some) {
+            self.reveal_opened = Some(config.vdf.clock.now());
+            debug!(round = self.round, "All commitments received, reveal window open");
+        }
+        Ok(())
+    }
+
+    /// Records a party's reveal after checking it against its commitment
+    pub fn reveal(&mut self, contribution: Contribution, config: &BeaconConfig) -> CryptoResult<()> {
+        let opened = self.reveal_opened.ok_or_else(||
+            CryptoError::InvalidState("Reveals are accepted only after all parties commit".into())
+        )?;
+
+        let elapsed = config.vdf.clock.elapsed_since(opened);
+        if elapsed > config.reveal_window {
+            return Err(CryptoError::TimingViolation {
+                expected: config.reveal_window,
+                actual: elapsed,
+            });
+        }
+
+        let party = contribution.party as usize;
+        let commitment = self.commitments
+            .get(party)
+            .copied()
+            .flatten()
+            .ok_or_else(|| CryptoError::InvalidInput(format!("No party {}", party)))?;
+        if contribution.commitment(self.round, &self.previous_output) != commitment {
+            warn!(round = self.round, party, "Reveal does not match commitment");
+            return Err(CryptoError::VerificationFailed(
+                format!("Reveal of party {} does not match its commitment", party)
+            ));
+        }
+
+        self.reveals[party] = Some(contribution);
+        Ok(())
+    }
+
+    /// Returns whether every party has revealed
+    pub fn is_ready(&self) -> bool {
+        self.reveals.iter().all(Option::is_some)
+    }
+
+    /// Parties that failed to reveal before the window closed
+    ///
+    /// Errors while the window is still open. Once this succeeds the round
+    /// cannot finish and should be dropped; the beacon's next round reuses its
+    /// number with fresh commitments. A withholder cannot tell whether the
+    /// retry helps it, since the window closed before the VDF could be
+    /// evaluated.
+    pub fn withholders(&self, config: &BeaconConfig) -> CryptoResult<Vec<u8>> {
+        let opened = self.reveal_opened.ok_or_else(||
+            CryptoError::InvalidState("Round is still collecting commitments".into())
+        )?;
+        if config.vdf.clock.elapsed_since(opened) <= config.reveal_window {
+            return Err(CryptoError::InvalidState("Reveal window is still open".into()));
+        }
+
+        let withheld: Vec<u8> = (0..PARTY_COUNT as u8)
+            .filter(|&party| self.reveals[party as usize].is_none())
+            .collect();
+        if withheld.is_empty() {
+            return Err(CryptoError::InvalidState("Every party revealed; finish the round instead".into()));
+        }
+
+        warn!(round = self.round, ?withheld, "Reveal window closed with reveals missing");
+        Ok(withheld)
+    }
+}
+
+/// Chain of beacon rounds
+#[derive(Debug)]
+pub struct Beacon {
+    pub config: BeaconConfig,
+    genesis: [u8; 32],
+    history: Vec<BeaconOutput>,
+}
+
+impl Beacon {
+    /// Creates a beacon whose first round chains to `genesis`
+    pub fn new(config: BeaconConfig, genesis: [u8; 32]) -> CryptoResult<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            genesis,
+            history: Vec::new(),
+        })
+    }
+
+    /// Value the first round is chained to
+    pub fn genesis(&self) -> &[u8; 32] {
+        &self.genesis
+    }
+
+    /// Output of the most recent round, or the genesis value
+    pub fn latest_output(&self) -> [u8; 32] {
+        self.history.last().map_or(self.genesis, |round| round.output)
+    }
+
+    /// Every finished round, in order
+    pub fn transcript(&self) -> &[BeaconOutput] {
+        &self.history
+    }
+
+    /// Opens the next round
+    pub fn start_round(&self) -> BeaconRound {
+        BeaconRound {
+            round: self.history.len() as u64,
+            previous_output: self.latest_output(),
+            commitments: [None; PARTY_COUNT],
+            reveals: Default::default(),
+            reveal_opened: None,
+        }
+    }
+
+    /// Runs the VDF over a fully revealed round and appends its output
+    pub fn finish_round(&mut self, round: BeaconRound) -> CryptoResult<&BeaconOutput> {
+        if round.round != self.history.len() as u64 || round.previous_output != self.latest_output() {
+            return Err(CryptoError::InvalidState("Round does not extend the beacon chain".into()));
+        }
+
+        let (commitments, contributions) = match (
+            round.commitments.iter().copied().collect::<Option<Vec<_>>>(),
+            round.reveals.into_iter().collect::<Option<Vec<_>>>(),
+        ) {
+            (Some(commitments), Some(contributions)) => (commitments, contributions),
+            _ => return Err(CryptoError::InvalidState("Round is missing reveals".into())),
+        };
+
+        let seed = derive_seed(round.round, &round.previous_output, &contributions);
+        let mut vdf = HashChainVDF::new(self.config.vdf.clone());
+        vdf.initialize(&seed)?;
+        vdf.run()?;
+        let vdf_proof = vdf.generate_proof()?;
+        let output = derive_output(round.round, &vdf.get_output()?);
+
+        info!(round = round.round, elapsed = ?vdf_proof.computation_time(), "Beacon round finished");
+
+        self.history.push(BeaconOutput {
+            round: round.round,
+            previous_output: round.previous_output,
+            commitments: commitments.try_into().expect("three commitments"),
+            contributions: contributions.try_into().expect("three contributions"),
+            seed,
+            vdf_proof,
+            output,
+        });
+        Ok(self.history.last().expect("round just pushed"))
+    }
+}
+
+/// Checks a beacon transcript from the genesis value onwards
+///
+/// Every round must extend the previous one, every reveal must open its
+/// commitment, and every VDF proof must verify for the derived seed.
+pub fn verify_transcript(genesis: &[u8; 32], transcript: &[BeaconOutput], config: &BeaconConfig) -> Verdict {
+    let verifier = HashChainVerifier::for_config(&config.vdf);
+    let mut previous = *genesis;
+
+    for (index, round) in transcript.iter().enumerate() {
+        if round.round != index as u64 || round.previous_output != previous {
+            return reject(format!("round {} does not extend the chain", index));
+        }
+
+        for (party, (contribution, commitment)) in round.contributions.iter().zip(&round.commitments).enumerate() {
+            if contribution.party as usize != party
+                || contribution.commitment(round.round, &round.previous_output) != *commitment
+            {
+                return reject(format!("round {}: reveal of party {} does not match", index, party));
+            }
+        }
+
+        if derive_seed(round.round, &round.previous_output, &round.contributions) != round.seed {
+            return reject(format!("round {}: seed does not match contributions", index));
+        }
+
+        let vdf_output = round.vdf_proof.output();
+        let verdict = verifier.verify(&HashChainVDF::hash_input(&round.seed), &vdf_output, &round.vdf_proof);
+        if let Verdict::Rejected(reason) = verdict {
+            return reject(format!("round {}: {}", index, reason));
+        }
+
+        if derive_output(round.round, &vdf_output) != round.output {
+            return reject(format!("round {}: output does not match VDF output", index));
+        }
+
+        previous = round.output;
+    }
+
+    Verdict::Accepted
+}
+
+fn derive_seed(round: u64, previous_output: &[u8; 32], contributions: &[Contribution]) -> [u8; 32] {
+    let mut combined = [0u8; 32];
+    for contribution in contributions {
+        for (c, v) in combined.iter_mut().zip(&contribution.value) {
+            *c ^= v;
+        }
+    }
+
+    Sha256::new()
+        .chain_update(SEED_DOMAIN)
+        .chain_update(round.to_le_bytes())
+        .chain_update(previous_output)
+        .chain_update(combined)
+        .finalize()
+        .into()
+}
+
+fn derive_output(round: u64, vdf_output: &[u8; 32]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(OUTPUT_DOMAIN)
+        .chain_update(round.to_le_bytes())
+        .chain_update(vdf_output)
+        .finalize()
+        .into()
+}
+
+fn reject(reason: impl Into<String>) -> Verdict {
+    let reason = reason.into();
+    warn!(%reason, "Beacon transcript rejected");
+    Verdict::Rejected(reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::utils::ManualClock;
+    use std::sync::Arc;
+
+    fn test_config(clock: &ManualClock) -> BeaconConfig {
+        BeaconConfig {
+            vdf: TemporalConfig {
+                iterations: 2_000,
+                clock: Arc::new(clock.clone()),
+                ..Default::default()
+            },
+            reveal_window: Duration::from_secs(10),
+            min_vdf_delay: Duration::from_secs(20),
+        }
+    }
+
+    fn run_round(beacon: &mut Beacon) -> CryptoResult<[u8; 32]> {
+        let mut round = beacon.start_round();
+        let contributions: Vec<_> = (0..PARTY_COUNT as u8).map(Contribution::random).collect();
+        for contribution in &contributions {
+            let commitment = contribution.commitment(round.round(), round.previous_output());
+            round.commit(contribution.party(), commitment, &beacon.config)?;
+        }
+        for contribution in contributions {
+            round.reveal(contribution, &beacon.config)?;
+        }
+        assert!(round.is_ready());
+        Ok(beacon.finish_round(round)?.output)
+    }
+
+    #[test]
+    fn test_chained_rounds_verify() -> CryptoResult<()> {
+        let clock = ManualClock::new();
+        let mut beacon = Beacon::new(test_config(&clock), [0u8; 32])?;
+
+        let first = run_round(&mut beacon)?;
+        let second = run_round(&mut beacon)?;
+        assert_ne!(first, second);
+        assert_eq!(beacon.latest_output(), second);
+        assert_eq!(beacon.transcript()[1].previous_output, first);
+
+        let verdict = verify_transcript(beacon.genesis(), beacon.transcript(), &beacon.config);
+        assert_eq!(verdict, Verdict::Accepted);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tampered_transcript_rejected() -> CryptoResult<()> {
+        let clock = ManualClock::new();
+        let mut beacon = Beacon::new(test_config(&clock), [1u8; 32])?;
+        run_round(&mut beacon)?;
+        run_round(&mut beacon)?;
+
+        let mut swapped = beacon.transcript().to_vec();
+        swapped.swap(0, 1);
+        assert!(!verify_transcript(beacon.genesis(), &swapped, &beacon.config).is_accepted());
+
+        let mut biased = beacon.transcript().to_vec();
+        biased[1].contributions[2].value[0] ^= 1;
+        assert!(!verify_transcript(beacon.genesis(), &biased, &beacon.config).is_accepted());
+
+        let mut forged = beacon.transcript().to_vec();
+        forged[0].output[0] ^= 1;
+        assert!(!verify_transcript(beacon.genesis(), &forged, &beacon.config).is_accepted());
+
+        assert!(!verify_transcript(&[9u8; 32], beacon.transcript(), &beacon.config).is_accepted());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reveal_rules() -> CryptoResult<()> {
+        let clock = ManualClock::new();
+        let beacon = Beacon::new(test_config(&clock), [0u8; 32])?;
+        let mut round = beacon.start_round();
+        let contributions: Vec<_> = (0..PARTY_COUNT as u8).map(Contribution::random).collect();
+
+        // No reveals before everyone has committed
+        let first = contributions[0].commitment(0, round.previous_output());
+        round.commit(0, first, &beacon.config)?;
+        assert!(matches!(
+            round.reveal(contributions[0].clone(), &beacon.config),
+            Err(CryptoError::InvalidState(_))
+        ));
+        assert!(round.commit(0, first, &beacon.config).is_err());
+        assert!(round.commit(3, first, &beacon.config).is_err());
+
+        for contribution in &contributions[1..] {
+            let commitment = contribution.commitment(0, round.previous_output());
+            round.commit(contribution.party(), commitment, &beacon.config)?;
+        }
+
+        // A changed value does not open the commitment
+        let mut changed = contributions[1].clone();
+        changed.value[0] ^= 1;
+        assert!(matches!(
+            round.reveal(changed, &beacon.config),
+            Err(CryptoError::VerificationFailed(_))
+        ));
+
+        // Late reveals are refused
+        round.reveal(contributions[0].clone(), &beacon.config)?;
+        clock.advance(Duration::from_secs(11));
+        assert!(matches!(
+            round.reveal(contributions[1].clone(), &beacon.config),
+            Err(CryptoError::TimingViolation { .. })
+        ));
+        assert!(!round.is_ready());
+        Ok(())
+    }
+
+    #[test]
+    fn test_withholding_round_aborts() -> CryptoResult<()> {
+        let clock = ManualClock::new();
+        let beacon = Beacon::new(test_config(&clock), [0u8; 32])?;
+        let contributions: Vec<_> = (0..PARTY_COUNT as u8).map(Contribution::random).collect();
+
+        let mut round = beacon.start_round();
+        assert!(matches!(round.withholders(&beacon.config), Err(CryptoError::InvalidState(_))));
+        for contribution in &contributions {
+            let commitment = contribution.commitment(0, round.previous_output());
+            round.commit(contribution.party(), commitment, &beacon.config)?;
+        }
+        round.reveal(contributions[0].clone(), &beacon.config)?;
+        round.reveal(contributions[1].clone(), &beacon.config)?;
+
+        // The last revealer is only reported once the window has closed
+        clock.advance(Duration::from_secs(5));
+        assert!(matches!(round.withholders(&beacon.config), Err(CryptoError::InvalidState(_))));
+        clock.advance(Duration::from_secs(6));
+        assert_eq!(round.withholders(&beacon.config)?, vec![2]);
+
+        // The abandoned round never reached the beacon, so its number is retried
+        assert_eq!(beacon.start_round().round(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_validation() -> CryptoResult<()> {
+        let default = BeaconConfig::default();
+        default.validate()?;
+        assert_eq!(default.vdf.iterations, DEFAULT_ITERATIONS);
+
+        let clock = ManualClock::new();
+        let mut config = test_config(&clock);
+        config.reveal_window = config.min_vdf_delay;
+        assert!(matches!(Beacon::new(config, [0u8; 32]), Err(CryptoError::InvalidInput(_))));
+        assert!(BeaconConfig::new(TemporalConfig::default(), Duration::ZERO, Duration::from_secs(1)).is_err());
+
+        let profile = CalibrationProfile::from_rates(Backend::HashChain, vec![1e6, 2e6], 1_000)?;
+        let config = BeaconConfig::calibrated(&profile, Duration::from_secs(3))?;
+        assert!(config.min_vdf_delay >= Duration::from_secs(6) - Duration::from_millis(1));
+        let xor = CalibrationProfile::from_rates(Backend::Xor { state_size: 64 }, vec![1e6], 1_000)?;
+        assert!(BeaconConfig::calibrated(&xor, Duration::from_secs(3)).is_err());
+        Ok(())
+    }
+}
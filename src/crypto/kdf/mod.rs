@@ -0,0 +1,216 @@
+//! Labeled key derivation with HKDF-SHA256
+//! Location: src/crypto/kdf/mod.rs
+//!
+//! Secrets that come out of reconstruction or key agreement are never used
+//! directly. They are extracted into a pseudorandom key once, and every
+//! subkey is expanded under a `Label` naming its purpose and context, so keys
+//! for different uses cannot collide.
+
+use crate::crypto::kex::SessionKey;
+use crate::error::{CryptoError, CryptoResult};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use std::fmt;
+
+/// Prefix of every expansion info string
+const INFO_DOMAIN: &[u8] = b"mcafee/kdf/v1";
+/// Extraction salt for secrets reconstructed from shares
+const SHARING_SALT: &[u8] = b"mcafee/kdf/sharing/v1";
+/// Extraction salt for key agreement outputs
+const KEX_SALT: &[u8] = b"mcafee/kdf/kex/v1";
+
+/// Shortest input keying material accepted
+pub const MIN_SECRET_LEN: usize = 16;
+/// Longest label context accepted
+pub const MAX_CONTEXT_LEN: usize = 64;
+/// Longest output HKDF-SHA256 can produce
+pub const MAX_OUTPUT_LEN: usize = 255 * 32;
+
+/// What a derived key is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyPurpose {
+    /// Symmetric encryption key
+    Encryption,
+    /// Message authentication key
+    Mac,
+    /// Public identifier, safe to reveal
+    Identifier,
+    /// Key that is itself fed into further derivation
+    Root,
+}
+
+impl KeyPurpose {
+    fn tag(&self) -> u8 {
+        match self {
+            KeyPurpose::Encryption => 1,
+            KeyPurpose::Mac => 2,
+            KeyPurpose::Identifier => 3,
+            KeyPurpose::Root => 4,
+        }
+    }
+}
+
+/// Domain-separated name of a subkey
+///
+/// The context is a short printable ASCII name such as `"records/2024"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Label {
+    purpose: KeyPurpose,
+    context: String,
+}
+
+impl Label {
+    /// Creates a label, rejecting empty, oversized or non-printable contexts
+    pub fn new(purpose: KeyPurpose, context: impl Into<String>) -> CryptoResult<Self> {
+        let context = context.into();
+        if context.is_empty() || context.len() > MAX_CONTEXT_LEN {
+            return Err(CryptoError::InvalidInput(
+                format!("Label context must be 1 to {} bytes", MAX_CONTEXT_LEN)
+            ));
+        }
+        if !context.bytes().all(|b| b.is_ascii_graphic()) {
+            return Err(CryptoError::InvalidInput("Label context must be printable ASCII".into()));
+        }
+        Ok(Self { purpose, context })
+    }
+
+    pub fn purpose(&self) -> KeyPurpose {
+        self.purpose
+    }
+
+    pub fn context(&self) -> &str {
+        &self.context
+    }
+
+    /// HKDF info string: domain, purpose and length-prefixed context
+    fn info(&self) -> Vec<u8> {
+        let mut info = Vec::with_capacity(INFO_DOMAIN.len() + 2 + self.context.len());
+        info.extend_from_slice(INFO_DOMAIN);
+        info.push(self.purpose.tag());
+        info.push(self.context.len() as u8);
+        info.extend_from_slice(self.context.as_bytes());
+        info
+    }
+}
+
+/// Encryption, MAC and identifier keys derived for one context
+#[derive(Clone, PartialEq, Eq)]
+pub struct DerivedKeys {
+    pub encryption: [u8; 32],
+    pub mac: [u8; 32],
+    pub identifier: [u8; 32],
+}
+
+impl fmt::Debug for DerivedKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DerivedKeys")
+            .field("identifier", &self.identifier)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Pseudorandom key extracted from a secret, ready for labeled expansion
+#[derive(Clone)]
+pub struct KeyDerivation {
+    hkdf: Hkdf<Sha256>,
+}
+
+impl fmt::Debug for KeyDerivation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("KeyDerivation(..)")
+    }
+}
+
+impl KeyDerivation {
+    /// Extracts from a secret with an explicit salt
+    pub fn new(secret: &[u8], salt: &[u8]) -> CryptoResult<Self> {
+        if secret.len() < MIN_SECRET_LEN {
+            return Err(CryptoError::InvalidInput(
+                format!("Secret must be at least {} bytes", MIN_SECRET_LEN)
+            ));
+        }
+        Ok(Self {
+            hkdf: Hkdf::new(Some(salt), secret),
+        })
+    }
+
+    /// Extracts from a secret reconstructed from shares
+    pub fn from_reconstructed(secret: &[u8]) -> CryptoResult<Self> {
+        Self::new(secret, SHARING_SALT)
+    }
+
+    /// Extracts from an agreed session key
+    pub fn from_session_key(key: &SessionKey) -> Self {
+        Self::new(key.as_bytes(), KEX_SALT).expect("session keys are 32 bytes")
+    }
+
+    /// Expands `len` bytes of key material for a label
+    pub fn derive(&self, label: &Label, len: usize) -> CryptoResult<Vec<u8>> {
+        if len == 0 || len > MAX_OUTPUT_LEN {
+            return Err(CryptoError::InvalidInput(
+                format!("Output length must be 1 to {} bytes", MAX_OUTPUT_LEN)
+            ));
+        }
+
+        let mut output = vec![0u8; len];
+        self.hkdf.expand(&label.info(), &mut output)
+            .map_err(|_| CryptoError::InvalidInput("HKDF output length rejected".into()))?;
+        Ok(output)
+    }
+
+    /// Expands a 32-byte key for a label
+    pub fn derive_key(&self, label: &Label) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        self.hkdf.expand(&label.info(), &mut key).expect("32 bytes is a valid HKDF length");
+        key
+    }
+
+    /// Derives the standard encryption, MAC and identifier keys for a context
+    pub fn derive_keys(&self, context: &str) -> CryptoResult<DerivedKeys> {
+        Ok(DerivedKeys {
+            encryption: self.derive_key(&Label::new(KeyPurpose::Encryption, context)?),
+            mac: self.derive_key(&Label::new(KeyPurpose::Mac, context)?),
+            identifier: self.derive_key(&Label::new(KeyPurpose::Identifier, context)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels_separate_keys() -> CryptoResult<()> {
+        let kdf = KeyDerivation::from_reconstructed(b"reconstructed secret material")?;
+        let keys = kdf.derive_keys("records")?;
+
+        assert_ne!(keys.encryption, keys.mac);
+        assert_ne!(keys.mac, keys.identifier);
+        assert_eq!(keys, kdf.derive_keys("records")?);
+        assert_ne!(keys.encryption, kdf.derive_keys("images")?.encryption);
+
+        // The length prefix keeps contexts from running into each other
+        let short = kdf.derive_key(&Label::new(KeyPurpose::Root, "ab")?);
+        assert_ne!(short, kdf.derive_key(&Label::new(KeyPurpose::Root, "abc")?));
+
+        // The same secret through another source gives other keys
+        let other = KeyDerivation::new(b"reconstructed secret material", b"elsewhere")?;
+        assert_ne!(keys.encryption, other.derive_keys("records")?.encryption);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_labels_and_lengths() -> CryptoResult<()> {
+        assert!(Label::new(KeyPurpose::Mac, "").is_err());
+        assert!(Label::new(KeyPurpose::Mac, "has space").is_err());
+        assert!(Label::new(KeyPurpose::Mac, "x".repeat(MAX_CONTEXT_LEN + 1)).is_err());
+        assert!(KeyDerivation::from_reconstructed(b"short").is_err());
+
+        let kdf = KeyDerivation::from_reconstructed(&[7u8; 32])?;
+        let label = Label::new(KeyPurpose::Encryption, "stream")?;
+        assert!(kdf.derive(&label, 0).is_err());
+        assert!(kdf.derive(&label, MAX_OUTPUT_LEN + 1).is_err());
+        assert_eq!(&kdf.derive(&label, 64)?[..32], &kdf.derive_key(&label));
+        Ok(())
+    }
+}
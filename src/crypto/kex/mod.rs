@@ -0,0 +1,364 @@
+//! Three-party group key agreement
+//! Location: src/crypto/kex/mod.rs
+//!
+//! Burmester–Desmedt over the Ristretto group. Each party broadcasts an
+//! ephemeral point, then a cross term built from its neighbours' points, and
+//! all three arrive at the same group element without it ever being sent.
+//! The session key is derived from that element with the labeled HKDF in
+//! `crypto::kdf`, salted by the transcript, and every party proves it holds
+//! the key with an HMAC tag before the key is released.
+//!
+//! The exchange resists eavesdroppers; the messages carry no long-term
+//! identity, so authenticating the parties is left to the transport.
+
+use crate::crypto::kdf::{KeyDerivation, KeyPurpose, Label};
+use crate::error::{CryptoError, CryptoResult};
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::Identity;
+use hmac::{Hmac, Mac};
+use sha2::{Sha256, Digest};
+use std::fmt;
+use tracing::{debug, info, warn};
+
+/// Number of parties in the exchange
+pub const PARTY_COUNT: usize = 3;
+
+/// Domain tag for the transcript hash
+const TRANSCRIPT_DOMAIN: &[u8] = b"mcafee/kex/transcript/v1";
+/// Label context for the session key
+const SESSION_CONTEXT: &str = "kex/session";
+/// Label context for the key confirmation key
+const CONFIRM_CONTEXT: &str = "kex/confirm";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// First-round broadcast: the party's ephemeral point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round1Message {
+    pub party: u8,
+    /// Compressed `x_i·G`
+    pub ephemeral: [u8; 32],
+}
+
+/// Second-round broadcast: the party's cross term
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round2Message {
+    pub party: u8,
+    /// Compressed `x_i·(z_{i+1} − z_{i−1})`
+    pub cross: [u8; 32],
+}
+
+/// Key confirmation tag over the transcript
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Confirmation {
+    pub party: u8,
+    pub tag: [u8; 32],
+}
+
+/// Agreed session key
+#[derive(Clone, PartialEq, Eq)]
+pub struct SessionKey([u8; 32]);
+
+impl SessionKey {
+    /// Raw key material
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Public fingerprint for comparing keys out of band
+    pub fn fingerprint(&self) -> [u8; 32] {
+        Sha256::digest(self.0).into()
+    }
+}
+
+impl fmt::Debug for SessionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SessionKey(..)")
+    }
+}
+
+/// Keys derived once both rounds are done, held until confirmation
+#[derive(Clone)]
+struct PendingKeys {
+    session_key: SessionKey,
+    confirm_key: [u8; 32],
+    transcript_hash: [u8; 32],
+}
+
+/// One party's side of the group key agreement
+///
+/// The exchange runs `round1` → `round2` → `confirm` → `finish`, each step
+/// taking every party's message from the previous one.
+pub struct GroupKeyExchange {
+    party: u8,
+    secret: Scalar,
+    ephemeral: RistrettoPoint,
+    /// Every party's ephemeral point, once round 1 is complete
+    ephemerals: Option<[RistrettoPoint; PARTY_COUNT]>,
+    /// Derived keys, once round 2 is complete
+    pending: Option<PendingKeys>,
+}
+
+impl fmt::Debug for GroupKeyExchange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GroupKeyExchange")
+            .field("party", &self.party)
+            .field("round1_done", &self.ephemerals.is_some())
+            .field("round2_done", &self.pending.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl GroupKeyExchange {
+    /// Starts an exchange for party 0, 1 or 2 with a fresh ephemeral secret
+    pub fn new(party: u8) -> CryptoResult<Self> {
+        if party as usize >= PARTY_COUNT {
+            return Err(CryptoError::InvalidInput(format!("No party {}", party)));
+        }
+
+        let secret = Scalar::random(&mut rand::thread_rng());
+        Ok(Self {
+            party,
+            secret,
+            ephemeral: RISTRETTO_BASEPOINT_POINT * secret,
+            ephemerals: None,
+            pending: None,
+        })
+    }
+
+    /// This party's index
+    pub fn party(&self) -> u8 {
+        self.party
+    }
+
+    /// Message to broadcast in the first round
+    pub fn round1(&self) -> Round1Message {
+        Round1Message {
+            party: self.party,
+            ephemeral: self.ephemeral.compress().to_bytes(),
+        }
+    }
+
+    /// Takes every first-round message and returns this party's second-round message
+    pub fn round2(&mut self, messages: &[Round1Message]) -> CryptoResult<Round2Message> {
+        if self.ephemerals.is_some() {
+            return Err(CryptoError::InvalidState("Round 1 already processed".into()));
+        }
+
+        let ordered = by_party(messages, |message| message.party)?;
+        let mut ephemerals = [RistrettoPoint::identity(); PARTY_COUNT];
+        for (point, message) in ephemerals.iter_mut().zip(ordered) {
+            *point = decode_point(&message.ephemeral)?;
+            if *point == RistrettoPoint::identity() {
+                return Err(CryptoError::VerificationFailed(
+                    format!("Party {} sent the identity point", message.party)
+                ));
+            }
+        }
+        if ephemerals[self.party as usize] != self.ephemeral {
+            return Err(CryptoError::VerificationFailed("Own round 1 message was altered".into()));
+        }
+
+        let (next, previous) = neighbours(self.party as usize);
+        let cross = (ephemerals[next] - ephemerals[previous]) * self.secret;
+        self.ephemerals = Some(ephemerals);
+
+        debug!(party = self.party, "Key exchange round 1 complete");
+        Ok(Round2Message {
+            party: self.party,
+            cross: cross.compress().to_bytes(),
+        })
+    }
+
+    /// Takes every second-round message, derives the keys and returns this party's confirmation
+    pub fn confirm(&mut self, messages: &[Round2Message]) -> CryptoResult<Confirmation> {
+        let ephemerals = self.ephemerals
+            .ok_or_else(|| CryptoError::InvalidState("Round 1 not processed".into()))?;
+        if self.pending.is_some() {
+            return Err(CryptoError::InvalidState("Round 2 already processed".into()));
+        }
+
+        let ordered = by_party(messages, |message| message.party)?;
+        let mut crosses = [RistrettoPoint::identity(); PARTY_COUNT];
+        for (point, message) in crosses.iter_mut().zip(ordered) {
+            *point = decode_point(&message.cross)?;
+        }
+
+        // The cross terms of an honest run telescope to the identity
+        if crosses.iter().sum::<RistrettoPoint>() != RistrettoPoint::identity() {
+            return Err(CryptoError::VerificationFailed("Round 2 cross terms are inconsistent".into()));
+        }
+
+        // K = 3·x_i·z_{i−1} + 2·X_i + X_{i+1}
+        let me = self.party as usize;
+        let (next, previous) = neighbours(me);
+        let shared = ephemerals[previous] * (Scalar::from(PARTY_COUNT as u64) * self.secret)
+            + crosses[me] * Scalar::from(2u64)
+            + crosses[next];
+
+        let transcript_hash = transcript_hash(&ephemerals, &crosses);
+        let kdf = KeyDerivation::new(shared.compress().as_bytes(), &transcript_hash)?;
+        let pending = PendingKeys {
+            session_key: SessionKey(kdf.derive_key(&Label::new(KeyPurpose::Root, SESSION_CONTEXT)?)),
+            confirm_key: kdf.derive_key(&Label::new(KeyPurpose::Mac, CONFIRM_CONTEXT)?),
+            transcript_hash,
+        };
+        let tag = confirmation_mac(&pending, self.party).finalize().into_bytes().into();
+        self.pending = Some(pending);
+
+        debug!(party = self.party, "Key exchange round 2 complete");
+        Ok(Confirmation { party: self.party, tag })
+    }
+
+    /// Checks every party's confirmation and releases the session key
+    pub fn finish(self, confirmations: &[Confirmation]) -> CryptoResult<SessionKey> {
+        let pending = self.pending
+            .ok_or_else(|| CryptoError::InvalidState("Round 2 not processed".into()))?;
+
+        for confirmation in by_party(confirmations, |confirmation| confirmation.party)? {
+            if confirmation_mac(&pending, confirmation.party).verify_slice(&confirmation.tag).is_err() {
+                warn!(party = self.party, from = confirmation.party, "Key confirmation failed");
+                return Err(CryptoError::VerificationFailed(
+                    format!("Key confirmation from party {} failed", confirmation.party)
+                ));
+            }
+        }
+
+        info!(party = self.party, "Group key agreed");
+        Ok(pending.session_key)
+    }
+}
+
+/// Indices of the next and previous party around the ring
+fn neighbours(party: usize) -> (usize, usize) {
+    ((party + 1) % PARTY_COUNT, (party + PARTY_COUNT - 1) % PARTY_COUNT)
+}
+
+/// Orders one message per party, rejecting gaps and duplicates
+fn by_party<T>(messages: &[T], party_of: impl Fn(&T) -> u8) -> CryptoResult<[&T; PARTY_COUNT]> {
+    if messages.len() != PARTY_COUNT {
+        return Err(CryptoError::InvalidInput(
+            format!("Expected {} messages, got {}", PARTY_COUNT, messages.len())
+        ));
+    }
+
+    let mut ordered: [Option<&T>; PARTY_COUNT] = [None; PARTY_COUNT];
+    for message in messages {
+        let party = party_of(message);
+        match ordered.get_mut(party as usize) {
+            Some(slot @ None) => *slot = Some(message),
+            Some(Some(_)) => return Err(CryptoError::InvalidInput(format!("Duplicate message from party {}", party))),
+            None => return Err(CryptoError::InvalidInput(format!("No party {}", party))),
+        }
+    }
+
+    Ok(ordered.map(|slot| slot.expect("every party present")))
+}
+
+fn decode_point(bytes: &[u8; 32]) -> CryptoResult<RistrettoPoint> {
+    CompressedRistretto(*bytes)
+        .decompress()
+        .ok_or_else(|| CryptoError::VerificationFailed("Invalid group element".into()))
+}
+
+fn transcript_hash(ephemerals: &[RistrettoPoint], crosses: &[RistrettoPoint]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(TRANSCRIPT_DOMAIN);
+    for point in ephemerals.iter().chain(crosses) {
+        hasher.update(point.compress().as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+fn confirmation_mac(pending: &PendingKeys, party: u8) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(&pending.confirm_key)
+        .expect("HMAC accepts any key length");
+    mac.update(&[party]);
+    mac.update(&pending.transcript_hash);
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange() -> CryptoResult<Vec<GroupKeyExchange>> {
+        (0..PARTY_COUNT as u8).map(GroupKeyExchange::new).collect()
+    }
+
+    fn run(mut parties: Vec<GroupKeyExchange>) -> CryptoResult<Vec<SessionKey>> {
+        let round1: Vec<_> = parties.iter().map(GroupKeyExchange::round1).collect();
+        let round2 = parties.iter_mut()
+            .map(|party| party.round2(&round1))
+            .collect::<CryptoResult<Vec<_>>>()?;
+        let confirmations = parties.iter_mut()
+            .map(|party| party.confirm(&round2))
+            .collect::<CryptoResult<Vec<_>>>()?;
+        parties.into_iter().map(|party| party.finish(&confirmations)).collect()
+    }
+
+    #[test]
+    fn test_parties_agree() -> CryptoResult<()> {
+        let keys = run(exchange()?)?;
+        assert_eq!(keys[0], keys[1]);
+        assert_eq!(keys[1], keys[2]);
+
+        // A fresh exchange gives a fresh key
+        let again = run(exchange()?)?;
+        assert_ne!(keys[0], again[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tampered_messages_rejected() -> CryptoResult<()> {
+        let mut parties = exchange()?;
+        let mut round1: Vec<_> = parties.iter().map(GroupKeyExchange::round1).collect();
+
+        // Substituting party 2's ephemeral splits the group
+        round1[2].ephemeral = GroupKeyExchange::new(2)?.round1().ephemeral;
+        let round2 = parties.iter_mut()
+            .map(|party| party.round2(&round1))
+            .collect::<CryptoResult<Vec<_>>>();
+        assert!(round2.is_err(), "party 2 notices its own message was replaced");
+
+        let mut parties = exchange()?;
+        let round1: Vec<_> = parties.iter().map(GroupKeyExchange::round1).collect();
+        let mut round2 = parties.iter_mut()
+            .map(|party| party.round2(&round1))
+            .collect::<CryptoResult<Vec<_>>>()?;
+        round2[1].cross = round2[0].cross;
+        assert!(matches!(parties[0].confirm(&round2), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_confirmation_required() -> CryptoResult<()> {
+        let mut parties = exchange()?;
+        let round1: Vec<_> = parties.iter().map(GroupKeyExchange::round1).collect();
+        let round2 = parties.iter_mut()
+            .map(|party| party.round2(&round1))
+            .collect::<CryptoResult<Vec<_>>>()?;
+        let mut confirmations = parties.iter_mut()
+            .map(|party| party.confirm(&round2))
+            .collect::<CryptoResult<Vec<_>>>()?;
+        confirmations[2].tag[0] ^= 1;
+
+        let party = parties.remove(0);
+        assert!(matches!(party.finish(&confirmations), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_message_set_checked() -> CryptoResult<()> {
+        assert!(GroupKeyExchange::new(3).is_err());
+
+        let mut party = GroupKeyExchange::new(0)?;
+        let first = party.round1();
+        assert!(party.round2(&[first, first, first]).is_err());
+        assert!(party.round2(&[first]).is_err());
+        assert!(matches!(party.confirm(&[]), Err(CryptoError::InvalidState(_))));
+        Ok(())
+    }
+}
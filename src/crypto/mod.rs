@@ -2,7 +2,15 @@
 //! Location: src/crypto/mod.rs
 
 pub mod vdf;
+pub mod audit;
+pub mod auth;
+pub mod beacon;
+pub mod kdf;
+pub mod kex;
+pub mod protocol;
 pub mod sharing;
+pub mod store;
+pub mod timelock;
 pub mod utils;
 
 // Re-export commonly used items
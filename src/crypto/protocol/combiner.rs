@@ -0,0 +1,175 @@
+//! Combiner role: collects released shares and reconstructs secrets
+//! Location: src/crypto/protocol/combiner.rs
+
+use super::{Actor, Envelope, Message, NodeId, ReconstructRequest, SecretId, ShareRelease, CUSTODIAN_COUNT};
+use crate::crypto::sharing::{Share, ThreePartySecretSharing};
+use crate::error::{CryptoError, CryptoResult};
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
+
+/// Reconstruction progress for one secret
+#[derive(Debug)]
+enum Reconstruction {
+    /// Waiting for releases answering the request with this nonce
+    Collecting {
+        nonce: [u8; 32],
+        shares: [Option<Share>; CUSTODIAN_COUNT],
+    },
+    /// Every share arrived and the secret was rebuilt
+    Complete(Vec<u8>),
+}
+
+/// Requests shares from custodians and rebuilds secrets once all arrive
+#[derive(Debug)]
+pub struct Combiner {
+    sharing: ThreePartySecretSharing,
+    reconstructions: HashMap<SecretId, Reconstruction>,
+}
+
+impl Combiner {
+    pub fn new(sharing: ThreePartySecretSharing) -> Self {
+        Self {
+            sharing,
+            reconstructions: HashMap::new(),
+        }
+    }
+
+    /// Starts reconstructing a secret and returns the requests to send
+    ///
+    /// Calling it again restarts collection under a new nonce, so releases
+    /// answering an earlier request are ignored.
+    pub fn request(&mut self, id: SecretId) -> Vec<Envelope> {
+        let nonce = rand::random();
+        self.reconstructions.insert(id, Reconstruction::Collecting {
+            nonce,
+            shares: Default::default(),
+        });
+
+        info!(secret = %id, "Requesting share release");
+        self.retransmit(&id)
+    }
+
+    /// Repeats the current request to custodians whose share has not arrived
+    pub fn retransmit(&self, id: &SecretId) -> Vec<Envelope> {
+        let Some(Reconstruction::Collecting { nonce, .. }) = self.reconstructions.get(id) else {
+            return Vec::new();
+        };
+        self.missing(id)
+            .into_iter()
+            .map(|custodian| Envelope::new(custodian, ReconstructRequest { secret_id: *id, nonce: *nonce }))
+            .collect()
+    }
+
+    /// Custodians whose share has not arrived yet
+    pub fn missing(&self, id: &SecretId) -> Vec<NodeId> {
+        match self.reconstructions.get(id) {
+            Some(Reconstruction::Collecting { shares, .. }) => shares.iter()
+                .enumerate()
+                .filter(|(_, share)| share.is_none())
+                .map(|(custodian, _)| NodeId::Custodian(custodian as u8))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether the secret has been reconstructed
+    pub fn is_complete(&self, id: &SecretId) -> bool {
+        matches!(self.reconstructions.get(id), Some(Reconstruction::Complete(_)))
+    }
+
+    /// Removes and returns a reconstructed secret
+    pub fn take_secret(&mut self, id: &SecretId) -> Option<Vec<u8>> {
+        match self.reconstructions.remove(id)? {
+            Reconstruction::Complete(secret) => Some(secret),
+            collecting => {
+                self.reconstructions.insert(*id, collecting);
+                None
+            }
+        }
+    }
+
+    fn handle_release(&mut self, from: NodeId, release: ShareRelease) -> CryptoResult<()> {
+        let share_id = release.share.id();
+        if from != NodeId::Custodian(share_id) {
+            return Err(CryptoError::VerificationFailed(format!("{} released share {}", from, share_id)));
+        }
+
+        let Some(Reconstruction::Collecting { nonce, shares }) = self.reconstructions.get_mut(&release.secret_id) else {
+            debug!(secret = %release.secret_id, "Ignoring release with no collection in progress");
+            return Ok(());
+        };
+        if release.nonce != *nonce {
+            debug!(secret = %release.secret_id, custodian = share_id, "Ignoring release for a stale request");
+            return Ok(());
+        }
+        if !release.share.verify() {
+            warn!(secret = %release.secret_id, custodian = share_id, "Released share failed verification");
+            return Err(CryptoError::VerificationFailed(format!("Share from {} failed verification", from)));
+        }
+
+        let slot = shares.get_mut(share_id as usize)
+            .ok_or_else(|| CryptoError::InvalidInput(format!("No custodian {}", share_id)))?;
+        *slot = Some(release.share);
+
+        if shares.iter().all(Option::is_some) {
+            let collected: Vec<Share> = shares.iter_mut().filter_map(Option::take).collect();
+            let secret = self.sharing.reconstruct_for(release.secret_id, &collected)?;
+            self.reconstructions.insert(release.secret_id, Reconstruction::Complete(secret));
+            info!(secret = %release.secret_id, "Secret reconstructed");
+        }
+        Ok(())
+    }
+}
+
+impl Actor for Combiner {
+    fn node(&self) -> NodeId {
+        NodeId::Combiner
+    }
+
+    fn handle(&mut self, from: NodeId, message: Message) -> CryptoResult<Vec<Envelope>> {
+        match message {
+            Message::ShareRelease(release) => self.handle_release(from, release).map(|_| Vec::new()),
+            other => Err(CryptoError::InvalidState(format!("Combiner cannot handle {}", other.kind()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignores_stale_and_rejects_misattributed_releases() -> CryptoResult<()> {
+        let mut sharing = ThreePartySecretSharing::default();
+        let shares = sharing.split(b"secret")?;
+        let mut combiner = Combiner::new(ThreePartySecretSharing::default());
+        let id = SecretId::random();
+
+        let stale_nonce = match &combiner.request(id)[0].message {
+            Message::ReconstructRequest(request) => request.nonce,
+            other => panic!("sent {}", other.kind()),
+        };
+        let nonce = match &combiner.request(id)[0].message {
+            Message::ReconstructRequest(request) => request.nonce,
+            other => panic!("sent {}", other.kind()),
+        };
+
+        let release = |share: &Share, nonce| Message::from(ShareRelease { secret_id: id, nonce, share: share.clone() });
+        combiner.handle(NodeId::Custodian(0), release(&shares[0], stale_nonce))?;
+        assert_eq!(combiner.missing(&id).len(), CUSTODIAN_COUNT);
+
+        combiner.handle(NodeId::Custodian(0), release(&shares[0], nonce))?;
+        let retried: Vec<_> = combiner.retransmit(&id).into_iter().map(|e| e.to).collect();
+        assert_eq!(retried, vec![NodeId::Custodian(1), NodeId::Custodian(2)]);
+
+        assert!(combiner.handle(NodeId::Custodian(1), release(&shares[0], nonce)).is_err());
+
+        for share in &shares {
+            combiner.handle(NodeId::Custodian(share.id()), release(share, nonce))?;
+        }
+        assert!(combiner.is_complete(&id));
+        assert_eq!(combiner.take_secret(&id).as_deref(), Some(&b"secret"[..]));
+        assert!(combiner.take_secret(&id).is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+//! Custodian role: holds one share per secret and releases it on request
+//! Location: src/crypto/protocol/custodian.rs
+
+use super::{Actor, DeliveryAck, Envelope, Message, NodeId, ReconstructRequest, SecretId, ShareDelivery, ShareRelease};
+use crate::crypto::sharing::Share;
+use crate::error::{CryptoError, CryptoResult};
+use std::collections::hash_map::{Entry, HashMap};
+use tracing::{debug, info, warn};
+
+/// Holds the shares with one share id and answers release requests
+#[derive(Debug)]
+pub struct Custodian {
+    share_id: u8,
+    shares: HashMap<SecretId, Share>,
+}
+
+impl Custodian {
+    /// Creates a custodian for the shares with the given id
+    pub fn new(share_id: u8) -> Self {
+        Self {
+            share_id,
+            shares: HashMap::new(),
+        }
+    }
+
+    /// Id of the shares this custodian holds
+    pub fn share_id(&self) -> u8 {
+        self.share_id
+    }
+
+    /// Whether a share of the secret is held
+    pub fn holds(&self, id: &SecretId) -> bool {
+        self.shares.contains_key(id)
+    }
+
+    /// Stores a share delivered out of band, checking it as a delivery would be
+    ///
+    /// A share that is already held may be delivered again, but it is never
+    /// replaced by a different one.
+    pub fn store(&mut self, secret_id: SecretId, share: Share) -> CryptoResult<()> {
+        if share.id() != self.share_id {
+            return Err(CryptoError::InvalidInput(
+                format!("Custodian {} cannot hold share {}", self.share_id, share.id())
+            ));
+        }
+        if !share.verify() {
+            warn!(secret = %secret_id, custodian = self.share_id, "Delivered share failed verification");
+            return Err(CryptoError::VerificationFailed("Delivered share failed verification".into()));
+        }
+
+        match self.shares.entry(secret_id) {
+            Entry::Occupied(held) if held.get().data() == share.data() => {
+                debug!(secret = %secret_id, custodian = self.share_id, "Share already held");
+            }
+            Entry::Occupied(_) => {
+                warn!(secret = %secret_id, custodian = self.share_id, "Refused to replace held share");
+                return Err(CryptoError::InvalidState(
+                    format!("Custodian {} already holds a different share of {}", self.share_id, secret_id)
+                ));
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(share);
+                debug!(secret = %secret_id, custodian = self.share_id, "Stored share");
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_delivery(&mut self, from: NodeId, delivery: ShareDelivery) -> CryptoResult<Vec<Envelope>> {
+        if from != NodeId::Dealer {
+            return Err(CryptoError::VerificationFailed(
+                format!("{} delivered share {}", from, delivery.share.id())
+            ));
+        }
+
+        self.store(delivery.secret_id, delivery.share)?;
+        Ok(vec![Envelope::new(from, DeliveryAck {
+            secret_id: delivery.secret_id,
+            share_id: self.share_id,
+        })])
+    }
+
+    fn handle_request(&mut self, from: NodeId, request: ReconstructRequest) -> CryptoResult<Vec<Envelope>> {
+        if from != NodeId::Combiner {
+            return Err(CryptoError::VerificationFailed(
+                format!("{} requested share {} of {}", from, self.share_id, request.secret_id)
+            ));
+        }
+
+        let share = self.shares.get(&request.secret_id).ok_or_else(|| CryptoError::InvalidState(
+            format!("Custodian {} holds no share of {}", self.share_id, request.secret_id)
+        ))?;
+
+        info!(secret = %request.secret_id, custodian = self.share_id, to = %from, "Releasing share");
+        Ok(vec![Envelope::new(from, ShareRelease {
+            secret_id: request.secret_id,
+            nonce: request.nonce,
+            share: share.clone(),
+        })])
+    }
+}
+
+impl Actor for Custodian {
+    fn node(&self) -> NodeId {
+        NodeId::Custodian(self.share_id)
+    }
+
+    fn handle(&mut self, from: NodeId, message: Message) -> CryptoResult<Vec<Envelope>> {
+        match message {
+            Message::ShareDelivery(delivery) => self.handle_delivery(from, delivery),
+            Message::ReconstructRequest(request) => self.handle_request(from, request),
+            other => Err(CryptoError::InvalidState(
+                format!("Custodian cannot handle {}", other.kind())
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_foreign_and_corrupt_shares() -> CryptoResult<()> {
+        let mut custodian = Custodian::new(1);
+        let id = SecretId::random();
+
+        let foreign = ShareDelivery { secret_id: id, share: Share::new(vec![1; 16], 0) };
+        assert!(custodian.handle(NodeId::Dealer, foreign.into()).is_err());
+
+        let corrupt = Share::from_parts(vec![1; 16], 1, [0; 32]);
+        let delivery = ShareDelivery { secret_id: id, share: corrupt };
+        assert!(matches!(
+            custodian.handle(NodeId::Dealer, delivery.into()),
+            Err(CryptoError::VerificationFailed(_))
+        ));
+
+        let request = ReconstructRequest { secret_id: id, nonce: [0; 32] };
+        assert!(custodian.handle(NodeId::Combiner, request.into()).is_err());
+
+        let delivery = ShareDelivery { secret_id: id, share: Share::new(vec![1; 16], 1) };
+        let replies = custodian.handle(NodeId::Dealer, delivery.into())?;
+        assert!(matches!(replies[0].message, Message::DeliveryAck(DeliveryAck { share_id: 1, .. })));
+        assert!(custodian.holds(&id));
+        Ok(())
+    }
+
+    #[test]
+    fn test_checks_sender_roles() -> CryptoResult<()> {
+        let mut custodian = Custodian::new(0);
+        let id = SecretId::random();
+
+        let delivery = ShareDelivery { secret_id: id, share: Share::new(vec![5; 16], 0) };
+        for sender in [NodeId::Combiner, NodeId::Custodian(1)] {
+            assert!(matches!(
+                custodian.handle(sender, delivery.clone().into()),
+                Err(CryptoError::VerificationFailed(_))
+            ));
+        }
+        assert!(!custodian.holds(&id));
+        custodian.handle(NodeId::Dealer, delivery.into())?;
+
+        let request = ReconstructRequest { secret_id: id, nonce: [1; 32] };
+        for sender in [NodeId::Dealer, NodeId::Custodian(2)] {
+            assert!(matches!(
+                custodian.handle(sender, request.into()),
+                Err(CryptoError::VerificationFailed(_))
+            ));
+        }
+        let replies = custodian.handle(NodeId::Combiner, request.into())?;
+        assert!(matches!(replies[0].message, Message::ShareRelease(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_refuses_to_replace_share() -> CryptoResult<()> {
+        let mut custodian = Custodian::new(2);
+        let id = SecretId::random();
+        let original = Share::new(vec![7; 16], 2);
+
+        custodian.store(id, original.clone())?;
+        // Redelivering the same share is acknowledged again
+        let redelivery = ShareDelivery { secret_id: id, share: original.clone() };
+        custodian.handle(NodeId::Dealer, redelivery.into())?;
+
+        let replacement = ShareDelivery { secret_id: id, share: Share::new(vec![8; 16], 2) };
+        assert!(matches!(
+            custodian.handle(NodeId::Dealer, replacement.into()),
+            Err(CryptoError::InvalidState(_))
+        ));
+
+        let request = ReconstructRequest { secret_id: id, nonce: [0; 32] };
+        match &custodian.handle(NodeId::Combiner, request.into())?[0].message {
+            Message::ShareRelease(release) => assert_eq!(release.share.data(), original.data()),
+            other => panic!("expected a release, got {}", other.kind()),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+//! Dealer role: splits secrets and delivers one share per custodian
+//! Location: src/crypto/protocol/dealer.rs
+
+use super::{Actor, DeliveryAck, Envelope, Message, NodeId, SecretId, ShareDelivery, CUSTODIAN_COUNT};
+use crate::crypto::audit::AuditEvent;
+use crate::crypto::sharing::{Share, ThreePartySecretSharing};
+use crate::error::{CryptoError, CryptoResult};
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+/// Delivery progress for one secret
+#[derive(Debug)]
+enum Delivery {
+    /// Shares not yet acknowledged are kept for retransmission
+    Pending([Option<Share>; CUSTODIAN_COUNT]),
+    /// Every custodian acknowledged; the dealer holds nothing
+    Delivered,
+}
+
+/// Splits secrets and tracks which custodians have acknowledged their share
+#[derive(Debug)]
+pub struct Dealer {
+    sharing: ThreePartySecretSharing,
+    deliveries: HashMap<SecretId, Delivery>,
+}
+
+impl Dealer {
+    pub fn new(sharing: ThreePartySecretSharing) -> Self {
+        Self {
+            sharing,
+            deliveries: HashMap::new(),
+        }
+    }
+
+    /// Splits a secret under a fresh id and returns the deliveries to send
+    pub fn deal(&mut self, secret: &[u8]) -> CryptoResult<(SecretId, Vec<Envelope>)> {
+        let id = SecretId::random();
+        let shares = self.sharing.split_for(id, secret)?;
+        let mut pending: [Option<Share>; CUSTODIAN_COUNT] = Default::default();
+        for share in shares {
+            let slot = pending.get_mut(share.id() as usize)
+                .ok_or_else(|| CryptoError::InvalidState(format!("Unexpected share id {}", share.id())))?;
+            *slot = Some(share);
+        }
+
+        self.deliveries.insert(id, Delivery::Pending(pending));
+        info!(secret = %id, "Dealt secret");
+        Ok((id, self.retransmit(&id)))
+    }
+
+    /// Deliveries for every custodian that has not acknowledged yet
+    pub fn retransmit(&self, id: &SecretId) -> Vec<Envelope> {
+        match self.deliveries.get(id) {
+            Some(Delivery::Pending(shares)) => shares.iter()
+                .flatten()
+                .map(|share| Envelope::new(
+                    NodeId::Custodian(share.id()),
+                    ShareDelivery { secret_id: *id, share: share.clone() },
+                ))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether every custodian acknowledged its share of the secret
+    pub fn is_delivered(&self, id: &SecretId) -> bool {
+        matches!(self.deliveries.get(id), Some(Delivery::Delivered))
+    }
+
+    fn handle_ack(&mut self, from: NodeId, ack: DeliveryAck) -> CryptoResult<()> {
+        if from != NodeId::Custodian(ack.share_id) {
+            return Err(CryptoError::VerificationFailed(
+                format!("{} acknowledged share {}", from, ack.share_id)
+            ));
+        }
+
+        let delivery = self.deliveries.get_mut(&ack.secret_id)
+            .ok_or_else(|| CryptoError::InvalidState(format!("Unknown secret {}", ack.secret_id)))?;
+        if let Delivery::Pending(shares) = delivery {
+            let first_ack = shares.get_mut(ack.share_id as usize).is_some_and(|slot| slot.take().is_some());
+            if let Some(log) = self.sharing.audit_log().filter(|_| first_ack) {
+                log.record(AuditEvent::ShareDelivered { secret: ack.secret_id, share_id: ack.share_id })?;
+            }
+            debug!(secret = %ack.secret_id, custodian = ack.share_id, "Delivery acknowledged");
+
+            if shares.iter().all(Option::is_none) {
+                *delivery = Delivery::Delivered;
+                info!(secret = %ack.secret_id, "All shares delivered");
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Actor for Dealer {
+    fn node(&self) -> NodeId {
+        NodeId::Dealer
+    }
+
+    fn handle(&mut self, from: NodeId, message: Message) -> CryptoResult<Vec<Envelope>> {
+        match message {
+            Message::DeliveryAck(ack) => self.handle_ack(from, ack).map(|_| Vec::new()),
+            other => Err(CryptoError::InvalidState(format!("Dealer cannot handle {}", other.kind()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::audit::AuditLog;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_retransmits_until_acknowledged() -> CryptoResult<()> {
+        let log = Arc::new(AuditLog::in_memory("dealer"));
+        let mut dealer = Dealer::new(ThreePartySecretSharing::default().with_audit_log(Arc::clone(&log)));
+        let (id, deliveries) = dealer.deal(b"secret")?;
+        assert_eq!(deliveries.len(), CUSTODIAN_COUNT);
+
+        dealer.handle(NodeId::Custodian(1), DeliveryAck { secret_id: id, share_id: 1 }.into())?;
+        dealer.handle(NodeId::Custodian(1), DeliveryAck { secret_id: id, share_id: 1 }.into())?;
+        let remaining: Vec<_> = dealer.retransmit(&id).into_iter().map(|e| e.to).collect();
+        assert_eq!(remaining, vec![NodeId::Custodian(0), NodeId::Custodian(2)]);
+
+        // Only the custodian holding a share may acknowledge it
+        let forged = DeliveryAck { secret_id: id, share_id: 0 };
+        assert!(dealer.handle(NodeId::Custodian(2), forged.into()).is_err());
+
+        for share_id in [0, 2] {
+            dealer.handle(NodeId::Custodian(share_id), DeliveryAck { secret_id: id, share_id }.into())?;
+        }
+        assert!(dealer.is_delivered(&id));
+        assert!(dealer.retransmit(&id).is_empty());
+
+        // One split under the secret's id, then each custodian's first acknowledgement
+        let records = log.records()?;
+        assert!(matches!(records[0].event, AuditEvent::Split { secret: Some(secret), .. } if secret == id));
+        let delivered: Vec<_> = records.into_iter().skip(1).map(|r| r.event).collect();
+        assert_eq!(delivered, [1, 0, 2].map(|share_id| AuditEvent::ShareDelivered { secret: id, share_id }));
+        Ok(())
+    }
+}
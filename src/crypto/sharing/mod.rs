@@ -2,7 +2,9 @@
 //! Location: src/crypto/sharing/mod.rs
 
 use crate::error::{CryptoError, CryptoResult};
-use crate::crypto::utils::padding;
+use crate::crypto::audit::{AuditEvent, AuditLog};
+use crate::crypto::protocol::SecretId;
+use crate::crypto::utils::{padding, CancellationToken};
 use rand::Rng;
 use sha2::{Sha256, Digest};
 use std::sync::Arc;
@@ -12,6 +14,8 @@ use rayon::prelude::*;
 
 /// Size of blocks for parallel processing
 const BLOCK_SIZE: usize = 1024 * 64; // 64KB blocks
+/// Domain tag for share hashes
+const SHARE_DOMAIN: &[u8] = b"mcafee/share/v1";
 
 /// A share in the secret sharing scheme
 #[derive(Debug, Clone)]
@@ -27,25 +31,44 @@ pub struct Share {
 impl Share {
     /// Creates a new share with given data and ID
     pub fn new(data: Vec<u8>, id: u8) -> Self {
-        let mut hasher = Sha256::new();
-        hasher.update(&data);
-        let hash = hasher.finalize().into();
-
+        let hash = share_hash(id, &data);
         Self { data, id, hash }
     }
 
-    /// Verifies the integrity of the share
+    /// Verifies the integrity of the share, including its identifier
     pub fn verify(&self) -> bool {
-        let mut hasher = Sha256::new();
-        hasher.update(&self.data);
-        let computed_hash: [u8; 32] = hasher.finalize().into();
-        computed_hash == self.hash
+        share_hash(self.id, &self.data) == self.hash
     }
 
     /// Gets a reference to the share data
     pub fn data(&self) -> &[u8] {
         &self.data
     }
+
+    /// Gets the share identifier
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Hash recorded when the share was created
+    pub(crate) fn hash(&self) -> &[u8; 32] {
+        &self.hash
+    }
+
+    /// Rebuilds a share received from elsewhere, keeping its recorded hash
+    pub(crate) fn from_parts(data: Vec<u8>, id: u8, hash: [u8; 32]) -> Self {
+        Self { data, id, hash }
+    }
+}
+
+/// Hashes a share's data under its identifier, so a relabelled share fails verification
+fn share_hash(id: u8, data: &[u8]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(SHARE_DOMAIN)
+        .chain_update([id])
+        .chain_update(data)
+        .finalize()
+        .into()
 }
 
 /// Configuration for the sharing scheme
@@ -70,27 +93,73 @@ impl Default for SharingConfig {
 }
 
 /// Implementation of three-party secret sharing
+#[derive(Debug)]
 pub struct ThreePartySecretSharing {
     config: SharingConfig,
-    shares: Vec<Option<Share>>,
+    /// Log receiving split, reconstruct and verification failure events
+    audit: Option<Arc<AuditLog>>,
+}
+
+impl Default for ThreePartySecretSharing {
+    /// Creates a new instance with default configuration
+    fn default() -> Self {
+        Self::new(SharingConfig::default())
+    }
 }
 
 impl ThreePartySecretSharing {
     /// Creates a new instance with given configuration
     pub fn new(config: SharingConfig) -> Self {
-        Self {
-            config,
-            shares: vec![None, None, None],
-        }
+        Self { config, audit: None }
     }
 
-    /// Creates a new instance with default configuration
-    pub fn default() -> Self {
-        Self::new(SharingConfig::default())
+    /// Records splits, reconstructions and failed share checks in `log`
+    pub fn with_audit_log(mut self, log: Arc<AuditLog>) -> Self {
+        self.audit = Some(log);
+        self
+    }
+
+    /// Audit log events are recorded in, if any
+    pub fn audit_log(&self) -> Option<&Arc<AuditLog>> {
+        self.audit.as_ref()
     }
 
     /// Splits a secret into three shares
     pub fn split(&mut self, secret: &[u8]) -> CryptoResult<Vec<Share>> {
+        self.split_until(secret, None, None)
+    }
+
+    /// Splits a secret, naming it by `id` in the audit log
+    pub fn split_for(&mut self, id: SecretId, secret: &[u8]) -> CryptoResult<Vec<Share>> {
+        self.split_until(secret, Some(id), None)
+    }
+
+    /// Splits a secret block by block, stopping when the token is cancelled or times out
+    pub fn split_with(&mut self, secret: &[u8], token: &CancellationToken) -> CryptoResult<Vec<Share>> {
+        self.split_until(secret, None, Some(token))
+    }
+
+    /// Reconstructs the secret from shares
+    pub fn reconstruct(&self, shares: &[Share]) -> CryptoResult<Vec<u8>> {
+        self.reconstruct_until(shares, None, None)
+    }
+
+    /// Reconstructs the secret, naming it by `id` in the audit log
+    pub fn reconstruct_for(&self, id: SecretId, shares: &[Share]) -> CryptoResult<Vec<u8>> {
+        self.reconstruct_until(shares, Some(id), None)
+    }
+
+    /// Reconstructs block by block, stopping when the token is cancelled or times out
+    pub fn reconstruct_with(&self, shares: &[Share], token: &CancellationToken) -> CryptoResult<Vec<u8>> {
+        self.reconstruct_until(shares, None, Some(token))
+    }
+
+    fn split_until(
+        &mut self,
+        secret: &[u8],
+        id: Option<SecretId>,
+        token: Option<&CancellationToken>,
+    ) -> CryptoResult<Vec<Share>> {
         if secret.is_empty() {
             return Err(CryptoError::InvalidInput("Secret cannot be empty".into()));
         }
@@ -98,15 +167,22 @@ impl ThreePartySecretSharing {
         // Use the new padding utility instead of internal pad_data
         let padded = padding::pad_data(secret)?;
 
-        if self.config.parallel && padded.len() >= self.config.parallel_threshold {
-            self.split_parallel(&padded)
+        let shares = if self.config.parallel && padded.len() >= self.config.parallel_threshold {
+            self.split_parallel(&padded, token)
         } else {
-            self.split_sequential(&padded)
-        }
+            self.split_sequential(&padded, token)
+        }?;
+
+        self.audit(AuditEvent::Split { secret: id, share_hashes: shares.iter().map(|s| s.hash).collect() })?;
+        Ok(shares)
     }
 
-    /// Reconstructs the secret from shares
-    pub fn reconstruct(&self, shares: &[Share]) -> CryptoResult<Vec<u8>> {
+    fn reconstruct_until(
+        &self,
+        shares: &[Share],
+        id: Option<SecretId>,
+        token: Option<&CancellationToken>,
+    ) -> CryptoResult<Vec<u8>> {
         // Validate shares
         if shares.len() != 3 {
             return Err(CryptoError::InvalidInput("Need exactly 3 shares".into()));
@@ -119,7 +195,7 @@ impl ThreePartySecretSharing {
         }
 
         // Check alignment
-        if share_len % padding::ALIGNMENT != 0 {
+        if !share_len.is_multiple_of(padding::ALIGNMENT) {
             return Err(CryptoError::InvalidInput(
                 format!("Share length must be aligned to {} bytes", padding::ALIGNMENT)
             ));
@@ -127,6 +203,7 @@ impl ThreePartySecretSharing {
 
         for share in shares {
             if !share.verify() {
+                self.audit(AuditEvent::VerifyFailed { secret: id, share_id: share.id })?;
                 return Err(CryptoError::VerificationFailed("Share verification failed".into()));
             }
         }
@@ -134,38 +211,49 @@ impl ThreePartySecretSharing {
         // Reconstruct padded data
         let reconstructed = if self.config.parallel &&
             shares[0].data.len() >= self.config.parallel_threshold {
-            self.reconstruct_parallel(shares)
+            self.reconstruct_parallel(shares, token)
         } else {
-            self.reconstruct_sequential(shares)
+            self.reconstruct_sequential(shares, token)
         }?;
 
         // Unpad using the new padding utility
-        padding::unpad_data(&reconstructed)
-    }
+        let secret = padding::unpad_data(&reconstructed)?;
 
-    // Private helper methods
-
-    fn pad_data(&self, data: &[u8]) -> CryptoResult<Vec<u8>> {
-        let mut padded = Vec::with_capacity(data.len() + 32);
-
-        // Store original length
-        padded.push(data.len() as u8);
-        padded.extend_from_slice(data);
+        self.audit(AuditEvent::Reconstruct { secret: id, share_hashes: shares.iter().map(|s| s.hash).collect() })?;
+        Ok(secret)
+    }
 
-        // Add random padding to block size
-        let padding_len = (self.config.block_size -
-            (padded.len() % self.config.block_size)) % self.config.block_size;
+    /// Records an event if an audit log is attached
+    fn audit(&self, event: AuditEvent) -> CryptoResult<()> {
+        if let Some(log) = &self.audit {
+            log.record(event)?;
+        }
+        Ok(())
+    }
 
-        let mut rng = rand::thread_rng();
-        padded.extend((0..padding_len).map(|_| rng.gen::<u8>()));
+    /// Splits a secret on the blocking thread pool
+    #[cfg(feature = "async")]
+    pub async fn split_async(&self, secret: Vec<u8>) -> CryptoResult<Vec<Share>> {
+        let mut sharing = Self { config: self.config.clone(), audit: self.audit.clone() };
+        tokio::task::spawn_blocking(move || sharing.split(&secret))
+            .await
+            .map_err(|e| CryptoError::InvalidState(format!("Blocking split task failed: {}", e)))?
+    }
 
-        Ok(padded)
+    /// Reconstructs the secret on the blocking thread pool
+    #[cfg(feature = "async")]
+    pub async fn reconstruct_async(&self, shares: Vec<Share>) -> CryptoResult<Vec<u8>> {
+        let sharing = Self { config: self.config.clone(), audit: self.audit.clone() };
+        tokio::task::spawn_blocking(move || sharing.reconstruct(&shares))
+            .await
+            .map_err(|e| CryptoError::InvalidState(format!("Blocking reconstruct task failed: {}", e)))?
     }
 
+    // Private helper methods
+
     #[cfg(feature = "parallel")]
-    fn split_parallel(&mut self, data: &[u8]) -> CryptoResult<Vec<Share>> {
+    fn split_parallel(&mut self, data: &[u8], token: Option<&CancellationToken>) -> CryptoResult<Vec<Share>> {
         let block_size = self.config.block_size;
-        let num_blocks = (data.len() + block_size - 1) / block_size;
 
         // Process blocks in parallel
         let blocks: Vec<_> = data.chunks(block_size)
@@ -173,66 +261,33 @@ impl ThreePartySecretSharing {
 
         let share_blocks: Vec<(Vec<u8>, Vec<u8>, Vec<u8>)> = blocks.par_iter()
             .map(|block| {
-                let mut rng = rand::thread_rng();
-                let share_a: Vec<u8> = (0..block.len()).map(|_| rng.gen()).collect();
-                let share_b: Vec<u8> = (0..block.len()).map(|_| rng.gen()).collect();
-
-                // Calculate share_c
-                let mut share_c = vec![0u8; block.len()];
-                for i in 0..block.len() {
-                    share_c[i] = block[i] ^ share_a[i] ^ share_b[i];
-                }
-
-                (share_a, share_b, share_c)
+                check_token(token)?;
+                Ok(split_block(block))
             })
-            .collect();
-
-        // Combine blocks for each share
-        let mut share_a = Vec::with_capacity(data.len());
-        let mut share_b = Vec::with_capacity(data.len());
-        let mut share_c = Vec::with_capacity(data.len());
+            .collect::<CryptoResult<_>>()?;
 
-        for (a, b, c) in share_blocks {
-            share_a.extend(a);
-            share_b.extend(b);
-            share_c.extend(c);
-        }
-
-        Ok(vec![
-            Share::new(share_a, 0),
-            Share::new(share_b, 1),
-            Share::new(share_c, 2),
-        ])
+        Ok(join_share_blocks(share_blocks, data.len()))
     }
 
     #[cfg(not(feature = "parallel"))]
-    fn split_parallel(&mut self, data: &[u8]) -> CryptoResult<Vec<Share>> {
+    fn split_parallel(&mut self, data: &[u8], token: Option<&CancellationToken>) -> CryptoResult<Vec<Share>> {
         // Fallback to sequential if parallel feature is not enabled
-        self.split_sequential(data)
+        self.split_sequential(data, token)
     }
 
-    fn split_sequential(&mut self, data: &[u8]) -> CryptoResult<Vec<Share>> {
-        let mut rng = rand::thread_rng();
-
-        // Generate random shares A and B
-        let share_a: Vec<u8> = (0..data.len()).map(|_| rng.gen()).collect();
-        let share_b: Vec<u8> = (0..data.len()).map(|_| rng.gen()).collect();
-
-        // Calculate share C
-        let mut share_c = vec![0u8; data.len()];
-        for i in 0..data.len() {
-            share_c[i] = data[i] ^ share_a[i] ^ share_b[i];
-        }
+    fn split_sequential(&mut self, data: &[u8], token: Option<&CancellationToken>) -> CryptoResult<Vec<Share>> {
+        let share_blocks = data.chunks(self.config.block_size.max(1))
+            .map(|block| {
+                check_token(token)?;
+                Ok(split_block(block))
+            })
+            .collect::<CryptoResult<Vec<_>>>()?;
 
-        Ok(vec![
-            Share::new(share_a, 0),
-            Share::new(share_b, 1),
-            Share::new(share_c, 2),
-        ])
+        Ok(join_share_blocks(share_blocks, data.len()))
     }
 
     #[cfg(feature = "parallel")]
-    fn reconstruct_parallel(&self, shares: &[Share]) -> CryptoResult<Vec<u8>> {
+    fn reconstruct_parallel(&self, shares: &[Share], token: Option<&CancellationToken>) -> CryptoResult<Vec<u8>> {
         let block_size = self.config.block_size;
         let blocks_a: Vec<_> = shares[0].data.chunks(block_size).collect();
         let blocks_b: Vec<_> = shares[1].data.chunks(block_size).collect();
@@ -242,39 +297,79 @@ impl ThreePartySecretSharing {
             .zip(blocks_b.par_iter())
             .zip(blocks_c.par_iter())
             .map(|((a, b), c)| {
-                let mut result = vec![0u8; a.len()];
-                for i in 0..a.len() {
-                    result[i] = a[i] ^ b[i] ^ c[i];
-                }
-                result
+                check_token(token)?;
+                Ok(xor_block(a, b, c))
             })
-            .collect();
-
-        let mut result = Vec::with_capacity(shares[0].data.len());
-        for block in reconstructed_blocks {
-            result.extend(block);
-        }
+            .collect::<CryptoResult<_>>()?;
 
-        Ok(result)
+        Ok(reconstructed_blocks.concat())
     }
 
     #[cfg(not(feature = "parallel"))]
-    fn reconstruct_parallel(&self, shares: &[Share]) -> CryptoResult<Vec<u8>> {
+    fn reconstruct_parallel(&self, shares: &[Share], token: Option<&CancellationToken>) -> CryptoResult<Vec<u8>> {
         // Fallback to sequential if parallel feature is not enabled
-        self.reconstruct_sequential(shares)
+        self.reconstruct_sequential(shares, token)
     }
 
-    fn reconstruct_sequential(&self, shares: &[Share]) -> CryptoResult<Vec<u8>> {
-        let mut result = vec![0u8; shares[0].data.len()];
+    fn reconstruct_sequential(&self, shares: &[Share], token: Option<&CancellationToken>) -> CryptoResult<Vec<u8>> {
+        let block_size = self.config.block_size.max(1);
+        let mut result = Vec::with_capacity(shares[0].data.len());
 
-        for i in 0..shares[0].data.len() {
-            result[i] = shares[0].data[i] ^ shares[1].data[i] ^ shares[2].data[i];
+        for ((a, b), c) in shares[0].data.chunks(block_size)
+            .zip(shares[1].data.chunks(block_size))
+            .zip(shares[2].data.chunks(block_size))
+        {
+            check_token(token)?;
+            result.extend(xor_block(a, b, c));
         }
 
         Ok(result)
     }
 }
 
+/// Checks an optional cancellation token between blocks
+fn check_token(token: Option<&CancellationToken>) -> CryptoResult<()> {
+    token.map_or(Ok(()), CancellationToken::check)
+}
+
+/// Splits one block into three random shares whose XOR is the block
+fn split_block(block: &[u8]) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    let mut rng = rand::thread_rng();
+    let share_a: Vec<u8> = (0..block.len()).map(|_| rng.gen()).collect();
+    let share_b: Vec<u8> = (0..block.len()).map(|_| rng.gen()).collect();
+
+    // Calculate share_c
+    let share_c = xor_block(block, &share_a, &share_b);
+
+    (share_a, share_b, share_c)
+}
+
+/// Concatenates per-block shares into the three full shares
+fn join_share_blocks(share_blocks: Vec<(Vec<u8>, Vec<u8>, Vec<u8>)>, len: usize) -> Vec<Share> {
+    let mut share_a = Vec::with_capacity(len);
+    let mut share_b = Vec::with_capacity(len);
+    let mut share_c = Vec::with_capacity(len);
+
+    for (a, b, c) in share_blocks {
+        share_a.extend(a);
+        share_b.extend(b);
+        share_c.extend(c);
+    }
+
+    vec![
+        Share::new(share_a, 0),
+        Share::new(share_b, 1),
+        Share::new(share_c, 2),
+    ]
+}
+
+fn xor_block(a: &[u8], b: &[u8], c: &[u8]) -> Vec<u8> {
+    a.iter()
+        .zip(b.iter().zip(c))
+        .map(|(x, (y, z))| x ^ y ^ z)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -331,6 +426,72 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_relabelled_share_rejected() -> CryptoResult<()> {
+        let mut sharing = ThreePartySecretSharing::default();
+        let mut shares = sharing.split(b"Test secret")?;
+
+        // Share 0 presented as share 1, keeping its recorded hash
+        let relabelled = Share::from_parts(shares[0].data.clone(), 1, shares[0].hash);
+        assert!(!relabelled.verify());
+
+        shares[1] = relabelled;
+        assert!(matches!(sharing.reconstruct(&shares), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_sharing() -> CryptoResult<()> {
+        let sharing = ThreePartySecretSharing::default();
+        let secret = b"Offloaded to the blocking pool".to_vec();
+
+        let shares = sharing.split_async(secret.clone()).await?;
+        assert_eq!(shares.len(), 3);
+        assert_eq!(sharing.reconstruct_async(shares.clone()).await?, secret);
+
+        assert!(matches!(
+            sharing.split_async(Vec::new()).await,
+            Err(CryptoError::InvalidInput(_))
+        ));
+        assert!(sharing.reconstruct_async(shares[..2].to_vec()).await.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cancelled_sharing() -> CryptoResult<()> {
+        let mut sharing = ThreePartySecretSharing::default();
+        let secret: Vec<u8> = (0..200_000).map(|i| (i % 251) as u8).collect();
+        let token = CancellationToken::new();
+
+        let shares = sharing.split_with(&secret, &token)?;
+        assert_eq!(sharing.reconstruct_with(&shares, &token)?, secret);
+
+        token.cancel();
+        assert!(matches!(sharing.split_with(&secret, &token), Err(CryptoError::Cancelled)));
+        assert!(matches!(sharing.reconstruct_with(&shares, &token), Err(CryptoError::Cancelled)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sharing_deadline() -> CryptoResult<()> {
+        use crate::crypto::utils::ManualClock;
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        let clock = ManualClock::new();
+        let token = CancellationToken::with_clock(Arc::new(clock.clone()))
+            .with_timeout(Duration::from_secs(1));
+        clock.advance(Duration::from_secs(2));
+
+        let mut sharing = ThreePartySecretSharing::default();
+        match sharing.split_with(b"Too late", &token) {
+            Err(CryptoError::Timeout(elapsed)) => assert_eq!(elapsed, Duration::from_secs(2)),
+            other => panic!("expected timeout, got {:?}", other),
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "parallel")]
     #[test]
     fn test_parallel_large_message() -> CryptoResult<()> {
@@ -350,6 +511,11 @@ mod tests {
 
         let reconstructed = sharing.reconstruct(&shares)?;
         assert_eq!(reconstructed, secret);
+
+        let token = CancellationToken::new();
+        token.cancel();
+        assert!(matches!(sharing.split_with(&secret, &token), Err(CryptoError::Cancelled)));
+        assert!(matches!(sharing.reconstruct_with(&shares, &token), Err(CryptoError::Cancelled)));
         Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,297 @@
+//! Passphrase-encrypted filesystem share store
+//! Location: src/crypto/store/encrypted.rs
+//!
+//! Uses the `FileShareStore` layout, but every record is sealed with
+//! ChaCha20-Poly1305 under a key stretched from a passphrase with Argon2id.
+//! The record's secret and share id are bound in as associated data, so
+//! records cannot be swapped between keys. The salt and Argon2 cost live in
+//! `<root>/store.key` together with a check value that rejects a wrong
+//! passphrase up front.
+
+use super::{decode_record, encode_record, file::FileShareStore, ShareStore};
+use crate::crypto::protocol::SecretId;
+use crate::crypto::sharing::Share;
+use crate::crypto::utils::{self, codec::{self, Decoder, Encoder}};
+use crate::error::{CryptoError, CryptoResult};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use std::fmt;
+use std::path::Path;
+use tracing::{debug, info};
+
+/// Name of the file holding the key derivation parameters
+const KEY_FILE: &str = "store.key";
+/// Key file magic
+const KEY_MAGIC: &[u8; 8] = b"MCSTOREK";
+/// Key file format version
+const KEY_VERSION: u32 = 1;
+/// Argon2 salt length
+const SALT_LEN: usize = 16;
+/// ChaCha20-Poly1305 nonce length
+const NONCE_LEN: usize = 12;
+/// Largest Argon2 memory cost accepted, 1 GiB, so a crafted key file cannot
+/// make opening the store allocate without bound
+pub const MAX_MEMORY_KIB: u32 = 1 << 20;
+
+/// Argon2id cost used when a new store is created
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PassphraseParams {
+    /// Memory cost in KiB
+    pub memory_kib: u32,
+    /// Number of passes over memory
+    pub iterations: u32,
+    /// Degree of parallelism
+    pub parallelism: u32,
+}
+
+impl Default for PassphraseParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl PassphraseParams {
+    /// Rejects a memory cost above `MAX_MEMORY_KIB`
+    fn check(&self) -> CryptoResult<()> {
+        if self.memory_kib > MAX_MEMORY_KIB {
+            return Err(CryptoError::InvalidInput(format!(
+                "Argon2 memory cost {} KiB exceeds the {} KiB limit",
+                self.memory_kib, MAX_MEMORY_KIB
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Share store encrypting each record under a passphrase-derived key
+pub struct EncryptedShareStore {
+    files: FileShareStore,
+    cipher: ChaCha20Poly1305,
+}
+
+impl EncryptedShareStore {
+    /// Opens or creates a store with the default Argon2id cost
+    pub fn open(root: impl AsRef<Path>, passphrase: &[u8]) -> CryptoResult<Self> {
+        Self::open_with(root, passphrase, PassphraseParams::default())
+    }
+
+    /// Opens a store, or creates one with `params` if none exists at `root`
+    ///
+    /// An existing store keeps the parameters it was created with.
+    pub fn open_with(root: impl AsRef<Path>, passphrase: &[u8], params: PassphraseParams) -> CryptoResult<Self> {
+        if passphrase.is_empty() {
+            return Err(CryptoError::InvalidInput("Passphrase must not be empty".into()));
+        }
+        let files = FileShareStore::open(root)?;
+        let key_path = files.root().join(KEY_FILE);
+
+        let cipher = match std::fs::read(&key_path) {
+            Ok(sealed) => {
+                let (salt, params, check) = decode_key_file(&sealed)?;
+                let cipher = derive_cipher(passphrase, &salt, params)?;
+                if cipher.decrypt(&Nonce::default(), Payload { msg: &check, aad: KEY_MAGIC }).is_err() {
+                    return Err(CryptoError::VerificationFailed("Wrong passphrase for share store".into()));
+                }
+                debug!(root = %files.root().display(), "Unlocked encrypted share store");
+                cipher
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                params.check()?;
+                let salt: [u8; SALT_LEN] = rand::random();
+                let cipher = derive_cipher(passphrase, &salt, params)?;
+                // The key encrypts this fixed message once, so the fixed nonce is never reused
+                let check = cipher.encrypt(&Nonce::default(), Payload { msg: &[], aad: KEY_MAGIC })
+                    .map_err(|_| CryptoError::InvalidState("Failed to encrypt key check".into()))?;
+                utils::write_atomic_private(&key_path, &encode_key_file(&salt, params, &check))?;
+                info!(root = %files.root().display(), "Created encrypted share store");
+                cipher
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(Self { files, cipher })
+    }
+
+    /// Directory holding the store
+    pub fn root(&self) -> &Path {
+        self.files.root()
+    }
+}
+
+impl fmt::Debug for EncryptedShareStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptedShareStore")
+            .field("root", &self.files.root())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ShareStore for EncryptedShareStore {
+    fn put(&mut self, secret: SecretId, share: &Share) -> CryptoResult<()> {
+        let share = super::verified(&secret, share.clone())?;
+        let nonce: [u8; NONCE_LEN] = rand::random();
+        let aad = associated_data(&secret, share.id());
+        let ciphertext = self.cipher
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: &encode_record(&secret, &share), aad: &aad })
+            .map_err(|_| CryptoError::InvalidState("Failed to encrypt share".into()))?;
+
+        let mut record = nonce.to_vec();
+        record.extend_from_slice(&ciphertext);
+        self.files.write_record(&secret, share.id(), &record)?;
+        debug!(%secret, share = share.id(), "Stored encrypted share");
+        Ok(())
+    }
+
+    fn get(&self, secret: &SecretId, share_id: u8) -> CryptoResult<Option<Share>> {
+        let Some(record) = self.files.read_record(secret, share_id)? else {
+            return Ok(None);
+        };
+        if record.len() < NONCE_LEN {
+            return Err(CryptoError::VerificationFailed("Encrypted share record too short".into()));
+        }
+        let (nonce, ciphertext) = record.split_at(NONCE_LEN);
+        let aad = associated_data(secret, share_id);
+        let plaintext = self.cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: &aad })
+            .map_err(|_| CryptoError::VerificationFailed(
+                format!("Share {} of {} failed to decrypt", share_id, secret)
+            ))?;
+        decode_record(&plaintext, secret, share_id).map(Some)
+    }
+
+    fn list(&self) -> CryptoResult<Vec<(SecretId, u8)>> {
+        self.files.keys()
+    }
+
+    fn delete(&mut self, secret: &SecretId, share_id: u8) -> CryptoResult<bool> {
+        self.files.remove_record(secret, share_id)
+    }
+}
+
+/// Binds a ciphertext to the key it is stored under
+fn associated_data(secret: &SecretId, share_id: u8) -> Vec<u8> {
+    let mut aad = secret.as_bytes().to_vec();
+    aad.push(share_id);
+    aad
+}
+
+fn derive_cipher(passphrase: &[u8], salt: &[u8], params: PassphraseParams) -> CryptoResult<ChaCha20Poly1305> {
+    let argon_params = Params::new(params.memory_kib, params.iterations, params.parallelism, Some(32))
+        .map_err(|e| CryptoError::InvalidInput(format!("Invalid passphrase parameters: {}", e)))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, argon_params)
+        .hash_password_into(passphrase, salt, &mut key)
+        .map_err(|e| CryptoError::InvalidInput(format!("Passphrase derivation failed: {}", e)))?;
+    Ok(ChaCha20Poly1305::new(&key.into()))
+}
+
+fn encode_key_file(salt: &[u8; SALT_LEN], params: PassphraseParams, check: &[u8]) -> Vec<u8> {
+    let mut encoder = Encoder::new();
+    encoder
+        .put_raw(KEY_MAGIC)
+        .put_u32(KEY_VERSION)
+        .put_raw(salt)
+        .put_u32(params.memory_kib)
+        .put_u32(params.iterations)
+        .put_u32(params.parallelism)
+        .put_bytes(check);
+    codec::seal(encoder.finish())
+}
+
+fn decode_key_file(sealed: &[u8]) -> CryptoResult<([u8; SALT_LEN], PassphraseParams, Vec<u8>)> {
+    let mut decoder = Decoder::new(codec::unseal(sealed)?);
+    if decoder.get_raw(KEY_MAGIC.len())? != KEY_MAGIC {
+        return Err(CryptoError::InvalidInput("Not a share store key file".into()));
+    }
+    let version = decoder.get_u32()?;
+    if version != KEY_VERSION {
+        return Err(CryptoError::InvalidInput(format!("Unsupported key file version {}", version)));
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    salt.copy_from_slice(decoder.get_raw(SALT_LEN)?);
+    let params = PassphraseParams {
+        memory_kib: decoder.get_u32()?,
+        iterations: decoder.get_u32()?,
+        parallelism: decoder.get_u32()?,
+    };
+    params.check()?;
+    let check = decoder.get_bytes()?.to_vec();
+    decoder.finish()?;
+    Ok((salt, params, check))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::utils::temp::TempDir;
+    use std::fs;
+
+    /// Cheap Argon2 cost so tests stay fast
+    const TEST_PARAMS: PassphraseParams = PassphraseParams { memory_kib: 64, iterations: 1, parallelism: 1 };
+
+    #[test]
+    fn test_encrypted_store_roundtrip() -> CryptoResult<()> {
+        let dir = TempDir::new("sealed")?;
+        let root = dir.join("store");
+        let id = SecretId::random();
+        let share = Share::new(b"very recognisable share contents".to_vec(), 1);
+
+        let mut store = EncryptedShareStore::open_with(&root, b"correct horse", TEST_PARAMS)?;
+        store.put(id, &share)?;
+        let on_disk = fs::read(root.join(id.to_string()).join("1.share"))?;
+        assert!(!on_disk.windows(share.data().len()).any(|w| w == share.data()));
+
+        let store = EncryptedShareStore::open_with(&root, b"correct horse", PassphraseParams::default())?;
+        assert_eq!(store.list()?, vec![(id, 1)]);
+        assert_eq!(store.get(&id, 1)?.map(|s| s.data().to_vec()), Some(share.data().to_vec()));
+
+        assert!(matches!(
+            EncryptedShareStore::open_with(&root, b"wrong horse", TEST_PARAMS),
+            Err(CryptoError::VerificationFailed(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_encrypted_records_bound_to_key() -> CryptoResult<()> {
+        let dir = TempDir::new("sealed")?;
+        let root = dir.join("store");
+        let (a, b) = (SecretId::random(), SecretId::random());
+        let mut store = EncryptedShareStore::open_with(&root, b"passphrase", TEST_PARAMS)?;
+        store.put(a, &Share::new(vec![1; 32], 0))?;
+        store.put(b, &Share::new(vec![2; 32], 0))?;
+
+        let path = |id: &SecretId| root.join(id.to_string()).join("0.share");
+        fs::copy(path(&b), path(&a))?;
+        assert!(matches!(store.get(&a, 0), Err(CryptoError::VerificationFailed(_))));
+        assert!(store.get(&b, 0)?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_cost_capped() -> CryptoResult<()> {
+        let dir = TempDir::new("sealed")?;
+        let root = dir.join("store");
+        let huge = PassphraseParams { memory_kib: MAX_MEMORY_KIB + 1, ..TEST_PARAMS };
+        assert!(matches!(
+            EncryptedShareStore::open_with(&root, b"passphrase", huge),
+            Err(CryptoError::InvalidInput(_))
+        ));
+
+        // A key file edited to demand more memory is refused before deriving
+        EncryptedShareStore::open_with(&root, b"passphrase", TEST_PARAMS)?;
+        let (salt, _, check) = decode_key_file(&fs::read(root.join(KEY_FILE))?)?;
+        fs::write(root.join(KEY_FILE), encode_key_file(&salt, huge, &check))?;
+        assert!(matches!(
+            EncryptedShareStore::open(&root, b"passphrase"),
+            Err(CryptoError::InvalidInput(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+//! Filesystem share store
+//! Location: src/crypto/store/file.rs
+//!
+//! Shares live at `<root>/<secret id>/<share id>.share`. Records are written
+//! atomically with owner-only permissions, and directories are synced after
+//! every change so a crash cannot lose an acknowledged write.
+
+use super::{decode_record, encode_record, ShareStore};
+use crate::crypto::protocol::SecretId;
+use crate::crypto::sharing::Share;
+use crate::crypto::utils;
+use crate::error::CryptoResult;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Extension of share record files
+const SHARE_EXTENSION: &str = "share";
+
+/// Share store keeping one file per share under a root directory
+#[derive(Debug, Clone)]
+pub struct FileShareStore {
+    root: PathBuf,
+}
+
+impl FileShareStore {
+    /// Opens a store rooted at `root`, creating the directory if needed
+    pub fn open(root: impl AsRef<Path>) -> CryptoResult<Self> {
+        let root = root.as_ref().to_path_buf();
+        create_private_dir(&root)?;
+        debug!(root = %root.display(), "Opened share store");
+        Ok(Self { root })
+    }
+
+    /// Directory holding the store
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn secret_dir(&self, secret: &SecretId) -> PathBuf {
+        self.root.join(secret.to_string())
+    }
+
+    fn share_path(&self, secret: &SecretId, share_id: u8) -> PathBuf {
+        self.secret_dir(secret).join(format!("{}.{}", share_id, SHARE_EXTENSION))
+    }
+
+    /// Durably writes the raw record for a share
+    pub(super) fn write_record(&self, secret: &SecretId, share_id: u8, record: &[u8]) -> CryptoResult<()> {
+        let dir = self.secret_dir(secret);
+        if !dir.is_dir() {
+            create_private_dir(&dir)?;
+            sync_dir(&self.root)?;
+        }
+        utils::write_atomic_private(&self.share_path(secret, share_id), record)?;
+        sync_dir(&dir)?;
+        Ok(())
+    }
+
+    /// Reads the raw record for a share, if present
+    pub(super) fn read_record(&self, secret: &SecretId, share_id: u8) -> CryptoResult<Option<Vec<u8>>> {
+        match fs::read(self.share_path(secret, share_id)) {
+            Ok(record) => Ok(Some(record)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Removes the record for a share, and its secret's directory once empty
+    pub(super) fn remove_record(&self, secret: &SecretId, share_id: u8) -> CryptoResult<bool> {
+        let dir = self.secret_dir(secret);
+        match fs::remove_file(self.share_path(secret, share_id)) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err.into()),
+        }
+        sync_dir(&dir)?;
+        if fs::remove_dir(&dir).is_ok() {
+            sync_dir(&self.root)?;
+        }
+        Ok(true)
+    }
+
+    /// Keys of every record in the store, skipping unrelated files
+    pub(super) fn keys(&self) -> CryptoResult<Vec<(SecretId, u8)>> {
+        let mut keys = Vec::new();
+        for entry in fs::read_dir(&self.root)? {
+            let entry = entry?;
+            let Some(secret) = entry.file_name().to_str().and_then(|name| name.parse::<SecretId>().ok()) else {
+                continue;
+            };
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            for file in fs::read_dir(entry.path())? {
+                let path = file?.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some(SHARE_EXTENSION) {
+                    continue;
+                }
+                if let Some(id) = path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.parse().ok()) {
+                    keys.push((secret, id));
+                }
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+impl ShareStore for FileShareStore {
+    fn put(&mut self, secret: SecretId, share: &Share) -> CryptoResult<()> {
+        let share = super::verified(&secret, share.clone())?;
+        self.write_record(&secret, share.id(), &encode_record(&secret, &share))?;
+        debug!(%secret, share = share.id(), "Stored share on disk");
+        Ok(())
+    }
+
+    fn get(&self, secret: &SecretId, share_id: u8) -> CryptoResult<Option<Share>> {
+        self.read_record(secret, share_id)?
+            .map(|record| decode_record(&record, secret, share_id))
+            .transpose()
+    }
+
+    fn list(&self) -> CryptoResult<Vec<(SecretId, u8)>> {
+        self.keys()
+    }
+
+    fn delete(&mut self, secret: &SecretId, share_id: u8) -> CryptoResult<bool> {
+        self.remove_record(secret, share_id)
+    }
+}
+
+/// Creates a directory (and parents) accessible only by its owner
+fn create_private_dir(path: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(path)
+}
+
+/// Flushes directory entries so renames and removals survive a crash
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn sync_dir(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    fs::File::open(path)?.sync_all()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::utils::temp::TempDir;
+    use crate::error::CryptoError;
+
+    #[test]
+    fn test_file_store_survives_reopen() -> CryptoResult<()> {
+        let dir = TempDir::new("shares")?;
+        let root = dir.join("store");
+        let id = SecretId::random();
+        let share = Share::new(vec![0x42; 1000], 2);
+
+        let mut store = FileShareStore::open(&root)?;
+        store.put(id, &share)?;
+        fs::write(root.join("notes.txt"), b"not a share")?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(store.share_path(&id, 2))?.permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let mut store = FileShareStore::open(&root)?;
+        assert_eq!(store.list()?, vec![(id, 2)]);
+        assert_eq!(store.get(&id, 2)?.map(|s| s.data().to_vec()), Some(share.data().to_vec()));
+
+        assert!(store.delete(&id, 2)?);
+        assert!(store.list()?.is_empty());
+        assert!(!store.secret_dir(&id).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_store_detects_tampering() -> CryptoResult<()> {
+        let dir = TempDir::new("shares")?;
+        let root = dir.join("store");
+        let (a, b) = (SecretId::random(), SecretId::random());
+        let mut store = FileShareStore::open(&root)?;
+        store.put(a, &Share::new(vec![1; 64], 0))?;
+        store.put(b, &Share::new(vec![2; 64], 0))?;
+
+        // Flipping a byte breaks the record seal
+        let path = store.share_path(&a, 0);
+        let mut record = fs::read(&path)?;
+        record[30] ^= 1;
+        fs::write(&path, &record)?;
+        assert!(matches!(store.get(&a, 0), Err(CryptoError::VerificationFailed(_))));
+
+        // A valid record moved under another secret is refused
+        fs::copy(store.share_path(&b, 0), &path)?;
+        assert!(matches!(store.get(&a, 0), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+}
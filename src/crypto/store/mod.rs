@@ -0,0 +1,165 @@
+//! Persistent storage for shares, keyed by secret and share id
+//! Location: src/crypto/store/mod.rs
+//!
+//! Every backend checks `Share::verify` on the way in and on every read, so a
+//! share that was corrupted at rest is reported instead of handed back.
+
+pub mod encrypted;
+pub mod file;
+
+pub use encrypted::{EncryptedShareStore, PassphraseParams};
+pub use file::FileShareStore;
+
+use crate::crypto::protocol::SecretId;
+use crate::crypto::sharing::Share;
+use crate::crypto::utils::codec::{self, Decoder, Encoder};
+use crate::error::{CryptoError, CryptoResult};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use tracing::warn;
+
+/// Share record magic
+const RECORD_MAGIC: &[u8; 8] = b"MCSHARES";
+/// Share record format version
+const RECORD_VERSION: u32 = 2;
+
+/// Storage backend for shares
+pub trait ShareStore: Send + Debug {
+    /// Stores a share of `secret`, replacing any share with the same id
+    fn put(&mut self, secret: SecretId, share: &Share) -> CryptoResult<()>;
+
+    /// Reads a verified share, or `None` if it is not stored
+    fn get(&self, secret: &SecretId, share_id: u8) -> CryptoResult<Option<Share>>;
+
+    /// Every stored `(secret, share id)` pair, in order
+    fn list(&self) -> CryptoResult<Vec<(SecretId, u8)>>;
+
+    /// Removes a share; returns false if it was not stored
+    fn delete(&mut self, secret: &SecretId, share_id: u8) -> CryptoResult<bool>;
+}
+
+/// Share store held in memory, lost on restart
+#[derive(Debug, Default)]
+pub struct MemoryShareStore {
+    shares: BTreeMap<(SecretId, u8), Share>,
+}
+
+impl MemoryShareStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ShareStore for MemoryShareStore {
+    fn put(&mut self, secret: SecretId, share: &Share) -> CryptoResult<()> {
+        let share = verified(&secret, share.clone())?;
+        self.shares.insert((secret, share.id()), share);
+        Ok(())
+    }
+
+    fn get(&self, secret: &SecretId, share_id: u8) -> CryptoResult<Option<Share>> {
+        self.shares.get(&(*secret, share_id))
+            .map(|share| verified(secret, share.clone()))
+            .transpose()
+    }
+
+    fn list(&self) -> CryptoResult<Vec<(SecretId, u8)>> {
+        Ok(self.shares.keys().copied().collect())
+    }
+
+    fn delete(&mut self, secret: &SecretId, share_id: u8) -> CryptoResult<bool> {
+        Ok(self.shares.remove(&(*secret, share_id)).is_some())
+    }
+}
+
+/// Passes a share through only if its data still matches its hash
+fn verified(secret: &SecretId, share: Share) -> CryptoResult<Share> {
+    if !share.verify() {
+        warn!(%secret, share = share.id(), "Stored share failed verification");
+        return Err(CryptoError::VerificationFailed(
+            format!("Share {} of {} failed verification", share.id(), secret)
+        ));
+    }
+    Ok(share)
+}
+
+/// Serializes a share together with the key it is stored under
+fn encode_record(secret: &SecretId, share: &Share) -> Vec<u8> {
+    let mut encoder = Encoder::new();
+    encoder
+        .put_raw(RECORD_MAGIC)
+        .put_u32(RECORD_VERSION)
+        .put_raw(secret.as_bytes())
+        .put_raw(&[share.id()])
+        .put_bytes(share.data())
+        .put_raw(share.hash());
+    codec::seal(encoder.finish())
+}
+
+/// Parses a record, rejecting one filed under a different key or failing verification
+fn decode_record(sealed: &[u8], secret: &SecretId, share_id: u8) -> CryptoResult<Share> {
+    let mut decoder = Decoder::new(codec::unseal(sealed)?);
+    if decoder.get_raw(RECORD_MAGIC.len())? != RECORD_MAGIC {
+        return Err(CryptoError::InvalidInput("Not a share record".into()));
+    }
+    let version = decoder.get_u32()?;
+    if version != RECORD_VERSION {
+        return Err(CryptoError::InvalidInput(format!("Unsupported share record version {}", version)));
+    }
+
+    let stored_secret = decoder.get_raw(secret.as_bytes().len())?;
+    let stored_id = decoder.get_raw(1)?[0];
+    if stored_secret != secret.as_bytes() || stored_id != share_id {
+        return Err(CryptoError::VerificationFailed(
+            format!("Record for share {} of {} holds a different share", share_id, secret)
+        ));
+    }
+    let data = decoder.get_bytes()?.to_vec();
+    let hash = decoder.get_array32()?;
+    decoder.finish()?;
+
+    verified(secret, Share::from_parts(data, share_id, hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::sharing::ThreePartySecretSharing;
+
+    #[test]
+    fn test_memory_store() -> CryptoResult<()> {
+        let mut store = MemoryShareStore::new();
+        let shares = ThreePartySecretSharing::default().split(b"secret")?;
+        let (a, b) = (SecretId::random(), SecretId::random());
+
+        store.put(a, &shares[0])?;
+        store.put(b, &shares[2])?;
+        assert_eq!(store.get(&a, 0)?.map(|s| s.data().to_vec()), Some(shares[0].data().to_vec()));
+        assert!(store.get(&a, 2)?.is_none());
+        assert_eq!(store.list()?.len(), 2);
+
+        assert!(store.delete(&a, 0)?);
+        assert!(!store.delete(&a, 0)?);
+        assert_eq!(store.list()?, vec![(b, 2)]);
+
+        let corrupt = Share::from_parts(vec![1; 16], 1, [0; 32]);
+        assert!(matches!(store.put(a, &corrupt), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_bound_to_key() -> CryptoResult<()> {
+        let id = SecretId::random();
+        let share = Share::new(vec![9; 64], 1);
+        let record = encode_record(&id, &share);
+
+        assert_eq!(decode_record(&record, &id, 1)?.data(), share.data());
+        assert!(matches!(decode_record(&record, &id, 2), Err(CryptoError::VerificationFailed(_))));
+        assert!(matches!(decode_record(&record, &SecretId::random(), 1), Err(CryptoError::VerificationFailed(_))));
+
+        // A record whose share no longer matches its hash is refused even if resealed
+        let forged = encode_record(&id, &Share::from_parts(vec![8; 64], 1, *share.hash()));
+        assert!(matches!(decode_record(&forged, &id, 1), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+}
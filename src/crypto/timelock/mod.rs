@@ -0,0 +1,357 @@
+//! Rivest–Shamir–Wagner time-lock puzzles for timed release of shares
+//! Location: src/crypto/timelock/mod.rs
+//!
+//! A creator who knows the factorisation of an RSA modulus `n` computes
+//! `a^(2^T) mod n` with a single exponentiation by reducing `2^T` modulo
+//! `phi(n)`. Anyone else has to perform the `T` squarings one after another.
+//! The result masks a 32-byte key, which in turn encrypts a share with
+//! ChaCha20-Poly1305, so a corrupted ciphertext fails to unlock.
+
+use crate::crypto::sharing::{Share, ThreePartySecretSharing};
+use crate::error::{CryptoError, CryptoResult};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use num_bigint_dig::{BigUint, RandBigInt, RandPrime};
+use sha2::{Sha256, Digest};
+use std::time::Instant;
+use tracing::{debug, info};
+
+/// Default size of the RSA modulus in bits
+pub const DEFAULT_MODULUS_BITS: usize = 2048;
+/// Smallest modulus accepted when generating a trapdoor
+pub const MIN_MODULUS_BITS: usize = 256;
+
+/// Domain tag for deriving the key mask from the puzzle solution
+const MASK_DOMAIN: &[u8] = b"mcafee/timelock/mask/v1";
+/// Domain tag for the key check value
+const CHECK_DOMAIN: &[u8] = b"mcafee/timelock/check/v1";
+/// Domain tag authenticated alongside each encrypted share
+const SHARE_DOMAIN: &[u8] = b"mcafee/timelock/share/v1";
+
+/// Holder of the trapdoor, able to lock keys without doing the squarings
+pub struct TimeLockCreator {
+    /// Public RSA modulus n = p * q
+    modulus: BigUint,
+    /// Euler totient (p - 1)(q - 1), the trapdoor
+    totient: BigUint,
+}
+
+impl std::fmt::Debug for TimeLockCreator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TimeLockCreator")
+            .field("modulus_bits", &self.modulus.bits())
+            .finish_non_exhaustive()
+    }
+}
+
+impl TimeLockCreator {
+    /// Generates a fresh modulus of the given size
+    pub fn generate(modulus_bits: usize) -> CryptoResult<Self> {
+        if modulus_bits < MIN_MODULUS_BITS {
+            return Err(CryptoError::InvalidInput(
+                format!("Modulus must be at least {} bits", MIN_MODULUS_BITS)
+            ));
+        }
+
+        let start = Instant::now();
+        let mut rng = rand::thread_rng();
+        let one = BigUint::from(1u32);
+        let (p, q) = loop {
+            let p = rng.gen_prime(modulus_bits / 2);
+            let q = rng.gen_prime(modulus_bits - modulus_bits / 2);
+            if p != q {
+                break (p, q);
+            }
+        };
+
+        let modulus = &p * &q;
+        let totient = (&p - &one) * (&q - &one);
+        debug!(bits = modulus.bits(), elapsed = ?start.elapsed(), "Generated time-lock modulus");
+
+        Ok(Self { modulus, totient })
+    }
+
+    /// Public modulus puzzles are created under
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// Locks a key so that recovering it takes `squarings` sequential squarings
+    pub fn lock(&self, key: &[u8; 32], squarings: u64) -> CryptoResult<TimeLockPuzzle> {
+        if squarings == 0 {
+            return Err(CryptoError::InvalidInput("Squaring count must be at least 1".into()));
+        }
+
+        let two = BigUint::from(2u32);
+        let base = rand::thread_rng().gen_biguint_range(&two, &(&self.modulus - 1u32));
+
+        // Shortcut through the trapdoor: a^(2^T) = a^(2^T mod phi) (mod n)
+        let exponent = two.modpow(&BigUint::from(squarings), &self.totient);
+        let solution = base.modpow(&exponent, &self.modulus);
+
+        let mask = derive_mask(&self.modulus, &solution);
+        let mut locked_key = [0u8; 32];
+        for (locked, (k, m)) in locked_key.iter_mut().zip(key.iter().zip(&mask)) {
+            *locked = k ^ m;
+        }
+
+        Ok(TimeLockPuzzle {
+            modulus: self.modulus.clone(),
+            base,
+            squarings,
+            locked_key,
+            key_check: key_check(key),
+        })
+    }
+}
+
+/// Public puzzle hiding a 32-byte key behind sequential squarings
+#[derive(Debug, Clone)]
+pub struct TimeLockPuzzle {
+    /// RSA modulus n
+    modulus: BigUint,
+    /// Starting value a
+    base: BigUint,
+    /// Number of squarings T
+    squarings: u64,
+    /// Key XOR the mask derived from a^(2^T) mod n
+    locked_key: [u8; 32],
+    /// Hash identifying the correct key, to detect a wrong solution
+    key_check: [u8; 32],
+}
+
+impl TimeLockPuzzle {
+    /// Number of sequential squarings needed to open the puzzle
+    pub fn squarings(&self) -> u64 {
+        self.squarings
+    }
+
+    /// Modulus the puzzle was created under
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// Performs the squarings and recovers the key
+    pub fn solve(&self) -> CryptoResult<[u8; 32]> {
+        let start = Instant::now();
+        let mut value = self.base.clone();
+        for _ in 0..self.squarings {
+            value = (&value * &value) % &self.modulus;
+        }
+
+        let mask = derive_mask(&self.modulus, &value);
+        let mut key = [0u8; 32];
+        for (k, (locked, m)) in key.iter_mut().zip(self.locked_key.iter().zip(&mask)) {
+            *k = locked ^ m;
+        }
+
+        if key_check(&key) != self.key_check {
+            return Err(CryptoError::VerificationFailed("Time-lock solution does not match key check".into()));
+        }
+
+        info!(squarings = self.squarings, elapsed = ?start.elapsed(), "Solved time-lock puzzle");
+        Ok(key)
+    }
+}
+
+/// A share encrypted under a time-locked key
+#[derive(Debug, Clone)]
+pub struct TimeLockedShare {
+    /// Identifier of the underlying share
+    id: u8,
+    /// Share data encrypted and authenticated under the locked key
+    ciphertext: Vec<u8>,
+    /// Puzzle hiding the encryption key
+    puzzle: TimeLockPuzzle,
+}
+
+impl TimeLockedShare {
+    /// Encrypts a share under a fresh key locked for `squarings` squarings
+    pub fn lock(share: &Share, creator: &TimeLockCreator, squarings: u64) -> CryptoResult<Self> {
+        if !share.verify() {
+            return Err(CryptoError::VerificationFailed("Share verification failed".into()));
+        }
+
+        let key: [u8; 32] = rand::random();
+        let puzzle = creator.lock(&key, squarings)?;
+
+        Ok(Self {
+            id: share.id(),
+            ciphertext: encrypt_share(&key, share.id(), share.data())?,
+            puzzle,
+        })
+    }
+
+    /// Identifier of the locked share
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Puzzle that has to be solved before the share can be used
+    pub fn puzzle(&self) -> &TimeLockPuzzle {
+        &self.puzzle
+    }
+
+    /// Solves the puzzle and decrypts the share
+    ///
+    /// Fails with `VerificationFailed` if the ciphertext or id was altered.
+    pub fn unlock(&self) -> CryptoResult<Share> {
+        let key = self.puzzle.solve()?;
+        Ok(Share::new(decrypt_share(&key, self.id, &self.ciphertext)?, self.id))
+    }
+}
+
+impl ThreePartySecretSharing {
+    /// Splits a secret and time-locks the shares whose ids are listed
+    ///
+    /// Returns the shares left open and the locked ones separately.
+    pub fn split_time_locked(
+        &mut self,
+        secret: &[u8],
+        creator: &TimeLockCreator,
+        locked_ids: &[u8],
+        squarings: u64,
+    ) -> CryptoResult<(Vec<Share>, Vec<TimeLockedShare>)> {
+        if let Some(id) = locked_ids.iter().find(|&&id| id > 2) {
+            return Err(CryptoError::InvalidInput(format!("No share with id {}", id)));
+        }
+
+        let mut open = Vec::new();
+        let mut locked = Vec::new();
+        for share in self.split(secret)? {
+            if locked_ids.contains(&share.id()) {
+                locked.push(TimeLockedShare::lock(&share, creator, squarings)?);
+            } else {
+                open.push(share);
+            }
+        }
+
+        debug!(open = open.len(), locked = locked.len(), squarings, "Split with time-locked shares");
+        Ok((open, locked))
+    }
+
+    /// Unlocks time-locked shares and reconstructs the secret
+    pub fn reconstruct_time_locked(
+        &self,
+        open: &[Share],
+        locked: &[TimeLockedShare],
+    ) -> CryptoResult<Vec<u8>> {
+        let mut shares = open.to_vec();
+        for share in locked {
+            shares.push(share.unlock()?);
+        }
+        shares.sort_by_key(Share::id);
+        self.reconstruct(&shares)
+    }
+}
+
+fn derive_mask(modulus: &BigUint, solution: &BigUint) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(MASK_DOMAIN);
+    hasher.update(modulus.to_bytes_be());
+    hasher.update(solution.to_bytes_be());
+    hasher.finalize().into()
+}
+
+fn key_check(key: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(CHECK_DOMAIN);
+    hasher.update(key);
+    hasher.finalize().into()
+}
+
+fn share_aad(id: u8) -> Vec<u8> {
+    let mut aad = SHARE_DOMAIN.to_vec();
+    aad.push(id);
+    aad
+}
+
+/// Encrypts share data under a key used for this share alone
+fn encrypt_share(key: &[u8; 32], id: u8, data: &[u8]) -> CryptoResult<Vec<u8>> {
+    // Every lock draws a fresh key, so the fixed nonce is never reused
+    ChaCha20Poly1305::new(key.into())
+        .encrypt(&Nonce::default(), Payload { msg: data, aad: &share_aad(id) })
+        .map_err(|_| CryptoError::InvalidState("Failed to encrypt share".into()))
+}
+
+fn decrypt_share(key: &[u8; 32], id: u8, ciphertext: &[u8]) -> CryptoResult<Vec<u8>> {
+    ChaCha20Poly1305::new(key.into())
+        .decrypt(&Nonce::default(), Payload { msg: ciphertext, aad: &share_aad(id) })
+        .map_err(|_| CryptoError::VerificationFailed(format!("Time-locked share {} failed to decrypt", id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_BITS: usize = 512;
+
+    #[test]
+    fn test_trapdoor_matches_sequential_squaring() -> CryptoResult<()> {
+        let creator = TimeLockCreator::generate(TEST_BITS)?;
+        let key: [u8; 32] = rand::random();
+
+        let puzzle = creator.lock(&key, 2_000)?;
+        assert_eq!(puzzle.squarings(), 2_000);
+        assert_eq!(puzzle.solve()?, key);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tampered_puzzle_rejected() -> CryptoResult<()> {
+        let creator = TimeLockCreator::generate(TEST_BITS)?;
+        let mut puzzle = creator.lock(&[7u8; 32], 100)?;
+        puzzle.squarings -= 1;
+
+        assert!(matches!(puzzle.solve(), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_time_locked_share_reconstruction() -> CryptoResult<()> {
+        let creator = TimeLockCreator::generate(TEST_BITS)?;
+        let mut sharing = ThreePartySecretSharing::default();
+        let secret = b"Release after the embargo";
+
+        let (open, locked) = sharing.split_time_locked(secret, &creator, &[0, 2], 500)?;
+        assert_eq!(open.len(), 1);
+        assert_eq!(locked.len(), 2);
+        assert_ne!(locked[0].ciphertext, open[0].data());
+
+        // Without the locked shares nothing can be reconstructed
+        assert!(sharing.reconstruct(&open).is_err());
+
+        let reconstructed = sharing.reconstruct_time_locked(&open, &locked)?;
+        assert_eq!(&reconstructed, secret);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tampered_ciphertext_rejected() -> CryptoResult<()> {
+        let creator = TimeLockCreator::generate(TEST_BITS)?;
+        let share = Share::new(vec![9u8; 40], 1);
+        let locked = TimeLockedShare::lock(&share, &creator, 50)?;
+        assert_eq!(locked.unlock()?.data(), share.data());
+
+        let mut flipped = locked.clone();
+        flipped.ciphertext[0] ^= 1;
+        assert!(matches!(flipped.unlock(), Err(CryptoError::VerificationFailed(_))));
+
+        let mut relabelled = locked;
+        relabelled.id = 2;
+        assert!(matches!(relabelled.unlock(), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_parameters_rejected() -> CryptoResult<()> {
+        assert!(TimeLockCreator::generate(128).is_err());
+
+        let creator = TimeLockCreator::generate(TEST_BITS)?;
+        assert!(creator.lock(&[0u8; 32], 0).is_err());
+
+        let mut sharing = ThreePartySecretSharing::default();
+        assert!(sharing.split_time_locked(b"secret", &creator, &[3], 10).is_err());
+        Ok(())
+    }
+}
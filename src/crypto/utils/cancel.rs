@@ -0,0 +1,119 @@
+//! Cancellation and deadlines for long-running operations
+//! Location: src/crypto/utils/cancel.rs
+
+use super::clock::{self, Clock};
+use crate::error::{CryptoError, CryptoResult};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Longest a cancellable wait sleeps before checking the token again
+pub const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Stops an operation on request or once a deadline has passed
+///
+/// Clones share the cancellation flag, so a scheduler can keep one clone
+/// and hand another to the computation. Operations call `check` between
+/// units of work and stop with `Cancelled` or `Timeout`.
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    started: Instant,
+    deadline: Option<Instant>,
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CancellationToken {
+    /// Creates a token without a deadline, measured on the system clock
+    pub fn new() -> Self {
+        Self::with_clock(clock::system_clock())
+    }
+
+    /// Creates a token measured on the given clock, starting now
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            started: clock.now(),
+            deadline: None,
+            clock,
+        }
+    }
+
+    /// Sets a deadline `timeout` after the token was created
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(self.started + timeout);
+        self
+    }
+
+    /// Requests cancellation of every operation holding a clone of this token
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether cancellation has been requested
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Time since the token was created
+    pub fn elapsed(&self) -> Duration {
+        self.clock.elapsed_since(self.started)
+    }
+
+    /// Time left until the deadline, if one is set
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline.map(|deadline| deadline.saturating_duration_since(self.clock.now()))
+    }
+
+    /// Fails with `Cancelled` or, past the deadline, `Timeout` with the elapsed time
+    pub fn check(&self) -> CryptoResult<()> {
+        if self.is_cancelled() {
+            return Err(CryptoError::Cancelled);
+        }
+        if self.remaining().is_some_and(|left| left.is_zero()) {
+            return Err(CryptoError::Timeout(self.elapsed()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::utils::ManualClock;
+
+    #[test]
+    fn test_cancel_is_shared_between_clones() {
+        let token = CancellationToken::new();
+        let worker = token.clone();
+        assert!(worker.check().is_ok());
+
+        token.cancel();
+        assert!(worker.is_cancelled());
+        assert!(matches!(worker.check(), Err(CryptoError::Cancelled)));
+    }
+
+    #[test]
+    fn test_deadline_reports_elapsed_time() {
+        let clock = ManualClock::new();
+        let token = CancellationToken::with_clock(Arc::new(clock.clone()))
+            .with_timeout(Duration::from_secs(5));
+
+        clock.advance(Duration::from_secs(3));
+        assert!(token.check().is_ok());
+        assert_eq!(token.remaining(), Some(Duration::from_secs(2)));
+
+        clock.advance(Duration::from_secs(4));
+        assert_eq!(token.remaining(), Some(Duration::ZERO));
+        match token.check() {
+            Err(CryptoError::Timeout(elapsed)) => assert_eq!(elapsed, Duration::from_secs(7)),
+            other => panic!("expected timeout, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Time sources for timing enforcement
+//! Location: src/crypto/utils/clock.rs
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Source of the current time and of delays
+///
+/// Timing-sensitive code asks a `Clock` instead of calling `Instant::now()`
+/// and `std::thread::sleep` directly, so tests can substitute `ManualClock`.
+pub trait Clock: Send + Sync + Debug {
+    /// Returns the current instant
+    fn now(&self) -> Instant;
+
+    /// Blocks until `duration` has passed on this clock
+    fn sleep(&self, duration: Duration);
+
+    /// Returns the time passed since `earlier`, or zero if it lies in the future
+    fn elapsed_since(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+/// Clock backed by the operating system
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// Clock that only moves when told to
+///
+/// `sleep` returns immediately after advancing the clock, so code that
+/// sleeps to meet a deadline runs instantly while still observing the delay.
+/// Clones share the same time.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    origin: Instant,
+    offset: Arc<Mutex<Duration>>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ManualClock {
+    /// Creates a clock stopped at the current instant
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            offset: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    /// Moves the clock forward
+    pub fn advance(&self, duration: Duration) {
+        *self.offset.lock().expect("clock lock poisoned") += duration;
+    }
+
+    /// Total time the clock has been advanced by
+    pub fn offset(&self) -> Duration {
+        *self.offset.lock().expect("clock lock poisoned")
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.origin + self.offset()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
+
+/// Returns a shared handle to the system clock
+pub fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock_moves_only_when_advanced() {
+        let clock = ManualClock::new();
+        let start = clock.now();
+        assert_eq!(clock.elapsed_since(start), Duration::ZERO);
+
+        clock.advance(Duration::from_secs(5));
+        clock.sleep(Duration::from_secs(2));
+        assert_eq!(clock.elapsed_since(start), Duration::from_secs(7));
+
+        // Clones observe the same time
+        let shared = clock.clone();
+        shared.advance(Duration::from_secs(1));
+        assert_eq!(clock.offset(), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn test_elapsed_since_future_is_zero() {
+        let clock = ManualClock::new();
+        let future = clock.now() + Duration::from_secs(1);
+        assert_eq!(clock.elapsed_since(future), Duration::ZERO);
+    }
+}
@@ -0,0 +1,192 @@
+//! Minimal binary encoding for snapshots and messages
+//! Location: src/crypto/utils/codec.rs
+
+use crate::error::{CryptoError, CryptoResult};
+use sha2::{Sha256, Digest};
+use std::time::Duration;
+
+/// Size of the SHA-256 digest appended by `seal`
+const DIGEST_SIZE: usize = 32;
+
+/// Appends little-endian fields to a byte buffer
+#[derive(Debug, Default)]
+pub(crate) struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn put_u32(&mut self, value: u32) -> &mut Self {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub(crate) fn put_u64(&mut self, value: u64) -> &mut Self {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Writes raw bytes without a length prefix
+    pub(crate) fn put_raw(&mut self, bytes: &[u8]) -> &mut Self {
+        self.buf.extend_from_slice(bytes);
+        self
+    }
+
+    /// Writes a u64 length prefix followed by the bytes
+    pub(crate) fn put_bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.put_u64(bytes.len() as u64);
+        self.put_raw(bytes)
+    }
+
+    pub(crate) fn put_duration(&mut self, duration: Duration) -> &mut Self {
+        self.put_u64(duration.as_secs());
+        self.put_u32(duration.subsec_nanos())
+    }
+
+    pub(crate) fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+/// Reads fields written by `Encoder`, failing on truncated input
+#[derive(Debug)]
+pub(crate) struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub(crate) fn get_u32(&mut self) -> CryptoResult<u32> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.get_raw(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    pub(crate) fn get_u64(&mut self) -> CryptoResult<u64> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.get_raw(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    pub(crate) fn get_array32(&mut self) -> CryptoResult<[u8; 32]> {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(self.get_raw(32)?);
+        Ok(bytes)
+    }
+
+    /// Reads exactly `len` raw bytes
+    pub(crate) fn get_raw(&mut self, len: usize) -> CryptoResult<&'a [u8]> {
+        let end = self.pos.checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| CryptoError::InvalidInput("Encoded data is truncated".into()))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    /// Reads a u64 length prefix followed by that many bytes
+    pub(crate) fn get_bytes(&mut self) -> CryptoResult<&'a [u8]> {
+        let len = usize::try_from(self.get_u64()?)
+            .map_err(|_| CryptoError::InvalidInput("Encoded length too large".into()))?;
+        self.get_raw(len)
+    }
+
+    pub(crate) fn get_duration(&mut self) -> CryptoResult<Duration> {
+        let secs = self.get_u64()?;
+        let nanos = self.get_u32()?;
+        if nanos >= 1_000_000_000 {
+            return Err(CryptoError::InvalidInput("Invalid encoded duration".into()));
+        }
+        Ok(Duration::new(secs, nanos))
+    }
+
+    /// Whether every byte has been consumed
+    pub(crate) fn is_exhausted(&self) -> bool {
+        self.pos == self.data.len()
+    }
+
+    /// Fails unless every byte has been consumed
+    pub(crate) fn finish(self) -> CryptoResult<()> {
+        if self.pos != self.data.len() {
+            return Err(CryptoError::InvalidInput(
+                format!("{} trailing bytes after encoded data", self.data.len() - self.pos)
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Appends a SHA-256 digest of the payload
+///
+/// The digest catches corruption only; it is unkeyed, so a writer can recompute it.
+pub(crate) fn seal(mut payload: Vec<u8>) -> Vec<u8> {
+    let digest = Sha256::digest(&payload);
+    payload.extend_from_slice(&digest);
+    payload
+}
+
+/// Checks and strips the digest appended by `seal`
+pub(crate) fn unseal(sealed: &[u8]) -> CryptoResult<&[u8]> {
+    if sealed.len() < DIGEST_SIZE {
+        return Err(CryptoError::VerificationFailed("Sealed data too short".into()));
+    }
+
+    let (payload, digest) = sealed.split_at(sealed.len() - DIGEST_SIZE);
+    if Sha256::digest(payload).as_slice() != digest {
+        return Err(CryptoError::VerificationFailed("Integrity digest mismatch".into()));
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() -> CryptoResult<()> {
+        let mut encoder = Encoder::new();
+        encoder
+            .put_u32(0xdead_beef)
+            .put_u64(u64::MAX)
+            .put_bytes(b"payload")
+            .put_raw(&[9u8; 32])
+            .put_duration(Duration::new(5, 123));
+        let encoded = encoder.finish();
+
+        let mut decoder = Decoder::new(&encoded);
+        assert_eq!(decoder.get_u32()?, 0xdead_beef);
+        assert_eq!(decoder.get_u64()?, u64::MAX);
+        assert_eq!(decoder.get_bytes()?, b"payload");
+        assert_eq!(decoder.get_array32()?, [9u8; 32]);
+        assert_eq!(decoder.get_duration()?, Duration::new(5, 123));
+        decoder.finish()
+    }
+
+    #[test]
+    fn test_truncated_input_rejected() {
+        let mut encoder = Encoder::new();
+        encoder.put_bytes(b"payload");
+        let encoded = encoder.finish();
+
+        let mut decoder = Decoder::new(&encoded[..encoded.len() - 1]);
+        assert!(decoder.get_bytes().is_err());
+    }
+
+    #[test]
+    fn test_seal_detects_corruption() -> CryptoResult<()> {
+        let sealed = seal(b"snapshot".to_vec());
+        assert_eq!(unseal(&sealed)?, b"snapshot");
+
+        let mut corrupted = sealed.clone();
+        corrupted[0] ^= 1;
+        assert!(matches!(unseal(&corrupted), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+}
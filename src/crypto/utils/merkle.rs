@@ -0,0 +1,164 @@
+//! Binary SHA-256 Merkle tree used to commit to lists of hashes
+//! Location: src/crypto/utils/merkle.rs
+
+use crate::error::{CryptoError, CryptoResult};
+use sha2::{Sha256, Digest};
+
+/// Domain prefix for leaf hashes, keeps leaves and nodes distinct
+const LEAF_PREFIX: u8 = 0x00;
+/// Domain prefix for interior node hashes
+const NODE_PREFIX: u8 = 0x01;
+
+/// Merkle tree over 32-byte leaves
+///
+/// Odd levels are completed by pairing the last node with itself.
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    /// Tree levels from hashed leaves up to the root
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// Builds a tree over the given leaves
+    pub fn new(leaves: &[[u8; 32]]) -> CryptoResult<Self> {
+        if leaves.is_empty() {
+            return Err(CryptoError::InvalidInput("Merkle tree needs at least one leaf".into()));
+        }
+
+        let mut levels = vec![leaves.iter().map(hash_leaf).collect::<Vec<_>>()];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let level = levels.last().expect("levels is never empty");
+            let next = level
+                .chunks(2)
+                .map(|pair| hash_node(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+                .collect();
+            levels.push(next);
+        }
+
+        Ok(Self { levels })
+    }
+
+    /// Returns the root commitment
+    pub fn root(&self) -> [u8; 32] {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    /// Returns the number of leaves
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Returns whether the tree has no leaves (never true for a built tree)
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// Returns the sibling path for a leaf, bottom level first
+    pub fn path(&self, index: usize) -> Option<Vec<[u8; 32]>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let mut path = Vec::with_capacity(self.levels.len() - 1);
+        let mut position = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = position ^ 1;
+            path.push(*level.get(sibling).unwrap_or(&level[position]));
+            position /= 2;
+        }
+        Some(path)
+    }
+}
+
+/// Checks that `leaf` sits at `index` of a tree with `leaf_count` leaves and the given root
+pub fn verify_path(
+    root: &[u8; 32],
+    leaf: &[u8; 32],
+    index: usize,
+    leaf_count: usize,
+    path: &[[u8; 32]],
+) -> bool {
+    if index >= leaf_count || path.len() != tree_depth(leaf_count) {
+        return false;
+    }
+
+    let mut node = hash_leaf(leaf);
+    let mut position = index;
+    for sibling in path {
+        node = if position.is_multiple_of(2) {
+            hash_node(&node, sibling)
+        } else {
+            hash_node(sibling, &node)
+        };
+        position /= 2;
+    }
+    &node == root
+}
+
+/// Number of sibling hashes in a path for a tree with `leaf_count` leaves
+fn tree_depth(leaf_count: usize) -> usize {
+    let mut depth = 0;
+    let mut width = leaf_count;
+    while width > 1 {
+        width = width.div_ceil(2);
+        depth += 1;
+    }
+    depth
+}
+
+fn hash_leaf(leaf: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(leaf);
+    hasher.finalize().into()
+}
+
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(count: usize) -> Vec<[u8; 32]> {
+        (0..count).map(|i| [i as u8; 32]).collect()
+    }
+
+    #[test]
+    fn test_paths_verify_for_every_leaf() -> CryptoResult<()> {
+        for count in [1, 2, 3, 5, 8, 13] {
+            let leaves = leaves(count);
+            let tree = MerkleTree::new(&leaves)?;
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let path = tree.path(index).expect("index is in range");
+                assert!(verify_path(&tree.root(), leaf, index, count, &path),
+                        "Leaf {} of {} failed to verify", index, count);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrong_leaf_or_index_rejected() -> CryptoResult<()> {
+        let leaves = leaves(6);
+        let tree = MerkleTree::new(&leaves)?;
+        let path = tree.path(2).expect("index is in range");
+
+        assert!(!verify_path(&tree.root(), &leaves[3], 2, 6, &path));
+        assert!(!verify_path(&tree.root(), &leaves[2], 3, 6, &path));
+        assert!(!verify_path(&tree.root(), &leaves[2], 2, 12, &path));
+        assert!(tree.path(6).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_tree_rejected() {
+        assert!(MerkleTree::new(&[]).is_err());
+    }
+}
@@ -2,8 +2,19 @@
 //! Location: src/crypto/utils/mod.rs
 
 pub mod padding;
+pub mod merkle;
+pub mod clock;
+pub mod cancel;
+pub(crate) mod codec;
+#[cfg(test)]
+pub(crate) mod temp;
+
+pub use cancel::CancellationToken;
+pub use clock::{Clock, ManualClock, SystemClock};
 
-use crate::error::CryptoResult;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
 use std::time::{Duration, Instant};
 
 /// Performs XOR operation on two byte slices
@@ -21,10 +32,70 @@ pub fn random_bytes(length: usize) -> Vec<u8> {
 
 /// Enforces minimum time delay for operation
 pub fn enforce_delay(start: Instant, min_delay: Duration) {
-    let elapsed = start.elapsed();
+    enforce_delay_with(&SystemClock, start, min_delay);
+}
+
+/// Enforces minimum time delay for operation, measured on the given clock
+pub fn enforce_delay_with(clock: &dyn Clock, start: Instant, min_delay: Duration) {
+    let elapsed = clock.elapsed_since(start);
     if elapsed < min_delay {
-        std::thread::sleep(min_delay - elapsed);
+        clock.sleep(min_delay - elapsed);
+    }
+}
+
+/// Writes a file by syncing a temporary sibling and renaming it into place
+///
+/// On unix the parent directory is synced as well, so the new file survives a crash.
+pub(crate) fn write_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    write_atomic_mode(path, data, None)
+}
+
+/// Like `write_atomic`, but the file is only readable and writable by its owner
+pub(crate) fn write_atomic_private(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    write_atomic_mode(path, data, Some(0o600))
+}
+
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn write_atomic_mode(path: &Path, data: &[u8], mode: Option<u32>) -> std::io::Result<()> {
+    let file_name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "path has no file name")
+    })?;
+    let suffix: u64 = rand::random();
+    let tmp_path = path.with_file_name(format!(
+        ".{}.{:016x}.tmp",
+        file_name.to_string_lossy(),
+        suffix
+    ));
+
+    let result = (|| {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        if let Some(mode) = mode {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(mode);
+        }
+        let mut file = options.open(&tmp_path)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result?;
+
+    // The rename only survives a crash once the directory entry is on disk
+    #[cfg(unix)]
+    {
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        fs::File::open(parent)?.sync_all()?;
     }
+    Ok(())
 }
 
 /// Aligns data to specified block size with random padding
@@ -69,6 +140,20 @@ mod tests {
         assert!(start.elapsed() >= delay);
     }
 
+    #[test]
+    fn test_enforce_delay_with_manual_clock() {
+        let clock = ManualClock::new();
+        let start = clock.now();
+        clock.advance(Duration::from_secs(3));
+
+        enforce_delay_with(&clock, start, Duration::from_secs(10));
+        assert_eq!(clock.elapsed_since(start), Duration::from_secs(10));
+
+        // Already past the delay, so no further waiting
+        enforce_delay_with(&clock, start, Duration::from_secs(5));
+        assert_eq!(clock.elapsed_since(start), Duration::from_secs(10));
+    }
+
     #[test]
     fn test_pad_to_block_size() {
         let data = vec![1, 2, 3];
@@ -2,8 +2,6 @@
 //! Location: src/crypto/utils/padding.rs
 
 use crate::error::{CryptoError, CryptoResult};
-use super::random_bytes;
-use std::convert::TryInto;
 use rand::Rng;
 
 pub(crate) const ALIGNMENT: usize = 16;
@@ -13,7 +11,7 @@ const LENGTH_SIZE: usize = 8; // Using u64 for length prefix
 #[inline]
 pub fn calculate_padded_size(input_size: usize) -> usize {
     // Add 8 bytes for length prefix (u64)
-    ((input_size + LENGTH_SIZE + ALIGNMENT - 1) / ALIGNMENT) * ALIGNMENT
+    (input_size + LENGTH_SIZE).div_ceil(ALIGNMENT) * ALIGNMENT
 }
 
 /// Adds padding to input data with 64-bit length prefix
@@ -36,8 +34,8 @@ pub fn pad_data(data: &[u8]) -> CryptoResult<Vec<u8>> {
     padded.resize(padded_size, 0);
     let padding_start = data.len() + LENGTH_SIZE;
     let mut rng = rand::thread_rng();
-    for i in padding_start..padded_size {
-        padded[i] = rng.gen();
+    for byte in &mut padded[padding_start..] {
+        *byte = rng.gen();
     }
 
     Ok(padded)
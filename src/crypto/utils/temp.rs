@@ -0,0 +1,34 @@
+//! Self-removing temporary directories for tests
+//! Location: src/crypto/utils/temp.rs
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Fresh directory under the system temp dir, removed with its contents on drop
+///
+/// Cleanup runs even when an assertion fails part way through a test.
+#[derive(Debug)]
+pub(crate) struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Creates `mcafee-<name>-<random>` in the system temp dir
+    pub(crate) fn new(name: &str) -> io::Result<Self> {
+        let path = std::env::temp_dir().join(format!("mcafee-{}-{:016x}", name, rand::random::<u64>()));
+        fs::create_dir_all(&path)?;
+        Ok(Self { path })
+    }
+
+    /// A path inside the directory
+    pub(crate) fn join(&self, name: &str) -> PathBuf {
+        self.path.join(name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
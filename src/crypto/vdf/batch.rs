@@ -0,0 +1,246 @@
+//! Batch evaluation of temporal VDFs over many inputs
+//! Location: src/crypto/vdf/batch.rs
+//!
+//! All instances are stepped in lockstep, one round at a time. Every instance
+//! in a round becomes ready at about the same moment, so the batch costs one
+//! wall-clock delay rather than one per input.
+
+use super::state::VDFState;
+use super::temporal::{TemporalConfig, TemporalProof, TemporalVDF};
+use crate::crypto::utils::CancellationToken;
+use crate::error::CryptoResult;
+use std::time::Duration;
+use tracing::{debug, info};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Output and proof for one input of a batch
+#[derive(Debug, Clone)]
+pub struct BatchOutput {
+    /// Commitment to the initial state, for third-party verification
+    pub input_commitment: [u8; 32],
+    /// Output of the computation
+    pub output: Vec<u8>,
+    /// Proof of the computation
+    pub proof: TemporalProof,
+}
+
+/// Runs one temporal VDF per input with a shared configuration
+#[derive(Debug, Clone)]
+pub struct VDFBatch {
+    config: TemporalConfig,
+    parallel: bool,
+}
+
+impl VDFBatch {
+    /// Creates a batch runner, parallel when the `parallel` feature is enabled
+    pub fn new(config: TemporalConfig) -> Self {
+        Self {
+            config,
+            parallel: cfg!(feature = "parallel"),
+        }
+    }
+
+    /// Chooses whether rounds step instances on the rayon pool
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Whether rounds step instances on the rayon pool
+    pub fn is_parallel(&self) -> bool {
+        self.parallel
+    }
+
+    /// Configuration every instance runs with
+    pub fn config(&self) -> &TemporalConfig {
+        &self.config
+    }
+
+    /// Evaluates every input, reporting success or failure per input
+    ///
+    /// An input that fails to initialize or iterate is dropped from later
+    /// rounds; the others carry on.
+    pub fn run<I: AsRef<[u8]>>(&self, inputs: &[I]) -> Vec<CryptoResult<BatchOutput>> {
+        self.run_until(inputs, None)
+    }
+
+    /// Evaluates every input, stopping unfinished ones when the token says so
+    pub fn run_with<I: AsRef<[u8]>>(
+        &self,
+        inputs: &[I],
+        token: &CancellationToken,
+    ) -> Vec<CryptoResult<BatchOutput>> {
+        self.run_until(inputs, Some(token))
+    }
+
+    fn run_until<I: AsRef<[u8]>>(
+        &self,
+        inputs: &[I],
+        token: Option<&CancellationToken>,
+    ) -> Vec<CryptoResult<BatchOutput>> {
+        info!(inputs = inputs.len(), iterations = self.config.iterations, "Starting VDF batch");
+
+        let mut slots: Vec<CryptoResult<TemporalVDF>> = inputs.iter()
+            .map(|input| {
+                let mut vdf = TemporalVDF::new(self.config.clone());
+                vdf.initialize(input.as_ref())?;
+                Ok(vdf)
+            })
+            .collect();
+
+        for round in 0..self.config.iterations {
+            debug!(round = round + 1, "Stepping VDF batch");
+            wait_for_round(&slots, token);
+            self.step_all(&mut slots, token);
+        }
+
+        let results: Vec<_> = slots.into_iter().map(|slot| slot.and_then(finish)).collect();
+        let failed = results.iter().filter(|result| result.is_err()).count();
+        info!(inputs = results.len(), failed, "Finished VDF batch");
+        results
+    }
+
+    #[cfg(feature = "parallel")]
+    fn step_all(&self, slots: &mut [CryptoResult<TemporalVDF>], token: Option<&CancellationToken>) {
+        if self.parallel {
+            slots.par_iter_mut().for_each(|slot| step(slot, token));
+        } else {
+            slots.iter_mut().for_each(|slot| step(slot, token));
+        }
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn step_all(&self, slots: &mut [CryptoResult<TemporalVDF>], token: Option<&CancellationToken>) {
+        // Lockstep stepping still shares the delay without a thread pool
+        slots.iter_mut().for_each(|slot| step(slot, token));
+    }
+}
+
+/// Sleeps until every live instance may take its next iteration
+///
+/// Instances share the configured clock, so waiting out the latest one makes
+/// them all ready. Without this, a `TimingPolicy::Reject` instance would be
+/// stepped early and fail. A stopped token ends the wait early; the
+/// following step then reports it for every instance.
+fn wait_for_round(slots: &[CryptoResult<TemporalVDF>], token: Option<&CancellationToken>) {
+    let latest = slots.iter()
+        .filter_map(|slot| slot.as_ref().ok())
+        .max_by_key(|vdf| vdf.progress().map_or(Duration::ZERO, VDFState::time_until_ready));
+
+    if let Some(vdf) = latest {
+        let _ = vdf.wait_until_ready(token);
+    }
+}
+
+/// Performs one iteration, replacing the instance with its error on failure
+fn step(slot: &mut CryptoResult<TemporalVDF>, token: Option<&CancellationToken>) {
+    let result = match slot {
+        Ok(vdf) => match token {
+            Some(token) => vdf.iterate_with(token),
+            None => vdf.iterate(),
+        },
+        Err(_) => return,
+    };
+
+    if let Err(err) = result {
+        *slot = Err(err);
+    }
+}
+
+fn finish(vdf: TemporalVDF) -> CryptoResult<BatchOutput> {
+    Ok(BatchOutput {
+        input_commitment: vdf.input_commitment().unwrap_or_default(),
+        output: vdf.get_output()?,
+        proof: vdf.generate_proof()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::utils::ManualClock;
+    use crate::crypto::vdf::temporal::CYCLE_LENGTH;
+    use crate::crypto::vdf::TemporalVerifier;
+    use crate::crypto::vdf::temporal::TimingPolicy;
+    use crate::error::CryptoError;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_batch_outputs_and_proofs() {
+        let config = TemporalConfig {
+            enforce_timing: false,
+            ..Default::default()
+        };
+        let inputs: Vec<Vec<u8>> = (0..8u8).map(|i| vec![i; 40]).collect();
+
+        let results = VDFBatch::new(config.clone()).run(&inputs);
+        assert_eq!(results.len(), inputs.len());
+
+        let verifier = TemporalVerifier::for_config(&config);
+        for (input, result) in inputs.iter().zip(results) {
+            let item = result.expect("batch item should succeed");
+            assert_eq!(&item.output[..input.len()], &input[..]);
+            let verdict = verifier.verify(&item.input_commitment, &item.proof.final_state_hash(), &item.proof);
+            assert!(verdict.is_accepted());
+        }
+    }
+
+    #[test]
+    fn test_failures_reported_per_item() {
+        let config = TemporalConfig {
+            enforce_timing: false,
+            ..Default::default()
+        };
+        let inputs: [&[u8]; 3] = [b"first", b"", b"third"];
+
+        let results = VDFBatch::new(config).run(&inputs);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(CryptoError::InvalidInput(_))));
+        assert!(results[2].is_ok());
+    }
+
+    #[test]
+    fn test_batch_shares_one_delay() {
+        let clock = ManualClock::new();
+        let config = TemporalConfig {
+            min_iteration_time: Duration::from_secs(60),
+            clock: Arc::new(clock.clone()),
+            ..Default::default()
+        };
+        let inputs: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i; 16]).collect();
+
+        let results = VDFBatch::new(config).with_parallel(false).run(&inputs);
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(clock.offset(), Duration::from_secs(60) * CYCLE_LENGTH as u32);
+    }
+
+    #[test]
+    fn test_batch_waits_under_reject_policy() {
+        let clock = ManualClock::new();
+        let config = TemporalConfig {
+            min_iteration_time: Duration::from_secs(30),
+            timing_policy: TimingPolicy::Reject,
+            clock: Arc::new(clock.clone()),
+            ..Default::default()
+        };
+        let inputs: Vec<Vec<u8>> = (0..3u8).map(|i| vec![i; 16]).collect();
+
+        let results = VDFBatch::new(config).with_parallel(false).run(&inputs);
+        for (input, result) in inputs.iter().zip(results) {
+            let item = result.expect("batch item should wait instead of failing");
+            assert_eq!(&item.output[..input.len()], &input[..]);
+        }
+        assert_eq!(clock.offset(), Duration::from_secs(30) * CYCLE_LENGTH as u32);
+    }
+
+    #[test]
+    fn test_cancelled_batch() {
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let results = VDFBatch::new(TemporalConfig::default()).run_with(&[b"input"], &token);
+        assert!(matches!(results[0], Err(CryptoError::Cancelled)));
+    }
+}
@@ -0,0 +1,489 @@
+//! Calibration of VDF difficulty against wall-clock delay
+//! Location: src/crypto/vdf/calibration.rs
+//!
+//! A `Calibrator` measures how many sequential iterations per second this
+//! machine performs for a backend. The resulting `CalibrationProfile` maps a
+//! target delay to an iteration count, reports the spread seen across samples
+//! and the delay an attacker with faster hardware would see, and can be
+//! stored on disk for `TemporalConfig` to load.
+
+use super::hashchain::HashChainVDF;
+use super::temporal::{IterationMode, TemporalConfig, TemporalVDF};
+use crate::crypto::utils::{self, codec::{self, Decoder, Encoder}};
+use crate::error::{CryptoError, CryptoResult};
+use num_bigint_dig::{BigUint, RandBigInt};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info};
+
+/// Magic bytes identifying a calibration profile
+const PROFILE_MAGIC: &[u8; 8] = b"MCVDFCAL";
+/// Calibration profile format version
+const PROFILE_VERSION: u32 = 1;
+
+/// Speedup over the fastest local sample assumed for a well-equipped attacker
+pub const DEFAULT_ATTACKER_SPEEDUP: f64 = 10.0;
+
+/// Most iterations a single calibration sample may run
+const MAX_SAMPLE_ITERATIONS: u64 = 1 << 26;
+
+/// Most iterations a calibrated `TemporalConfig` may ask for
+///
+/// `TemporalProof` keeps a state hash, transcript entry, duration and (in
+/// memory-hard mode) a digest per iteration, so this bounds a proof to a few
+/// tens of MiB.
+pub const MAX_CALIBRATED_ITERATIONS: usize = 1 << 18;
+
+/// VDF backend whose sequential iteration rate is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// XOR rounds of `TemporalVDF` over a state of `state_size` bytes
+    Xor { state_size: usize },
+    /// Memory-hard rounds of `TemporalVDF` over `memory_size` bytes
+    MemoryHard { memory_size: usize },
+    /// SHA-256 steps of `HashChainVDF`
+    HashChain,
+    /// Modular squarings of a time-lock puzzle with a `modulus_bits` modulus
+    TimeLock { modulus_bits: usize },
+}
+
+impl Backend {
+    fn tag(&self) -> u32 {
+        match self {
+            Backend::Xor { .. } => 0,
+            Backend::MemoryHard { .. } => 1,
+            Backend::HashChain => 2,
+            Backend::TimeLock { .. } => 3,
+        }
+    }
+
+    fn parameter(&self) -> u64 {
+        match *self {
+            Backend::Xor { state_size } => state_size as u64,
+            Backend::MemoryHard { memory_size } => memory_size as u64,
+            Backend::HashChain => 0,
+            Backend::TimeLock { modulus_bits } => modulus_bits as u64,
+        }
+    }
+
+    fn from_parts(tag: u32, parameter: u64) -> CryptoResult<Self> {
+        let parameter = parameter as usize;
+        match tag {
+            0 => Ok(Backend::Xor { state_size: parameter }),
+            1 => Ok(Backend::MemoryHard { memory_size: parameter }),
+            2 => Ok(Backend::HashChain),
+            3 => Ok(Backend::TimeLock { modulus_bits: parameter }),
+            _ => Err(CryptoError::InvalidInput(format!("Unknown calibration backend {}", tag))),
+        }
+    }
+
+    /// Runs `iterations` sequential iterations and returns the time taken
+    fn measure(&self, iterations: u64) -> CryptoResult<Duration> {
+        let iterations = iterations.max(1);
+        match *self {
+            Backend::Xor { state_size } => {
+                measure_temporal(IterationMode::Xor, state_size, 0, iterations)
+            }
+            Backend::MemoryHard { memory_size } => {
+                measure_temporal(IterationMode::MemoryHard, 64, memory_size, iterations)
+            }
+            Backend::HashChain => {
+                let config = TemporalConfig {
+                    iterations: iterations as usize,
+                    ..Default::default()
+                };
+                let mut chain = HashChainVDF::new(config);
+                chain.initialize(b"calibration")?;
+                let start = Instant::now();
+                chain.run()?;
+                Ok(start.elapsed())
+            }
+            Backend::TimeLock { modulus_bits } => {
+                if modulus_bits < 2 {
+                    return Err(CryptoError::InvalidInput("Modulus must be at least 2 bits".into()));
+                }
+                // Squaring speed does not depend on the modulus being an RSA modulus
+                let mut rng = rand::thread_rng();
+                let modulus = rng.gen_biguint(modulus_bits) | (BigUint::from(1u32) << (modulus_bits - 1)) | BigUint::from(1u32);
+                let mut value = rng.gen_biguint_below(&modulus);
+
+                let start = Instant::now();
+                for _ in 0..iterations {
+                    value = (&value * &value) % &modulus;
+                }
+                Ok(start.elapsed())
+            }
+        }
+    }
+}
+
+fn measure_temporal(mode: IterationMode, state_size: usize, memory_size: usize, iterations: u64) -> CryptoResult<Duration> {
+    let config = TemporalConfig {
+        enforce_timing: false,
+        mode,
+        memory_size,
+        iterations: iterations as usize,
+        ..Default::default()
+    };
+    let mut vdf = TemporalVDF::new(config);
+    vdf.initialize(&utils::random_bytes(state_size.max(1)))?;
+
+    let start = Instant::now();
+    vdf.run_to_completion()?;
+    Ok(start.elapsed())
+}
+
+/// Iteration count for a target delay, with the delays it implies
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difficulty {
+    /// Sequential iterations (or squarings) to perform
+    pub iterations: u64,
+    /// Delay on the calibrated machine at its mean rate
+    pub expected: Duration,
+    /// Delay at the fastest and slowest sampled rates
+    pub observed_range: (Duration, Duration),
+    /// Speedup over the fastest sample assumed for an attacker
+    pub attacker_speedup: f64,
+    /// Delay for an attacker running `attacker_speedup` times faster
+    pub attacker_delay: Duration,
+}
+
+/// Measured sequential iteration rate of one backend on one machine
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationProfile {
+    backend: Backend,
+    /// Iterations per second of each sample
+    sample_rates: Vec<f64>,
+    /// Iterations each sample ran
+    sample_iterations: u64,
+    /// Seconds since the Unix epoch when the profile was measured
+    measured_at: u64,
+}
+
+impl CalibrationProfile {
+    /// Builds a profile from measured per-sample rates
+    pub fn from_rates(backend: Backend, sample_rates: Vec<f64>, sample_iterations: u64) -> CryptoResult<Self> {
+        if sample_rates.is_empty() || sample_rates.iter().any(|rate| !rate.is_finite() || *rate <= 0.0) {
+            return Err(CryptoError::InvalidInput("Calibration needs positive, finite sample rates".into()));
+        }
+
+        let measured_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+
+        Ok(Self { backend, sample_rates, sample_iterations, measured_at })
+    }
+
+    /// Backend the profile was measured for
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// Iterations per second of each sample
+    pub fn sample_rates(&self) -> &[f64] {
+        &self.sample_rates
+    }
+
+    /// Iterations each sample ran
+    pub fn sample_iterations(&self) -> u64 {
+        self.sample_iterations
+    }
+
+    /// Seconds since the Unix epoch when the profile was measured
+    pub fn measured_at(&self) -> u64 {
+        self.measured_at
+    }
+
+    /// Mean iterations per second
+    pub fn mean_rate(&self) -> f64 {
+        self.sample_rates.iter().sum::<f64>() / self.sample_rates.len() as f64
+    }
+
+    /// Fastest sampled iterations per second
+    pub fn max_rate(&self) -> f64 {
+        self.sample_rates.iter().copied().fold(f64::MIN, f64::max)
+    }
+
+    /// Slowest sampled iterations per second
+    pub fn min_rate(&self) -> f64 {
+        self.sample_rates.iter().copied().fold(f64::MAX, f64::min)
+    }
+
+    /// Maps a target delay to an iteration count
+    ///
+    /// The count is chosen so this machine needs `target` at its mean rate.
+    /// An attacker `attacker_speedup` times faster than the fastest sample
+    /// finishes in `attacker_delay`; pick the target with that in mind.
+    pub fn difficulty_for(&self, target: Duration, attacker_speedup: f64) -> CryptoResult<Difficulty> {
+        if target.is_zero() {
+            return Err(CryptoError::InvalidInput("Target delay must be positive".into()));
+        }
+        if !attacker_speedup.is_finite() || attacker_speedup < 1.0 {
+            return Err(CryptoError::InvalidInput("Attacker speedup must be at least 1".into()));
+        }
+
+        let iterations = (target.as_secs_f64() * self.mean_rate()).ceil().max(1.0) as u64;
+        let delay_at = |rate: f64| Duration::from_secs_f64(iterations as f64 / rate);
+
+        Ok(Difficulty {
+            iterations,
+            expected: delay_at(self.mean_rate()),
+            observed_range: (delay_at(self.max_rate()), delay_at(self.min_rate())),
+            attacker_speedup,
+            attacker_delay: delay_at(self.max_rate() * attacker_speedup),
+        })
+    }
+
+    /// Writes the profile to disk with an integrity digest
+    pub fn save(&self, path: impl AsRef<Path>) -> CryptoResult<()> {
+        let mut encoder = Encoder::new();
+        encoder
+            .put_raw(PROFILE_MAGIC)
+            .put_u32(PROFILE_VERSION)
+            .put_u32(self.backend.tag())
+            .put_u64(self.backend.parameter())
+            .put_u64(self.sample_iterations)
+            .put_u64(self.measured_at)
+            .put_u32(self.sample_rates.len() as u32);
+        for rate in &self.sample_rates {
+            encoder.put_u64(rate.to_bits());
+        }
+
+        utils::write_atomic(path.as_ref(), &codec::seal(encoder.finish()))?;
+        Ok(())
+    }
+
+    /// Reads a profile written by `save`
+    pub fn load(path: impl AsRef<Path>) -> CryptoResult<Self> {
+        let sealed = std::fs::read(path)?;
+        let mut decoder = Decoder::new(codec::unseal(&sealed)?);
+
+        if decoder.get_raw(PROFILE_MAGIC.len())? != PROFILE_MAGIC {
+            return Err(CryptoError::InvalidInput("Not a calibration profile".into()));
+        }
+        let version = decoder.get_u32()?;
+        if version != PROFILE_VERSION {
+            return Err(CryptoError::InvalidInput(
+                format!("Unsupported calibration profile version {}", version)
+            ));
+        }
+
+        let backend = Backend::from_parts(decoder.get_u32()?, decoder.get_u64()?)?;
+        let sample_iterations = decoder.get_u64()?;
+        let measured_at = decoder.get_u64()?;
+        let sample_rates = (0..decoder.get_u32()?)
+            .map(|_| decoder.get_u64().map(f64::from_bits))
+            .collect::<CryptoResult<Vec<_>>>()?;
+        decoder.finish()?;
+
+        let mut profile = Self::from_rates(backend, sample_rates, sample_iterations)?;
+        profile.measured_at = measured_at;
+        Ok(profile)
+    }
+}
+
+/// Measures sequential iteration rates
+#[derive(Debug, Clone)]
+pub struct Calibrator {
+    /// Number of timed samples
+    samples: usize,
+    /// Minimum duration of each sample
+    sample_duration: Duration,
+}
+
+impl Default for Calibrator {
+    fn default() -> Self {
+        Self {
+            samples: 5,
+            sample_duration: Duration::from_millis(200),
+        }
+    }
+}
+
+impl Calibrator {
+    /// Creates a calibrator taking `samples` samples of at least `sample_duration` each
+    pub fn new(samples: usize, sample_duration: Duration) -> Self {
+        Self {
+            samples: samples.max(1),
+            sample_duration,
+        }
+    }
+
+    /// Measures a backend's sequential iteration rate on this machine
+    ///
+    /// The iteration count per sample is doubled until one run lasts at
+    /// least `sample_duration`, then `samples` runs of that size are timed.
+    pub fn calibrate(&self, backend: Backend) -> CryptoResult<CalibrationProfile> {
+        let mut iterations = 1u64;
+        while iterations < MAX_SAMPLE_ITERATIONS && backend.measure(iterations)? < self.sample_duration {
+            iterations *= 2;
+        }
+        debug!(?backend, iterations, "Sized calibration samples");
+
+        let sample_rates = (0..self.samples)
+            .map(|_| {
+                let elapsed = backend.measure(iterations)?.max(Duration::from_nanos(1));
+                Ok(iterations as f64 / elapsed.as_secs_f64())
+            })
+            .collect::<CryptoResult<Vec<_>>>()?;
+
+        let profile = CalibrationProfile::from_rates(backend, sample_rates, iterations)?;
+        info!(
+            ?backend,
+            mean_rate = profile.mean_rate(),
+            min_rate = profile.min_rate(),
+            max_rate = profile.max_rate(),
+            "Calibrated VDF backend"
+        );
+        Ok(profile)
+    }
+}
+
+impl TemporalConfig {
+    /// Sizes the configuration from a calibration profile for a target delay
+    ///
+    /// The delay then comes from the iterations themselves, so
+    /// `enforce_timing` is switched off. Only XOR and memory-hard profiles
+    /// describe `TemporalVDF`; hash-chain and time-lock profiles are rejected,
+    /// as is a target needing more than `MAX_CALIBRATED_ITERATIONS`.
+    pub fn with_calibration(mut self, profile: &CalibrationProfile, target: Duration) -> CryptoResult<Self> {
+        match profile.backend() {
+            Backend::Xor { .. } => self.mode = IterationMode::Xor,
+            Backend::MemoryHard { memory_size } => {
+                self.mode = IterationMode::MemoryHard;
+                self.memory_size = memory_size;
+            }
+            Backend::HashChain => {
+                return Err(CryptoError::InvalidInput(
+                    "Hash-chain profiles size HashChainVDF, not TemporalVDF".into()
+                ));
+            }
+            Backend::TimeLock { .. } => {
+                return Err(CryptoError::InvalidInput(
+                    "Time-lock profiles size puzzle squarings, not VDF iterations".into()
+                ));
+            }
+        }
+
+        let difficulty = profile.difficulty_for(target, DEFAULT_ATTACKER_SPEEDUP)?;
+        self.iterations = usize::try_from(difficulty.iterations)
+            .ok()
+            .filter(|iterations| *iterations <= MAX_CALIBRATED_ITERATIONS)
+            .ok_or_else(|| CryptoError::InvalidInput(format!(
+                "Target needs {} iterations, more than the {} a TemporalProof allows",
+                difficulty.iterations, MAX_CALIBRATED_ITERATIONS
+            )))?;
+        self.enforce_timing = false;
+        self.validate()?;
+
+        info!(
+            iterations = self.iterations,
+            ?target,
+            attacker_delay = ?difficulty.attacker_delay,
+            "Configured VDF from calibration"
+        );
+        Ok(self)
+    }
+
+    /// Loads a stored calibration profile and sizes a default configuration from it
+    pub fn load_calibrated(path: impl AsRef<Path>, target: Duration) -> CryptoResult<Self> {
+        Self::default().with_calibration(&CalibrationProfile::load(path)?, target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::utils::temp::TempDir;
+
+    fn quick() -> Calibrator {
+        Calibrator::new(2, Duration::from_millis(5))
+    }
+
+    #[test]
+    fn test_calibrates_every_backend() -> CryptoResult<()> {
+        for backend in [
+            Backend::Xor { state_size: 64 },
+            Backend::MemoryHard { memory_size: 4096 },
+            Backend::HashChain,
+            Backend::TimeLock { modulus_bits: 256 },
+        ] {
+            let profile = quick().calibrate(backend)?;
+            assert_eq!(profile.backend(), backend);
+            assert_eq!(profile.sample_rates().len(), 2);
+            assert!(profile.min_rate() > 0.0);
+            assert!(profile.min_rate() <= profile.mean_rate() && profile.mean_rate() <= profile.max_rate());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_difficulty_and_bounds() -> CryptoResult<()> {
+        let profile = CalibrationProfile::from_rates(Backend::HashChain, vec![900.0, 1000.0, 1100.0], 1024)?;
+        let difficulty = profile.difficulty_for(Duration::from_secs(600), 10.0)?;
+
+        assert_eq!(difficulty.iterations, 600_000);
+        assert_eq!(difficulty.expected, Duration::from_secs(600));
+        assert!(difficulty.observed_range.0 < difficulty.expected);
+        assert!(difficulty.observed_range.1 > difficulty.expected);
+        assert_eq!(difficulty.attacker_delay, Duration::from_secs_f64(600_000.0 / 11_000.0));
+
+        assert!(profile.difficulty_for(Duration::ZERO, 10.0).is_err());
+        assert!(profile.difficulty_for(Duration::from_secs(1), 0.5).is_err());
+        assert!(CalibrationProfile::from_rates(Backend::HashChain, vec![], 1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_round_trip_and_config() -> CryptoResult<()> {
+        let dir = TempDir::new("calibration")?;
+        let path = dir.join("profile.bin");
+        let profile = CalibrationProfile::from_rates(
+            Backend::MemoryHard { memory_size: 8192 },
+            vec![40.0, 50.0, 60.0],
+            64,
+        )?;
+        profile.save(&path)?;
+
+        let loaded = CalibrationProfile::load(&path)?;
+        assert_eq!(loaded, profile);
+
+        let config = TemporalConfig::load_calibrated(&path, Duration::from_secs(2))?;
+        assert_eq!(config.mode, IterationMode::MemoryHard);
+        assert_eq!(config.memory_size, 8192);
+        assert_eq!(config.iterations, 100);
+        assert!(!config.enforce_timing);
+
+        let timelock = CalibrationProfile::from_rates(Backend::TimeLock { modulus_bits: 2048 }, vec![1e5], 1)?;
+        assert!(TemporalConfig::default().with_calibration(&timelock, Duration::from_secs(1)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_chain_profile_rejected() -> CryptoResult<()> {
+        let profile = CalibrationProfile::from_rates(Backend::HashChain, vec![1e6], 1024)?;
+        assert!(matches!(
+            TemporalConfig::default().with_calibration(&profile, Duration::from_secs(1)),
+            Err(CryptoError::InvalidInput(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_calibrated_iterations_bounded() -> CryptoResult<()> {
+        let target = Duration::from_secs(1);
+        let profile = quick().calibrate(Backend::MemoryHard { memory_size: 4096 })?;
+        match TemporalConfig::default().with_calibration(&profile, target) {
+            Ok(config) => assert!(config.iterations <= MAX_CALIBRATED_ITERATIONS),
+            Err(error) => assert!(matches!(error, CryptoError::InvalidInput(_))),
+        }
+
+        // A fast XOR backend would need millions of iterations for the same delay
+        let fast = CalibrationProfile::from_rates(Backend::Xor { state_size: 64 }, vec![5e6], 1024)?;
+        assert!(matches!(
+            TemporalConfig::default().with_calibration(&fast, target),
+            Err(CryptoError::InvalidInput(_))
+        ));
+        Ok(())
+    }
+}
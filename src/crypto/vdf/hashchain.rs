@@ -0,0 +1,580 @@
+//! Hash-chain delay function with Merkle-committed checkpoints
+//! Location: src/crypto/vdf/hashchain.rs
+
+use super::temporal::TemporalConfig;
+use super::verifier::Verdict;
+use crate::crypto::utils::merkle::{verify_path, MerkleTree};
+use crate::error::{CryptoError, CryptoResult};
+use sha2::{Sha256, Digest};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Domain tag hashed with the input to start the chain
+const CHAIN_DOMAIN: &[u8] = b"mcafee/hashchain/v1";
+/// Domain tag for deriving spot-check indices from the Merkle root
+const SAMPLE_DOMAIN: &[u8] = b"mcafee/hashchain/sample/v1";
+/// Target number of segments when no checkpoint interval is given
+///
+/// Each sampled segment costs the verifier `1 / DEFAULT_SEGMENTS` of the
+/// chain, so this must stay well above `verification_steps`: with 64 segments
+/// the default 32 samples would recompute up to half the chain.
+pub const DEFAULT_SEGMENTS: usize = 1024;
+
+/// Opening of one chain segment against the checkpoint commitment
+#[derive(Debug, Clone)]
+pub struct SegmentOpening {
+    /// Segment index; the segment runs from checkpoint `index` to `index + 1`
+    pub index: usize,
+    /// Chain value at the start of the segment
+    pub start: [u8; 32],
+    /// Merkle path for the start checkpoint
+    pub start_path: Vec<[u8; 32]>,
+    /// Chain value at the end of the segment
+    pub end: [u8; 32],
+    /// Merkle path for the end checkpoint
+    pub end_path: Vec<[u8; 32]>,
+}
+
+/// Proof of a hash-chain evaluation
+#[derive(Debug, Clone)]
+pub struct HashChainProof {
+    /// First chain value, derived from the input
+    pub(crate) input_hash: [u8; 32],
+    /// Last chain value
+    pub(crate) output: [u8; 32],
+    /// Chain length T
+    pub(crate) iterations: usize,
+    /// Hashes between consecutive checkpoints
+    pub(crate) checkpoint_interval: usize,
+    /// Merkle root over all checkpoints
+    pub(crate) merkle_root: [u8; 32],
+    /// Segments selected from the Merkle root
+    pub(crate) openings: Vec<SegmentOpening>,
+    /// Merkle path for the first checkpoint
+    pub(crate) first_path: Vec<[u8; 32]>,
+    /// Merkle path for the last checkpoint
+    pub(crate) last_path: Vec<[u8; 32]>,
+    /// Time taken for computation
+    pub(crate) computation_time: Duration,
+}
+
+impl HashChainProof {
+    /// First chain value, which commits to the input
+    pub fn input_hash(&self) -> [u8; 32] {
+        self.input_hash
+    }
+
+    /// Claimed output of the chain
+    pub fn output(&self) -> [u8; 32] {
+        self.output
+    }
+
+    /// Chain length the prover claims to have computed
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Hashes between consecutive checkpoints
+    pub fn checkpoint_interval(&self) -> usize {
+        self.checkpoint_interval
+    }
+
+    /// Merkle root committing to every checkpoint
+    pub fn merkle_root(&self) -> [u8; 32] {
+        self.merkle_root
+    }
+
+    /// Segment openings included with the proof
+    pub fn openings(&self) -> &[SegmentOpening] {
+        &self.openings
+    }
+
+    /// Wall-clock time the prover reported for the computation
+    pub fn computation_time(&self) -> Duration {
+        self.computation_time
+    }
+}
+
+/// Iterated SHA-256 delay function
+///
+/// Computes `x_{i+1} = SHA-256(x_i)` for `config.iterations` steps and keeps
+/// every `checkpoint_interval`-th value. Unlike the XOR VDF the delay comes
+/// from the sequential hashing itself, so `min_iteration_time` is not applied.
+#[derive(Debug)]
+pub struct HashChainVDF {
+    pub config: TemporalConfig,
+    checkpoint_interval: Option<usize>,
+    checkpoints: Vec<[u8; 32]>,
+    current: [u8; 32],
+    position: usize,
+    start_time: Option<Instant>,
+}
+
+impl HashChainVDF {
+    pub fn new(config: TemporalConfig) -> Self {
+        Self {
+            config,
+            checkpoint_interval: None,
+            checkpoints: Vec::new(),
+            current: [0u8; 32],
+            position: 0,
+            start_time: None,
+        }
+    }
+
+    /// Sets the number of hashes between recorded checkpoints
+    pub fn with_checkpoint_interval(mut self, interval: usize) -> Self {
+        self.checkpoint_interval = Some(interval);
+        self
+    }
+
+    /// Number of hashes between recorded checkpoints
+    pub fn checkpoint_interval(&self) -> usize {
+        self.checkpoint_interval
+            .unwrap_or_else(|| default_interval(self.config.iterations))
+            .max(1)
+    }
+
+    /// Number of segments the chain is divided into
+    pub fn segment_count(&self) -> usize {
+        self.config.iterations.div_ceil(self.checkpoint_interval())
+    }
+
+    /// Computes the first chain value for an input
+    pub fn hash_input(input: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(CHAIN_DOMAIN);
+        hasher.update(input);
+        hasher.finalize().into()
+    }
+
+    pub fn initialize(&mut self, input: &[u8]) -> CryptoResult<()> {
+        if input.is_empty() {
+            return Err(CryptoError::InvalidInput("Input cannot be empty".into()));
+        }
+        self.config.validate()?;
+        if self.checkpoint_interval == Some(0) {
+            return Err(CryptoError::InvalidInput("Checkpoint interval must be at least 1".into()));
+        }
+
+        self.current = Self::hash_input(input);
+        self.checkpoints = vec![self.current];
+        self.position = 0;
+        self.start_time = Some(self.config.clock.now());
+
+        debug!(
+            iterations = self.config.iterations,
+            interval = self.checkpoint_interval(),
+            "Hash chain initialized"
+        );
+
+        Ok(())
+    }
+
+    /// Check if the chain has been initialized with input data
+    pub fn is_initialized(&self) -> bool {
+        !self.checkpoints.is_empty()
+    }
+
+    /// Computes the next segment of the chain and records its checkpoint
+    pub fn iterate(&mut self) -> CryptoResult<()> {
+        if !self.is_initialized() {
+            return Err(CryptoError::InvalidState("VDF not initialized".into()));
+        }
+
+        if self.is_complete() {
+            return Err(CryptoError::InvalidState(
+                format!("Chain already complete after {} iterations", self.config.iterations)
+            ));
+        }
+
+        let steps = self.checkpoint_interval().min(self.config.iterations - self.position);
+        self.current = hash_steps(self.current, steps);
+        self.position += steps;
+        self.checkpoints.push(self.current);
+
+        debug!(position = self.position, total = self.config.iterations, "Recorded checkpoint");
+
+        Ok(())
+    }
+
+    /// Computes the remaining chain
+    pub fn run(&mut self) -> CryptoResult<()> {
+        while !self.is_complete() {
+            self.iterate()?;
+        }
+
+        info!(
+            iterations = self.config.iterations,
+            elapsed = ?self.start_time.map(|start| self.config.clock.elapsed_since(start)),
+            "Hash chain complete"
+        );
+
+        Ok(())
+    }
+
+    /// Check if computation is complete
+    pub fn is_complete(&self) -> bool {
+        self.is_initialized() && self.position == self.config.iterations
+    }
+
+    /// Get the chain output if computation is complete
+    pub fn get_output(&self) -> CryptoResult<[u8; 32]> {
+        if !self.is_complete() {
+            return Err(CryptoError::InvalidState(
+                format!("Computation not complete: {}/{} iterations",
+                        self.position, self.config.iterations)
+            ));
+        }
+        Ok(self.current)
+    }
+
+    /// Opens a single segment, for verifiers that pick their own samples
+    pub fn open_segment(&self, index: usize) -> CryptoResult<SegmentOpening> {
+        if !self.is_complete() {
+            return Err(CryptoError::InvalidState("Cannot open segment of incomplete chain".into()));
+        }
+        let tree = MerkleTree::new(&self.checkpoints)?;
+        open_segment(&tree, &self.checkpoints, index)
+    }
+
+    /// Generate proof of computation
+    ///
+    /// The opened segments are derived from the Merkle root, so they are
+    /// fixed only after every checkpoint has been committed.
+    pub fn generate_proof(&self) -> CryptoResult<HashChainProof> {
+        if !self.is_complete() {
+            return Err(CryptoError::InvalidState(
+                format!("Cannot generate proof: {}/{} iterations complete",
+                        self.position, self.config.iterations)
+            ));
+        }
+
+        let tree = MerkleTree::new(&self.checkpoints)?;
+        let merkle_root = tree.root();
+        let last = self.checkpoints.len() - 1;
+
+        let openings = sample_segments(&merkle_root, self.segment_count(), self.config.verification_steps)
+            .into_iter()
+            .map(|index| open_segment(&tree, &self.checkpoints, index))
+            .collect::<CryptoResult<Vec<_>>>()?;
+
+        let computation_time = self.config.clock.elapsed_since(
+            self.start_time.expect("start_time should be set during initialization")
+        );
+
+        debug!(
+            ?merkle_root,
+            openings = openings.len(),
+            ?computation_time,
+            "Generating hash chain proof"
+        );
+
+        Ok(HashChainProof {
+            input_hash: self.checkpoints[0],
+            output: self.current,
+            iterations: self.config.iterations,
+            checkpoint_interval: self.checkpoint_interval(),
+            merkle_root,
+            openings,
+            first_path: tree.path(0).expect("first checkpoint exists"),
+            last_path: tree.path(last).expect("last checkpoint exists"),
+            computation_time,
+        })
+    }
+}
+
+/// Verifies hash-chain proofs by recomputing sampled segments
+///
+/// Verification cost is `verification_steps` segments rather than the whole
+/// chain, so it can be tuned independently of the delay. The verifier fixes
+/// the checkpoint interval itself rather than taking the prover's.
+#[derive(Debug, Clone)]
+pub struct HashChainVerifier {
+    /// Chain length a valid proof must cover
+    expected_iterations: usize,
+    /// Number of hashes between checkpoints a valid proof must use
+    checkpoint_interval: usize,
+    /// Number of segments to recompute
+    verification_steps: usize,
+}
+
+impl HashChainVerifier {
+    /// Creates a verifier matching a VDF configuration
+    pub fn for_config(config: &TemporalConfig) -> Self {
+        Self {
+            expected_iterations: config.iterations,
+            checkpoint_interval: default_interval(config.iterations),
+            verification_steps: config.verification_steps,
+        }
+    }
+
+    /// Expects proofs from a chain built `with_checkpoint_interval(interval)`
+    pub fn with_checkpoint_interval(mut self, interval: usize) -> Self {
+        self.checkpoint_interval = interval;
+        self
+    }
+
+    /// Checks a proof against the input commitment and claimed output
+    pub fn verify(
+        &self,
+        input_hash: &[u8; 32],
+        output: &[u8; 32],
+        proof: &HashChainProof,
+    ) -> Verdict {
+        if let Err(reason) = self.check_parameters(proof) {
+            return reject(reason);
+        }
+
+        if &proof.input_hash != input_hash {
+            return reject("input hash does not match input commitment");
+        }
+
+        if &proof.output != output {
+            return reject("output does not match claimed output");
+        }
+
+        let segments = proof.iterations.div_ceil(proof.checkpoint_interval);
+        let leaf_count = segments + 1;
+
+        if !verify_path(&proof.merkle_root, &proof.input_hash, 0, leaf_count, &proof.first_path) {
+            return reject("first checkpoint is not committed to the input");
+        }
+
+        if !verify_path(&proof.merkle_root, &proof.output, segments, leaf_count, &proof.last_path) {
+            return reject("last checkpoint is not committed to the output");
+        }
+
+        let expected = sample_segments(&proof.merkle_root, segments, self.verification_steps);
+        if proof.openings.len() != expected.len()
+            || proof.openings.iter().zip(&expected).any(|(opening, &index)| opening.index != index)
+        {
+            return reject("openings do not match the segments sampled from the Merkle root");
+        }
+
+        for opening in &proof.openings {
+            if let Err(reason) = check_opening(proof, opening) {
+                return reject(reason);
+            }
+        }
+
+        debug!(checked = proof.openings.len(), segments, "Hash chain proof accepted");
+        Verdict::Accepted
+    }
+
+    /// Checks a segment the verifier chose itself against a proof's commitment
+    pub fn verify_opening(&self, proof: &HashChainProof, opening: &SegmentOpening) -> Verdict {
+        match self.check_parameters(proof).and_then(|()| check_opening(proof, opening)) {
+            Ok(()) => Verdict::Accepted,
+            Err(reason) => reject(reason),
+        }
+    }
+
+    /// Checks the proof covers the expected chain with the expected segments
+    fn check_parameters(&self, proof: &HashChainProof) -> Result<(), String> {
+        if self.expected_iterations == 0 || self.checkpoint_interval == 0 {
+            return Err("verifier needs at least one iteration and a positive checkpoint interval".into());
+        }
+        if proof.iterations != self.expected_iterations {
+            return Err(format!(
+                "expected {} iterations, proof claims {}",
+                self.expected_iterations, proof.iterations
+            ));
+        }
+        if proof.checkpoint_interval != self.checkpoint_interval {
+            return Err(format!(
+                "expected checkpoint interval {}, proof uses {}",
+                self.checkpoint_interval, proof.checkpoint_interval
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Checkpoint interval splitting a chain into about `DEFAULT_SEGMENTS` segments
+fn default_interval(iterations: usize) -> usize {
+    iterations.div_ceil(DEFAULT_SEGMENTS).max(1)
+}
+
+/// Checks a segment's Merkle paths and recomputes its hashes
+fn check_opening(proof: &HashChainProof, opening: &SegmentOpening) -> Result<(), String> {
+    if proof.checkpoint_interval == 0 {
+        return Err("checkpoint interval must be at least 1".into());
+    }
+
+    let segments = proof.iterations.div_ceil(proof.checkpoint_interval);
+    if opening.index >= segments {
+        return Err(format!("segment {} out of range ({} segments)", opening.index, segments));
+    }
+
+    let leaf_count = segments + 1;
+    if !verify_path(&proof.merkle_root, &opening.start, opening.index, leaf_count, &opening.start_path)
+        || !verify_path(&proof.merkle_root, &opening.end, opening.index + 1, leaf_count, &opening.end_path)
+    {
+        return Err(format!("segment {} is not committed by the Merkle root", opening.index));
+    }
+
+    let offset = opening.index * proof.checkpoint_interval;
+    let steps = proof.checkpoint_interval.min(proof.iterations - offset);
+    if hash_steps(opening.start, steps) != opening.end {
+        return Err(format!("segment {} does not recompute", opening.index));
+    }
+
+    Ok(())
+}
+
+fn open_segment(tree: &MerkleTree, checkpoints: &[[u8; 32]], index: usize) -> CryptoResult<SegmentOpening> {
+    if index + 1 >= checkpoints.len() {
+        return Err(CryptoError::InvalidInput(format!("Segment {} out of range", index)));
+    }
+
+    Ok(SegmentOpening {
+        index,
+        start: checkpoints[index],
+        start_path: tree.path(index).expect("index checked above"),
+        end: checkpoints[index + 1],
+        end_path: tree.path(index + 1).expect("index checked above"),
+    })
+}
+
+/// Derives `count` segment indices from the Merkle root
+fn sample_segments(root: &[u8; 32], segments: usize, count: usize) -> Vec<usize> {
+    (0..count as u64)
+        .map(|j| {
+            let mut hasher = Sha256::new();
+            hasher.update(SAMPLE_DOMAIN);
+            hasher.update(root);
+            hasher.update(j.to_le_bytes());
+            let digest: [u8; 32] = hasher.finalize().into();
+            let mut word = [0u8; 8];
+            word.copy_from_slice(&digest[..8]);
+            (u64::from_le_bytes(word) % segments as u64) as usize
+        })
+        .collect()
+}
+
+fn hash_steps(mut value: [u8; 32], steps: usize) -> [u8; 32] {
+    for _ in 0..steps {
+        value = Sha256::digest(value).into();
+    }
+    value
+}
+
+fn reject(reason: impl Into<String>) -> Verdict {
+    let reason = reason.into();
+    warn!(%reason, "Hash chain proof rejected");
+    Verdict::Rejected(reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(iterations: usize, verification_steps: usize) -> TemporalConfig {
+        TemporalConfig {
+            iterations,
+            verification_steps,
+            ..Default::default()
+        }
+    }
+
+    fn completed_chain(config: TemporalConfig, interval: usize) -> CryptoResult<HashChainVDF> {
+        let mut vdf = HashChainVDF::new(config).with_checkpoint_interval(interval);
+        vdf.initialize(b"Hash chain input")?;
+        vdf.run()?;
+        Ok(vdf)
+    }
+
+    #[test]
+    fn test_chain_matches_direct_hashing() -> CryptoResult<()> {
+        let vdf = completed_chain(config(1000, 4), 64)?;
+        let expected = hash_steps(HashChainVDF::hash_input(b"Hash chain input"), 1000);
+        assert_eq!(vdf.get_output()?, expected);
+        assert_eq!(vdf.segment_count(), 16);
+        Ok(())
+    }
+
+    #[test]
+    fn test_spot_check_verification() -> CryptoResult<()> {
+        let config = config(1000, 5);
+        let vdf = completed_chain(config.clone(), 64)?;
+        let proof = vdf.generate_proof()?;
+        assert_eq!(proof.openings().len(), 5);
+
+        let input_hash = HashChainVDF::hash_input(b"Hash chain input");
+        let verifier = HashChainVerifier::for_config(&config).with_checkpoint_interval(64);
+        assert_eq!(verifier.verify(&input_hash, &vdf.get_output()?, &proof), Verdict::Accepted);
+
+        // The prover cannot pick its own segment size
+        let verdict = HashChainVerifier::for_config(&config).verify(&input_hash, &vdf.get_output()?, &proof);
+        assert!(!verdict.is_accepted());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tampered_segment_rejected() -> CryptoResult<()> {
+        let config = config(512, 8);
+        let vdf = completed_chain(config.clone(), 32)?;
+        let verifier = HashChainVerifier::for_config(&config).with_checkpoint_interval(32);
+        let input_hash = HashChainVDF::hash_input(b"Hash chain input");
+        let output = vdf.get_output()?;
+
+        let mut proof = vdf.generate_proof()?;
+        proof.openings[0].end[0] ^= 1;
+        assert!(!verifier.verify(&input_hash, &output, &proof).is_accepted());
+
+        let mut proof = vdf.generate_proof()?;
+        proof.merkle_root[0] ^= 1;
+        assert!(!verifier.verify(&input_hash, &output, &proof).is_accepted());
+
+        let proof = vdf.generate_proof()?;
+        assert!(!verifier.verify(&input_hash, &[0u8; 32], &proof).is_accepted());
+        Ok(())
+    }
+
+    #[test]
+    fn test_verifier_chosen_segments() -> CryptoResult<()> {
+        let config = config(300, 2);
+        let vdf = completed_chain(config.clone(), 7)?;
+        let proof = vdf.generate_proof()?;
+        let verifier = HashChainVerifier::for_config(&config).with_checkpoint_interval(7);
+
+        // The last segment is shorter than the checkpoint interval
+        for index in [0, 20, vdf.segment_count() - 1] {
+            let opening = vdf.open_segment(index)?;
+            assert!(verifier.verify_opening(&proof, &opening).is_accepted());
+        }
+        assert!(vdf.open_segment(vdf.segment_count()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_interval_and_empty_chain() -> CryptoResult<()> {
+        let config = config(3000, 4);
+        let mut vdf = HashChainVDF::new(config.clone());
+        vdf.initialize(b"Hash chain input")?;
+        vdf.run()?;
+        let mut proof = vdf.generate_proof()?;
+        let input_hash = HashChainVDF::hash_input(b"Hash chain input");
+        let output = vdf.get_output()?;
+        assert!(HashChainVerifier::for_config(&config).verify(&input_hash, &output, &proof).is_accepted());
+
+        // A verifier expecting no iterations rejects instead of dividing by zero
+        proof.iterations = 0;
+        let empty = HashChainVerifier::for_config(&TemporalConfig { iterations: 0, ..config });
+        assert!(!empty.verify(&input_hash, &output, &proof).is_accepted());
+        Ok(())
+    }
+
+    #[test]
+    fn test_incomplete_chain() -> CryptoResult<()> {
+        let mut vdf = HashChainVDF::new(config(100, 2)).with_checkpoint_interval(10);
+        assert!(vdf.iterate().is_err());
+
+        vdf.initialize(b"Hash chain input")?;
+        vdf.iterate()?;
+        assert!(vdf.get_output().is_err());
+        assert!(vdf.generate_proof().is_err());
+        Ok(())
+    }
+}
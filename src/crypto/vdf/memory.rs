@@ -0,0 +1,116 @@
+//! Memory-hard work for temporal VDF iterations
+//! Location: src/crypto/vdf/memory.rs
+//!
+//! Each iteration fills a `memory_size` buffer with a SHA-256 chain and then
+//! walks it in data-dependent order, overwriting every block it visits, in the
+//! style of scrypt's ROMix with Argon2's in-place updates. Custom hardware
+//! gains little on the delay because every step needs the whole buffer.
+//!
+//! The resulting digest masks the iteration's state and is chained into its
+//! transcript entry, which seeds the next iteration's work, so neither the
+//! output nor the final commitment can be produced without it.
+
+use sha2::{Sha256, Digest};
+
+/// Size in bytes of one block of working memory
+pub const BLOCK_SIZE: usize = 32;
+/// Smallest working buffer accepted in memory-hard mode
+pub const MIN_MEMORY_SIZE: usize = 1024;
+
+/// Domain tag for deriving the per-iteration seed
+const SEED_DOMAIN: &[u8] = b"mcafee/vdf/memory/seed/v1";
+/// Domain tag for filling the buffer
+const FILL_DOMAIN: &[u8] = b"mcafee/vdf/memory/fill/v1";
+/// Domain tag for the random walk
+const WALK_DOMAIN: &[u8] = b"mcafee/vdf/memory/walk/v1";
+/// Domain tag for the state mask keystream
+const MASK_DOMAIN: &[u8] = b"mcafee/vdf/memory/mask/v1";
+
+/// Derives the seed of an iteration's memory work
+///
+/// `previous` is the transcript entry of the previous iteration, or the
+/// initial state hash for the first one. That entry covers the previous
+/// digest and state, so the memory work forms a sequential chain.
+pub(crate) fn iteration_seed(previous: &[u8; 32], iteration: usize) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(SEED_DOMAIN);
+    hasher.update(previous);
+    hasher.update((iteration as u64).to_le_bytes());
+    hasher.finalize().into()
+}
+
+/// Fills and walks a `memory_size` buffer seeded by `seed`, returning its digest
+pub fn memory_hard_digest(seed: &[u8; 32], memory_size: usize) -> [u8; 32] {
+    let blocks = (memory_size / BLOCK_SIZE).max(1);
+    let mut memory = vec![[0u8; BLOCK_SIZE]; blocks];
+
+    let mut current: [u8; 32] = Sha256::new()
+        .chain_update(FILL_DOMAIN)
+        .chain_update(seed)
+        .finalize()
+        .into();
+    for block in memory.iter_mut() {
+        *block = current;
+        current = Sha256::digest(current).into();
+    }
+
+    for step in 0..blocks {
+        let index = (u64::from_le_bytes(current[..8].try_into().expect("8 bytes")) % blocks as u64) as usize;
+        current = Sha256::new()
+            .chain_update(WALK_DOMAIN)
+            .chain_update(current)
+            .chain_update(memory[index])
+            .chain_update((step as u64).to_le_bytes())
+            .finalize()
+            .into();
+        memory[index] = current;
+    }
+
+    current
+}
+
+/// XORs `data` with a keystream derived from an iteration's digest
+pub(crate) fn apply_mask(data: &mut [u8], digest: &[u8; 32]) {
+    for (counter, chunk) in data.chunks_mut(BLOCK_SIZE).enumerate() {
+        let block: [u8; 32] = Sha256::new()
+            .chain_update(MASK_DOMAIN)
+            .chain_update(digest)
+            .chain_update((counter as u64).to_le_bytes())
+            .finalize()
+            .into();
+        for (byte, key) in chunk.iter_mut().zip(block) {
+            *byte ^= key;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_is_deterministic_and_size_dependent() {
+        let seed = [3u8; 32];
+        assert_eq!(memory_hard_digest(&seed, 4096), memory_hard_digest(&seed, 4096));
+        assert_ne!(memory_hard_digest(&seed, 4096), memory_hard_digest(&seed, 8192));
+        assert_ne!(memory_hard_digest(&seed, 4096), memory_hard_digest(&[4u8; 32], 4096));
+    }
+
+    #[test]
+    fn test_seed_depends_on_previous_entry_and_iteration() {
+        let seed = iteration_seed(&[1u8; 32], 0);
+        assert_eq!(seed, iteration_seed(&[1u8; 32], 0));
+        assert_ne!(seed, iteration_seed(&[2u8; 32], 0));
+        assert_ne!(seed, iteration_seed(&[1u8; 32], 1));
+    }
+
+    #[test]
+    fn test_mask_is_an_involution() {
+        let original: Vec<u8> = (0..100u8).collect();
+        let mut data = original.clone();
+        apply_mask(&mut data, &[7u8; 32]);
+        assert_ne!(data, original);
+        apply_mask(&mut data, &[7u8; 32]);
+        assert_eq!(data, original);
+    }
+}
@@ -3,5 +3,20 @@
 
 pub mod temporal;
 pub mod state;
+pub mod verifier;
+pub mod hashchain;
+pub mod memory;
+pub mod progress;
+pub mod typestate;
+pub mod batch;
+pub mod context;
+pub mod calibration;
 
-pub use temporal::TemporalVDF;
\ No newline at end of file
+pub use temporal::{IterationMode, TemporalProof, TemporalVDF};
+pub use batch::{BatchOutput, VDFBatch};
+pub use calibration::{Backend, CalibrationProfile, Calibrator, Difficulty};
+pub use context::ProofContext;
+pub use progress::{ProgressEvent, ProgressObserver};
+pub use hashchain::{HashChainVDF, HashChainVerifier};
+pub use typestate::{Lifecycle, Step, Vdf};
+pub use verifier::{TemporalVerifier, Verdict};
\ No newline at end of file
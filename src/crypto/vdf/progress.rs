@@ -0,0 +1,95 @@
+//! Progress reporting for long-running VDF computations
+//! Location: src/crypto/vdf/progress.rs
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+/// Notable point in a VDF computation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// An iteration completed
+    Iteration {
+        /// Number of iterations completed so far
+        iteration: usize,
+        /// Number of iterations that completes the computation
+        total: usize,
+        /// Total computation time, including time before a resume
+        elapsed: Duration,
+        /// Estimated time until the computation completes
+        estimated_remaining: Duration,
+    },
+    /// A snapshot of the computation was written
+    Checkpoint {
+        /// Number of iterations the snapshot holds
+        iteration: usize,
+        /// Location of the snapshot
+        path: PathBuf,
+    },
+    /// The last iteration completed
+    Complete {
+        /// Number of iterations performed
+        total: usize,
+        /// Total computation time
+        elapsed: Duration,
+    },
+}
+
+impl ProgressEvent {
+    /// Estimated time until completion, zero once complete
+    pub fn estimated_remaining(&self) -> Option<Duration> {
+        match self {
+            ProgressEvent::Iteration { estimated_remaining, .. } => Some(*estimated_remaining),
+            ProgressEvent::Complete { .. } => Some(Duration::ZERO),
+            ProgressEvent::Checkpoint { .. } => None,
+        }
+    }
+}
+
+/// Receiver of progress events
+///
+/// Implemented for closures taking `&ProgressEvent` and for
+/// `mpsc::Sender<ProgressEvent>`. Observers run on the iterating thread, so
+/// they should return quickly.
+pub trait ProgressObserver: Send + Sync {
+    fn on_progress(&self, event: &ProgressEvent);
+}
+
+impl<F> ProgressObserver for F
+where
+    F: Fn(&ProgressEvent) + Send + Sync,
+{
+    fn on_progress(&self, event: &ProgressEvent) {
+        self(event)
+    }
+}
+
+impl ProgressObserver for mpsc::Sender<ProgressEvent> {
+    fn on_progress(&self, event: &ProgressEvent) {
+        // A dropped receiver just means nobody is watching any more
+        let _ = self.send(event.clone());
+    }
+}
+
+/// Observers registered on a VDF
+#[derive(Clone, Default)]
+pub(crate) struct Observers(Vec<Arc<dyn ProgressObserver>>);
+
+impl Observers {
+    pub(crate) fn push(&mut self, observer: Arc<dyn ProgressObserver>) {
+        self.0.push(observer);
+    }
+
+    pub(crate) fn notify(&self, event: ProgressEvent) {
+        for observer in &self.0 {
+            observer.on_progress(&event);
+        }
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Observers({})", self.0.len())
+    }
+}
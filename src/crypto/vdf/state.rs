@@ -1,18 +1,35 @@
 //! Temporal VDF state management
 //! Location: src/crypto/vdf/state.rs
 
+use crate::crypto::utils::clock::{self, Clock};
 use crate::error::{CryptoError, CryptoResult};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tracing::{debug, info, warn};
+use tracing::{debug, info};
 
-/// Maximum number of iterations for temporal VDF
+/// Default maximum number of iterations for temporal VDF
 pub const MAX_ITERATIONS: usize = 4;
 
+/// Timing data recorded while a VDF computation progressed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimingRecord {
+    /// Minimum spacing required between iterations
+    pub min_iteration_time: Duration,
+    /// Whether the spacing was enforced
+    pub enforced: bool,
+    /// Total time spent on the computation, including time before a resume
+    pub total_elapsed: Duration,
+    /// Time between each iteration's completion and the previous one (or the start)
+    pub iteration_durations: Vec<Duration>,
+}
+
 /// Represents the state of a VDF computation
 #[derive(Debug, Clone)]
 pub struct VDFState {
     /// Current iteration number
     iteration: usize,
+    /// Number of iterations that completes the computation
+    max_iterations: usize,
     /// Start time of computation
     start_time: Instant,
     /// Minimum time per iteration
@@ -21,29 +38,104 @@ pub struct VDFState {
     enforce_timing: bool,
     /// Last iteration completion time
     last_iteration: Option<Instant>,
+    /// Time source for timing checks
+    clock: Arc<dyn Clock>,
+    /// Time between consecutive iteration completions
+    iteration_durations: Vec<Duration>,
+    /// Time spent before this state was restored from a snapshot
+    prior_elapsed: Duration,
+    /// Whether the first iteration is also spaced from the start time
+    pace_first: bool,
 }
 
 impl VDFState {
     /// Creates a new VDF state tracker
     pub fn new(min_iteration_time: Duration, enforce_timing: bool) -> Self {
+        Self::with_iterations(MAX_ITERATIONS, min_iteration_time, enforce_timing)
+    }
+
+    /// Creates a new VDF state tracker that completes after `max_iterations`
+    pub fn with_iterations(
+        max_iterations: usize,
+        min_iteration_time: Duration,
+        enforce_timing: bool,
+    ) -> Self {
         Self {
             iteration: 0,
+            max_iterations,
             start_time: Instant::now(),
             min_iteration_time,
             enforce_timing,
             last_iteration: None,
+            clock: clock::system_clock(),
+            iteration_durations: Vec::new(),
+            prior_elapsed: Duration::ZERO,
+            pace_first: false,
         }
     }
 
-    /// Advances the state by one iteration
-    pub fn advance(&mut self) -> CryptoResult<()> {
-        if self.iteration >= MAX_ITERATIONS {
-            return Err(CryptoError::InvalidState("Maximum iterations reached".into()));
+    /// Spaces the first iteration from the start time like every later one
+    ///
+    /// Without this the first iteration may complete immediately, so a run
+    /// of `n` iterations only guarantees `n - 1` spacings.
+    pub fn paced_from_start(mut self) -> Self {
+        self.pace_first = true;
+        self
+    }
+
+    /// Measures time on the given clock, restarting the computation timer
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.start_time = clock.now();
+        self.clock = clock;
+        self
+    }
+
+    /// Rebuilds a state tracker for a computation resumed from a snapshot
+    ///
+    /// The next iteration is spaced from the moment of the restore, since the
+    /// time of the last completed iteration is not known.
+    pub(crate) fn restore(
+        mut self,
+        iteration: usize,
+        prior_elapsed: Duration,
+        iteration_durations: Vec<Duration>,
+    ) -> CryptoResult<Self> {
+        if iteration > self.max_iterations || iteration_durations.len() != iteration {
+            return Err(CryptoError::InvalidInput("Inconsistent VDF progress".into()));
         }
 
-        // Check timing requirements
-        if let Some(last) = self.last_iteration {
-            let elapsed = last.elapsed();
+        let now = self.clock.now();
+        self.iteration = iteration;
+        self.start_time = now;
+        self.last_iteration = (iteration > 0).then_some(now);
+        self.iteration_durations = iteration_durations;
+        self.prior_elapsed = prior_elapsed;
+        Ok(self)
+    }
+
+    /// Moment the next iteration is spaced from, if any
+    fn pacing_reference(&self) -> Option<Instant> {
+        match self.last_iteration {
+            Some(last) => Some(last),
+            None if self.pace_first => Some(self.start_time),
+            None => None,
+        }
+    }
+
+    /// Returns how long to wait before the next iteration meets the timing requirement
+    pub fn time_until_ready(&self) -> Duration {
+        match self.pacing_reference() {
+            Some(last) if self.enforce_timing => {
+                self.min_iteration_time.saturating_sub(self.clock.elapsed_since(last))
+            }
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Checks the timing requirement without advancing
+    pub fn check_timing(&self) -> CryptoResult<()> {
+        if let Some(last) = self.pacing_reference() {
+            let elapsed = self.clock.elapsed_since(last);
             if self.enforce_timing && elapsed < self.min_iteration_time {
                 return Err(CryptoError::TimingViolation {
                     expected: self.min_iteration_time,
@@ -51,14 +143,30 @@ impl VDFState {
                 });
             }
         }
+        Ok(())
+    }
+
+    /// Advances the state by one iteration
+    pub fn advance(&mut self) -> CryptoResult<()> {
+        if self.iteration >= self.max_iterations {
+            return Err(CryptoError::InvalidState("Maximum iterations reached".into()));
+        }
+
+        // Check timing requirements
+        self.check_timing()?;
 
         debug!(iteration = self.iteration + 1, "Starting VDF iteration");
+        let now = self.clock.now();
+        let since = self.last_iteration.unwrap_or(self.start_time);
+        self.iteration_durations.push(now.saturating_duration_since(since));
         self.iteration += 1;
-        self.last_iteration = Some(Instant::now());
+        self.last_iteration = Some(now);
 
         info!(
             iteration = self.iteration,
-            elapsed = ?self.start_time.elapsed(),
+            total = self.max_iterations,
+            elapsed = ?self.elapsed(),
+            remaining = ?self.estimated_remaining(),
             "Completed VDF iteration"
         );
 
@@ -72,19 +180,66 @@ impl VDFState {
 
     /// Returns whether computation is complete
     pub fn is_complete(&self) -> bool {
-        self.iteration >= MAX_ITERATIONS
+        self.iteration >= self.max_iterations
+    }
+
+    /// Returns the number of iterations that completes the computation
+    pub fn max_iterations(&self) -> usize {
+        self.max_iterations
+    }
+
+    /// Returns the clock this state measures time on
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
     }
 
     /// Returns total elapsed time
     pub fn elapsed(&self) -> Duration {
-        self.start_time.elapsed()
+        self.prior_elapsed + self.clock.elapsed_since(self.start_time)
+    }
+
+    /// Returns the time between consecutive iteration completions
+    pub fn iteration_durations(&self) -> &[Duration] {
+        &self.iteration_durations
+    }
+
+    /// Estimates the time left until the computation completes
+    ///
+    /// Remaining iterations are assumed to take as long as the average so
+    /// far, and never less than `min_iteration_time` when timing is enforced.
+    pub fn estimated_remaining(&self) -> Duration {
+        let remaining = self.max_iterations.saturating_sub(self.iteration) as u32;
+        if remaining == 0 {
+            return Duration::ZERO;
+        }
+
+        let average = match self.iteration_durations.len() {
+            0 => Duration::ZERO,
+            n => self.iteration_durations.iter().sum::<Duration>() / n as u32,
+        };
+        let per_iteration = if self.enforce_timing {
+            average.max(self.min_iteration_time)
+        } else {
+            average
+        };
+        per_iteration * remaining
+    }
+
+    /// Returns the timing data recorded so far
+    pub fn timing_record(&self) -> TimingRecord {
+        TimingRecord {
+            min_iteration_time: self.min_iteration_time,
+            enforced: self.enforce_timing,
+            total_elapsed: self.elapsed(),
+            iteration_durations: self.iteration_durations.clone(),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::thread;
+    use crate::crypto::utils::ManualClock;
 
     #[test]
     fn test_state_progression() -> CryptoResult<()> {
@@ -102,10 +257,76 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_custom_iteration_count() -> CryptoResult<()> {
+        let mut state = VDFState::with_iterations(7, Duration::from_millis(1), false);
+
+        for _ in 0..7 {
+            assert!(!state.is_complete());
+            state.advance()?;
+        }
+
+        assert!(state.is_complete());
+        assert!(state.advance().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_time_until_ready_and_record() -> CryptoResult<()> {
+        let clock = ManualClock::new();
+        let min_time = Duration::from_secs(10);
+        let mut state = VDFState::with_iterations(3, min_time, true)
+            .with_clock(Arc::new(clock.clone()));
+
+        assert_eq!(state.time_until_ready(), Duration::ZERO);
+        clock.advance(Duration::from_secs(2));
+        state.advance()?;
+
+        assert_eq!(state.time_until_ready(), min_time);
+        clock.advance(Duration::from_secs(4));
+        assert_eq!(state.time_until_ready(), Duration::from_secs(6));
+        assert!(state.check_timing().is_err());
+
+        clock.sleep(state.time_until_ready());
+        state.check_timing()?;
+        state.advance()?;
+
+        let record = state.timing_record();
+        assert!(record.enforced);
+        assert_eq!(record.iteration_durations, vec![Duration::from_secs(2), min_time]);
+        assert_eq!(record.total_elapsed, Duration::from_secs(12));
+        Ok(())
+    }
+
+    #[test]
+    fn test_estimated_remaining() -> CryptoResult<()> {
+        let clock = ManualClock::new();
+        let mut state = VDFState::with_iterations(4, Duration::from_secs(10), true)
+            .with_clock(Arc::new(clock.clone()));
+
+        // Nothing measured yet, so the enforced minimum is the estimate
+        assert_eq!(state.estimated_remaining(), Duration::from_secs(40));
+
+        clock.advance(Duration::from_secs(2));
+        state.advance()?;
+        clock.advance(Duration::from_secs(30));
+        state.advance()?;
+        assert_eq!(state.estimated_remaining(), Duration::from_secs(32));
+
+        clock.advance(Duration::from_secs(10));
+        state.advance()?;
+        clock.advance(Duration::from_secs(10));
+        state.advance()?;
+        assert_eq!(state.estimated_remaining(), Duration::ZERO);
+        Ok(())
+    }
+
     #[test]
     fn test_timing_enforcement() {
+        let clock = ManualClock::new();
         let min_time = Duration::from_millis(50);
-        let mut state = VDFState::new(min_time, true);
+        let mut state = VDFState::new(min_time, true).with_clock(Arc::new(clock.clone()));
 
         // First iteration should succeed
         state.advance().unwrap();
@@ -116,8 +337,39 @@ mod tests {
             Err(CryptoError::TimingViolation { .. })
         ));
 
+        // Just short of the minimum still fails, reporting the observed gap
+        clock.advance(min_time - Duration::from_millis(1));
+        match state.advance() {
+            Err(CryptoError::TimingViolation { expected, actual }) => {
+                assert_eq!(expected, min_time);
+                assert_eq!(actual, min_time - Duration::from_millis(1));
+            }
+            other => panic!("expected timing violation, got {:?}", other),
+        }
+
         // Wait and try again
-        thread::sleep(min_time);
+        clock.advance(Duration::from_millis(1));
         assert!(state.advance().is_ok());
+        assert_eq!(state.elapsed(), min_time);
+    }
+
+    #[test]
+    fn test_first_iteration_paced_from_start() -> CryptoResult<()> {
+        let clock = ManualClock::new();
+        let min_time = Duration::from_secs(10);
+        let mut state = VDFState::with_iterations(2, min_time, true)
+            .with_clock(Arc::new(clock.clone()))
+            .paced_from_start();
+
+        assert_eq!(state.time_until_ready(), min_time);
+        assert!(matches!(state.advance(), Err(CryptoError::TimingViolation { .. })));
+
+        clock.advance(min_time);
+        state.advance()?;
+        clock.advance(min_time);
+        state.advance()?;
+        assert_eq!(state.iteration_durations(), &[min_time, min_time]);
+        assert_eq!(state.elapsed(), min_time * 2);
+        Ok(())
     }
 }
\ No newline at end of file
@@ -1,26 +1,248 @@
 //! Temporal XOR-based Verifiable Delay Function implementation
 //! Location: src/crypto/vdf/temporal.rs
 
+use super::context::ProofContext;
+use super::memory;
+use super::progress::{Observers, ProgressEvent, ProgressObserver};
+use super::state::{TimingRecord, VDFState};
+use crate::crypto::audit::{AuditEvent, AuditLog};
+use crate::crypto::utils::{self, cancel::{self, CancellationToken}, clock::{self, Clock}, codec::{self, Decoder, Encoder}};
 use crate::error::{CryptoError, CryptoResult};
 use sha2::{Sha256, Digest};
-use std::time::{Duration, Instant};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, info, warn};
 use rand::Rng;
 
-/// Number of iterations required for a complete cycle
+/// Default number of iterations required for a complete cycle
 pub const CYCLE_LENGTH: usize = 4;
 
+/// Magic bytes identifying a TemporalVDF snapshot
+const SNAPSHOT_MAGIC: &[u8; 8] = b"MCVDFSNP";
+/// Snapshot format version
+const SNAPSHOT_VERSION: u32 = 5;
+/// Magic bytes identifying an encoded TemporalProof
+const PROOF_MAGIC: &[u8; 8] = b"MCVDFPRF";
+/// Encoded proof format version
+const PROOF_VERSION: u32 = 1;
+/// Domain tag for hashing a state under its bound initial commitment
+const STATE_DOMAIN: &[u8] = b"mcafee/vdf/state/v1";
+/// Domain tag for chaining transcript entries
+const TRANSCRIPT_DOMAIN: &[u8] = b"mcafee/vdf/transcript/v1";
+
 /// Represents proof of temporal computation
 #[derive(Debug, Clone)]
 pub struct TemporalProof {
-    /// Hash of initial state
-    initial_state_hash: [u8; 32],
-    /// Hash of final state
-    final_state_hash: [u8; 32],
+    /// Hash of initial state, bound to the proof context
+    pub(crate) initial_state_hash: [u8; 32],
+    /// Hash of the initial shares before context binding
+    pub(crate) state_commitment: [u8; 32],
+    /// Domain and challenge the computation is bound to
+    pub(crate) context: ProofContext,
+    /// Last transcript entry, committing to the final state
+    pub(crate) final_state_hash: [u8; 32],
     /// Time taken for computation
-    computation_time: Duration,
+    pub(crate) computation_time: Duration,
     /// Number of iterations performed
-    iteration_count: usize,
+    pub(crate) iteration_count: usize,
+    /// Hash of the state after each iteration, in order
+    pub(crate) state_hashes: Vec<[u8; 32]>,
+    /// Per-iteration entries, each chaining a state hash onto the previous entry
+    pub(crate) transcript: Vec<[u8; 32]>,
+    /// Timing data recorded by the VDF state tracker
+    pub(crate) timing: TimingRecord,
+    /// Iteration mode the computation ran in
+    pub(crate) mode: IterationMode,
+    /// Working memory per iteration in bytes, zero in XOR mode
+    pub(crate) memory_size: usize,
+    /// Digest of each iteration's memory-hard work, in order
+    pub(crate) memory_digests: Vec<[u8; 32]>,
+}
+
+impl TemporalProof {
+    /// Commitment to the input state the computation started from
+    pub fn initial_state_hash(&self) -> [u8; 32] {
+        self.initial_state_hash
+    }
+
+    /// Hash of the initial shares before context binding
+    pub fn state_commitment(&self) -> [u8; 32] {
+        self.state_commitment
+    }
+
+    /// Domain and challenge the computation is bound to
+    pub fn context(&self) -> &ProofContext {
+        &self.context
+    }
+
+    /// Commitment to the state the computation ended in
+    pub fn final_state_hash(&self) -> [u8; 32] {
+        self.final_state_hash
+    }
+
+    /// Wall-clock time the prover reported for the computation
+    pub fn computation_time(&self) -> Duration {
+        self.computation_time
+    }
+
+    /// Number of iterations the prover claims to have performed
+    pub fn iteration_count(&self) -> usize {
+        self.iteration_count
+    }
+
+    /// Per-iteration state hashes recorded by the prover
+    pub fn state_hashes(&self) -> &[[u8; 32]] {
+        &self.state_hashes
+    }
+
+    /// Chained per-iteration entries, starting from the initial state hash
+    pub fn transcript(&self) -> &[[u8; 32]] {
+        &self.transcript
+    }
+
+    /// Per-iteration timing the prover recorded
+    pub fn timing(&self) -> &TimingRecord {
+        &self.timing
+    }
+
+    /// Iteration mode the prover ran in
+    pub fn mode(&self) -> IterationMode {
+        self.mode
+    }
+
+    /// Working memory per iteration the prover claims, zero in XOR mode
+    pub fn memory_size(&self) -> usize {
+        self.memory_size
+    }
+
+    /// Per-iteration digests of the memory-hard work
+    pub fn memory_digests(&self) -> &[[u8; 32]] {
+        &self.memory_digests
+    }
+
+    /// Encodes the proof for handing to a third-party verifier
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new();
+        encoder
+            .put_raw(PROOF_MAGIC)
+            .put_u32(PROOF_VERSION)
+            .put_raw(&self.initial_state_hash)
+            .put_raw(&self.state_commitment)
+            .put_bytes(self.context.domain())
+            .put_bytes(self.context.challenge())
+            .put_raw(&self.final_state_hash)
+            .put_duration(self.computation_time)
+            .put_u64(self.iteration_count as u64)
+            .put_u32(self.mode as u32)
+            .put_u64(self.memory_size as u64);
+        put_hashes(&mut encoder, &self.state_hashes);
+        put_hashes(&mut encoder, &self.transcript);
+        put_hashes(&mut encoder, &self.memory_digests);
+        encoder
+            .put_duration(self.timing.min_iteration_time)
+            .put_u32(self.timing.enforced as u32)
+            .put_duration(self.timing.total_elapsed)
+            .put_u64(self.timing.iteration_durations.len() as u64);
+        for duration in &self.timing.iteration_durations {
+            encoder.put_duration(*duration);
+        }
+        encoder.finish()
+    }
+
+    /// Decodes a proof written by `encode`
+    ///
+    /// Decoding only checks the format; the proof still has to be checked
+    /// with a `TemporalVerifier`.
+    pub fn decode(data: &[u8]) -> CryptoResult<Self> {
+        let mut decoder = Decoder::new(data);
+        if decoder.get_raw(PROOF_MAGIC.len())? != PROOF_MAGIC {
+            return Err(CryptoError::InvalidInput("Not a temporal proof".into()));
+        }
+        let version = decoder.get_u32()?;
+        if version != PROOF_VERSION {
+            return Err(CryptoError::InvalidInput(format!("Unsupported proof version {}", version)));
+        }
+
+        let initial_state_hash = decoder.get_array32()?;
+        let state_commitment = decoder.get_array32()?;
+        let context = ProofContext::new(decoder.get_bytes()?, decoder.get_bytes()?);
+        let final_state_hash = decoder.get_array32()?;
+        let computation_time = decoder.get_duration()?;
+        let iteration_count = decoder.get_u64()? as usize;
+        let mode = match decoder.get_u32()? {
+            0 => IterationMode::Xor,
+            1 => IterationMode::MemoryHard,
+            other => return Err(CryptoError::InvalidInput(format!("Unknown iteration mode {}", other))),
+        };
+        let memory_size = decoder.get_u64()? as usize;
+        let state_hashes = get_hashes(&mut decoder)?;
+        let transcript = get_hashes(&mut decoder)?;
+        let memory_digests = get_hashes(&mut decoder)?;
+
+        let min_iteration_time = decoder.get_duration()?;
+        let enforced = match decoder.get_u32()? {
+            0 => false,
+            1 => true,
+            other => return Err(CryptoError::InvalidInput(format!("Invalid timing flag {}", other))),
+        };
+        let total_elapsed = decoder.get_duration()?;
+        let duration_count = decoder.get_u64()?;
+        let iteration_durations = (0..duration_count)
+            .map(|_| decoder.get_duration())
+            .collect::<CryptoResult<Vec<_>>>()?;
+        decoder.finish()?;
+
+        Ok(Self {
+            initial_state_hash,
+            state_commitment,
+            context,
+            final_state_hash,
+            computation_time,
+            iteration_count,
+            state_hashes,
+            transcript,
+            timing: TimingRecord { min_iteration_time, enforced, total_elapsed, iteration_durations },
+            mode,
+            memory_size,
+            memory_digests,
+        })
+    }
+}
+
+fn put_hashes(encoder: &mut Encoder, hashes: &[[u8; 32]]) {
+    encoder.put_u64(hashes.len() as u64);
+    for hash in hashes {
+        encoder.put_raw(hash);
+    }
+}
+
+fn get_hashes(decoder: &mut Decoder<'_>) -> CryptoResult<Vec<[u8; 32]>> {
+    let count = decoder.get_u64()?;
+    (0..count).map(|_| decoder.get_array32()).collect()
+}
+
+/// Work performed by each iteration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IterationMode {
+    /// XOR round only
+    #[default]
+    Xor,
+    /// XOR round plus filling and walking `memory_size` bytes of memory
+    ///
+    /// Each iteration's digest masks the state, so the output is the input
+    /// XORed with a keystream only the memory work produces.
+    MemoryHard,
+}
+
+/// What to do when an iteration is requested before `min_iteration_time` has passed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimingPolicy {
+    /// Sleep until the iteration may complete
+    #[default]
+    Sleep,
+    /// Fail with `CryptoError::TimingViolation`
+    Reject,
 }
 
 /// Configuration for the temporal VDF
@@ -30,10 +252,24 @@ pub struct TemporalConfig {
     pub min_iteration_time: Duration,
     /// Whether to enforce strict timing requirements
     pub enforce_timing: bool,
-    /// Size of the working memory in bytes
+    /// How enforced timing is applied to early iterations
+    pub timing_policy: TimingPolicy,
+    /// Work performed by each iteration
+    pub mode: IterationMode,
+    /// Size of the working memory in bytes, used in memory-hard mode
     pub memory_size: usize,
-    /// Number of verification steps required
+    /// Number of hash-chain segments a verifier recomputes
+    ///
+    /// A prover that skipped a fraction `f` of the segments passes with
+    /// probability about `(1 - f)^verification_steps`. The samples derive from
+    /// the prover's own Merkle root, so a cheat can retry roots until one
+    /// passes; the default of 32 makes skipping half the chain cost about
+    /// 2^32 attempts, where 4 let it through in about 16.
     pub verification_steps: usize,
+    /// Number of sequential iterations (T) a complete computation performs
+    pub iterations: usize,
+    /// Time source used for timing enforcement
+    pub clock: Arc<dyn Clock>,
 }
 
 impl Default for TemporalConfig {
@@ -41,26 +277,195 @@ impl Default for TemporalConfig {
         Self {
             min_iteration_time: Duration::from_millis(100),
             enforce_timing: true,
+            timing_policy: TimingPolicy::default(),
+            mode: IterationMode::default(),
             memory_size: 1024 * 1024, // 1MB
-            verification_steps: 4,
+            verification_steps: 32,
+            iterations: CYCLE_LENGTH,
+            clock: clock::system_clock(),
         }
     }
 }
 
+impl TemporalConfig {
+    /// Checks that the configuration describes a computation that can complete
+    ///
+    /// The XOR round replaces every share with the XOR of all three, which
+    /// leaves the XOR of the shares unchanged. The output is therefore
+    /// recoverable after any number of rounds and `iterations` need not be a
+    /// multiple of `CYCLE_LENGTH`; it only has to be non-zero so that the
+    /// proof covers at least one round.
+    pub fn validate(&self) -> CryptoResult<()> {
+        if self.iterations == 0 {
+            return Err(CryptoError::InvalidInput(
+                "Iteration count must be at least 1".into()
+            ));
+        }
+        if self.mode == IterationMode::MemoryHard && self.memory_size < memory::MIN_MEMORY_SIZE {
+            return Err(CryptoError::InvalidInput(format!(
+                "Memory-hard mode needs at least {} bytes of memory",
+                memory::MIN_MEMORY_SIZE
+            )));
+        }
+        Ok(())
+    }
+
+    /// Memory parameter recorded in proofs, zero unless memory-hard
+    pub(crate) fn proof_memory_size(&self) -> usize {
+        match self.mode {
+            IterationMode::Xor => 0,
+            IterationMode::MemoryHard => self.memory_size,
+        }
+    }
+
+    /// Creates a fresh state tracker for this configuration
+    pub(crate) fn new_state(&self) -> VDFState {
+        VDFState::with_iterations(self.iterations, self.min_iteration_time, self.enforce_timing)
+            .with_clock(Arc::clone(&self.clock))
+            .paced_from_start()
+    }
+}
+
 /// Temporal VDF implementation using XOR operations
 #[derive(Debug)]
 pub struct TemporalVDF {
     pub config: TemporalConfig,
     state: Vec<Vec<u8>>,
-    current_iteration: usize,
-    start_time: Option<Instant>,
-    initial_hash: Option<[u8; 32]>,  // Add this field
+    /// Iteration count and timing, present once initialized
+    progress: Option<VDFState>,
+    /// Initial state hash bound to `context`
+    initial_hash: Option<[u8; 32]>,
+    /// Initial state hash before binding
+    state_commitment: Option<[u8; 32]>,
+    context: ProofContext,
+    state_hashes: Vec<[u8; 32]>,
+    transcript: Vec<[u8; 32]>,
+    memory_digests: Vec<[u8; 32]>,
+    observers: Observers,
+    /// Log receiving an event for every generated proof
+    audit: Option<Arc<AuditLog>>,
+}
+
+/// Everything an iteration's work depends on besides the state
+struct RoundInput {
+    initial_hash: [u8; 32],
+    previous: [u8; 32],
+    iteration: usize,
+    mode: IterationMode,
+    memory_size: usize,
+}
+
+impl RoundInput {
+    /// Performs the iteration's work on `state`
+    ///
+    /// In memory-hard mode the work includes a `memory_size` fill and walk
+    /// seeded from the previous transcript entry. Its digest masks every
+    /// share and is chained into the new transcript entry.
+    fn compute(&self, state: &[Vec<u8>]) -> Round {
+        // Perform XOR transformation
+        let mut new_state = Vec::with_capacity(3);
+        for (i, share) in state.iter().enumerate() {
+            let mut result = share.clone();
+            for (j, other) in state.iter().enumerate() {
+                if i != j {
+                    for (r, v) in result.iter_mut().zip(other) {
+                        *r ^= v;
+                    }
+                }
+            }
+            new_state.push(result);
+        }
+
+        let memory_digest = match self.mode {
+            IterationMode::Xor => None,
+            IterationMode::MemoryHard => {
+                let seed = memory::iteration_seed(&self.previous, self.iteration);
+                let digest = memory::memory_hard_digest(&seed, self.memory_size);
+                for share in &mut new_state {
+                    memory::apply_mask(share, &digest);
+                }
+                Some(digest)
+            }
+        };
+
+        let state_hash = hash_bound_state(&self.initial_hash, &new_state);
+        let link = chain_link(&self.previous, self.iteration, &state_hash, memory_digest.as_ref());
+
+        Round { state: new_state, state_hash, link, memory_digest }
+    }
+}
+
+/// Result of an iteration's work, installed once timing allows
+struct Round {
+    state: Vec<Vec<u8>>,
+    state_hash: [u8; 32],
+    link: [u8; 32],
+    memory_digest: Option<[u8; 32]>,
+}
+
+/// Hashes the concatenation of all shares in a VDF state
+pub(crate) fn hash_state(state: &[Vec<u8>]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for share in state {
+        hasher.update(share);
+    }
+    hasher.finalize().into()
+}
+
+/// Hashes an iteration's state together with the context-bound initial hash
+///
+/// Every state hash depends on the proof context, so moving a proof to
+/// another context means recomputing the states rather than one hash.
+pub(crate) fn hash_bound_state(initial_state_hash: &[u8; 32], state: &[Vec<u8>]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(STATE_DOMAIN);
+    hasher.update(initial_state_hash);
+    for share in state {
+        hasher.update(share);
+    }
+    hasher.finalize().into()
+}
+
+/// Chains an iteration's state hash and memory digest onto the previous transcript entry
+///
+/// The first entry chains from the initial state hash, so a transcript only
+/// verifies against the input commitment it was computed from.
+pub(crate) fn chain_link(
+    previous: &[u8; 32],
+    iteration: usize,
+    state_hash: &[u8; 32],
+    memory_digest: Option<&[u8; 32]>,
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(TRANSCRIPT_DOMAIN);
+    hasher.update(previous);
+    hasher.update((iteration as u64).to_le_bytes());
+    hasher.update(state_hash);
+    if let Some(digest) = memory_digest {
+        hasher.update(digest);
+    }
+    hasher.finalize().into()
 }
 
 impl TemporalVDF {
     /// Check if the VDF has been initialized with input data
     pub fn is_initialized(&self) -> bool {
-        !self.state.is_empty() && self.initial_hash.is_some() && self.start_time.is_some()
+        !self.state.is_empty() && self.initial_hash.is_some() && self.progress.is_some()
+    }
+
+    /// Number of iterations completed so far
+    pub fn current_iteration(&self) -> usize {
+        self.progress.as_ref().map_or(0, VDFState::current_iteration)
+    }
+
+    /// State tracker driving iteration count and timing
+    pub fn progress(&self) -> Option<&VDFState> {
+        self.progress.as_ref()
+    }
+
+    /// Registers an observer notified of iterations, checkpoints and completion
+    pub fn add_observer(&mut self, observer: impl ProgressObserver + 'static) {
+        self.observers.push(Arc::new(observer));
     }
 }
 
@@ -69,49 +474,72 @@ impl TemporalVDF {
         Self {
             config,
             state: Vec::new(),
-            current_iteration: 0,
-            start_time: None,
+            progress: None,
             initial_hash: None,
+            state_commitment: None,
+            context: ProofContext::default(),
+            state_hashes: Vec::new(),
+            transcript: Vec::new(),
+            memory_digests: Vec::new(),
+            observers: Observers::default(),
+            audit: None,
         }
     }
 
+    /// Records every generated proof in `log`
+    pub fn with_audit_log(mut self, log: Arc<AuditLog>) -> Self {
+        self.audit = Some(log);
+        self
+    }
+
+    /// Initializes the VDF with input data under the default context
     pub fn initialize(&mut self, input: &[u8]) -> CryptoResult<()> {
+        self.initialize_with_context(input, ProofContext::default())
+    }
+
+    /// Initializes the VDF with input data bound to a domain and challenge
+    ///
+    /// The context is hashed into the initial state commitment and recorded
+    /// in the proof, so verifiers can insist on their own challenge. In
+    /// memory-hard mode the memory work is seeded from the bound commitment
+    /// and cannot be done before the challenge is known.
+    pub fn initialize_with_context(&mut self, input: &[u8], context: ProofContext) -> CryptoResult<()> {
         if input.is_empty() {
             return Err(CryptoError::InvalidInput("Input cannot be empty".into()));
         }
+        self.config.validate()?;
 
         debug!("Initializing VDF with input length {}", input.len());
 
         // Create three shares from input
         let mut rng = rand::thread_rng();
-        let padded_len = ((input.len() + 15) / 16) * 16; // Align to 16 bytes
+        let padded_len = input.len().div_ceil(16) * 16; // Align to 16 bytes
 
         // Generate first two shares randomly
         let share_a: Vec<u8> = (0..padded_len).map(|_| rng.gen()).collect();
         let share_b: Vec<u8> = (0..padded_len).map(|_| rng.gen()).collect();
 
         // Calculate third share to make XOR equal input
-        let mut share_c = vec![0u8; padded_len];
-        for i in 0..input.len() {
-            share_c[i] = input[i] ^ share_a[i] ^ share_b[i];
-        }
+        let mut share_c: Vec<u8> = input.iter()
+            .zip(share_a.iter().zip(&share_b))
+            .map(|(d, (a, b))| d ^ a ^ b)
+            .collect();
 
         // Fill remaining padding
-        for i in input.len()..padded_len {
-            share_c[i] = rng.gen();
-        }
+        share_c.extend((input.len()..padded_len).map(|_| rng.gen::<u8>()));
 
         self.state = vec![share_a, share_b, share_c];
 
-        // Calculate initial state hash
-        let mut hasher = Sha256::new();
-        for share in &self.state {
-            hasher.update(share);
-        }
-        self.initial_hash = Some(hasher.finalize().into());
+        // Calculate initial state hash and bind it to the context
+        let state_commitment = hash_state(&self.state);
+        self.initial_hash = Some(context.bind(&state_commitment));
+        self.state_commitment = Some(state_commitment);
+        self.context = context;
 
-        self.current_iteration = 0;
-        self.start_time = Some(Instant::now());
+        self.state_hashes.clear();
+        self.transcript.clear();
+        self.memory_digests.clear();
+        self.progress = Some(self.config.new_state());
 
         debug!("VDF initialized with {} shares of {} bytes each", 
                self.state.len(), padded_len);
@@ -120,48 +548,167 @@ impl TemporalVDF {
     }
 
     /// Perform one iteration of the VDF
+    ///
+    /// With enforced timing, an iteration requested less than
+    /// `min_iteration_time` after the previous one either sleeps until it may
+    /// complete or fails with `TimingViolation`, depending on `timing_policy`.
     pub fn iterate(&mut self) -> CryptoResult<()> {
-        if self.state.is_empty() {
-            return Err(CryptoError::InvalidState("VDF not initialized".into()));
+        self.iterate_until(None)
+    }
+
+    /// Perform one iteration unless the token is cancelled or its deadline passes
+    ///
+    /// A stopped iteration is discarded without touching the state, so the
+    /// VDF can be iterated, checkpointed or resumed afterwards as usual.
+    pub fn iterate_with(&mut self, token: &CancellationToken) -> CryptoResult<()> {
+        self.iterate_until(Some(token))
+    }
+
+    /// Iterate until the configured number of iterations is reached
+    pub fn run_to_completion(&mut self) -> CryptoResult<()> {
+        while !self.is_complete() {
+            self.iterate()?;
         }
+        Ok(())
+    }
 
-        if self.current_iteration >= CYCLE_LENGTH {
+    /// Iterate until complete, stopping with `Cancelled` or `Timeout` when the token says so
+    pub fn run_to_completion_with(&mut self, token: &CancellationToken) -> CryptoResult<()> {
+        while !self.is_complete() {
+            if let Err(err) = self.iterate_with(token) {
+                warn!(iteration = self.current_iteration(), error = %err, "VDF computation stopped");
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    fn iterate_until(&mut self, token: Option<&CancellationToken>) -> CryptoResult<()> {
+        if let Some(token) = token {
+            token.check()?;
+        }
+
+        let round = self.round_input()?.compute(&self.state);
+        if self.config.timing_policy == TimingPolicy::Sleep {
+            self.wait_until_ready(token)?;
+        }
+
+        self.commit_round(round)
+    }
+
+    /// Sleeps until the next iteration may complete, polling the token if given
+    pub(super) fn wait_until_ready(&self, token: Option<&CancellationToken>) -> CryptoResult<()> {
+        loop {
+            let wait = self.progress().map_or(Duration::ZERO, VDFState::time_until_ready);
+            if wait.is_zero() {
+                return Ok(());
+            }
+
+            let slice = match token {
+                None => wait,
+                Some(token) => {
+                    token.check()?;
+                    let slice = wait.min(cancel::POLL_INTERVAL);
+                    token.remaining().map_or(slice, |left| slice.min(left))
+                }
+            };
+            self.config.clock.sleep(slice);
+        }
+    }
+
+    /// Checks that an iteration may start and collects what its work needs
+    fn round_input(&self) -> CryptoResult<RoundInput> {
+        let progress = match &self.progress {
+            Some(progress) if !self.state.is_empty() => progress,
+            _ => return Err(CryptoError::InvalidState("VDF not initialized".into())),
+        };
+
+        if progress.is_complete() {
             return Err(CryptoError::InvalidState(
-                format!("Maximum iterations ({}) already reached", CYCLE_LENGTH)
+                format!("Maximum iterations ({}) already reached", self.config.iterations)
             ));
         }
 
-        let iteration_start = Instant::now();
-        debug!("Starting iteration {}/{}", self.current_iteration + 1, CYCLE_LENGTH);
+        if self.config.timing_policy == TimingPolicy::Reject {
+            progress.check_timing()?;
+        }
 
-        // Perform XOR transformation
-        let mut new_state = Vec::with_capacity(3);
-        for i in 0..3 {
-            let mut result = self.state[i].clone();
-            for j in 0..3 {
-                if i != j {
-                    for (r, v) in result.iter_mut().zip(&self.state[j]) {
-                        *r ^= v;
-                    }
-                }
-            }
-            new_state.push(result);
+        debug!("Starting iteration {}/{}", progress.current_iteration() + 1, self.config.iterations);
+
+        let initial_hash = self.initial_hash
+            .ok_or_else(|| CryptoError::InvalidState("VDF not initialized".into()))?;
+        Ok(RoundInput {
+            initial_hash,
+            previous: self.transcript.last().copied().unwrap_or(initial_hash),
+            iteration: progress.current_iteration(),
+            mode: self.config.mode,
+            memory_size: self.config.memory_size,
+        })
+    }
+
+    /// Records the iteration with the state tracker and installs the new state
+    fn commit_round(&mut self, round: Round) -> CryptoResult<()> {
+        let progress = self.progress
+            .as_mut()
+            .ok_or_else(|| CryptoError::InvalidState("VDF not initialized".into()))?;
+        progress.advance()?;
+
+        self.state = round.state;
+        self.state_hashes.push(round.state_hash);
+        self.transcript.push(round.link);
+        self.memory_digests.extend(round.memory_digest);
+
+        self.observers.notify(ProgressEvent::Iteration {
+            iteration: progress.current_iteration(),
+            total: progress.max_iterations(),
+            elapsed: progress.elapsed(),
+            estimated_remaining: progress.estimated_remaining(),
+        });
+        if progress.is_complete() {
+            self.observers.notify(ProgressEvent::Complete {
+                total: progress.max_iterations(),
+                elapsed: progress.elapsed(),
+            });
         }
+        Ok(())
+    }
 
-        self.state = new_state;
-        self.current_iteration += 1;
+    /// Perform one iteration without blocking the async runtime
+    ///
+    /// The work and any wait on the configured clock run on tokio's blocking
+    /// pool, so a `ManualClock` applies here just as it does to `iterate`.
+    #[cfg(feature = "async")]
+    pub async fn iterate_async(&mut self) -> CryptoResult<()> {
+        let input = self.round_input()?;
+        let state = self.state.clone();
+        let clock = Arc::clone(&self.config.clock);
+        let ready_at = match self.config.timing_policy {
+            TimingPolicy::Sleep => self.progress().map(|progress| clock.now() + progress.time_until_ready()),
+            TimingPolicy::Reject => None,
+        };
 
-        // Enforce minimum time if required
-        if self.config.enforce_timing {
-            let elapsed = iteration_start.elapsed();
-            if elapsed < self.config.min_iteration_time {
-                std::thread::sleep(self.config.min_iteration_time - elapsed);
+        let round = tokio::task::spawn_blocking(move || {
+            let round = input.compute(&state);
+            if let Some(ready_at) = ready_at {
+                let wait = ready_at.saturating_duration_since(clock.now());
+                if !wait.is_zero() {
+                    clock.sleep(wait);
+                }
             }
-        }
+            round
+        })
+        .await
+        .map_err(|err| CryptoError::InvalidState(format!("Iteration task failed: {}", err)))?;
 
-        info!("Completed iteration {}/{} in {:?}", 
-              self.current_iteration, CYCLE_LENGTH, iteration_start.elapsed());
+        self.commit_round(round)
+    }
 
+    /// Iterate until complete without blocking the async runtime
+    #[cfg(feature = "async")]
+    pub async fn run_to_completion_async(&mut self) -> CryptoResult<()> {
+        while !self.is_complete() {
+            self.iterate_async().await?;
+        }
         Ok(())
     }
 
@@ -170,7 +717,7 @@ impl TemporalVDF {
         if !self.is_complete() {
             return Err(CryptoError::InvalidState(
                 format!("Cannot generate proof: {}/{} iterations complete",
-                        self.current_iteration, CYCLE_LENGTH)
+                        self.current_iteration(), self.config.iterations)
             ));
         }
 
@@ -178,30 +725,44 @@ impl TemporalVDF {
             CryptoError::InvalidState("VDF not properly initialized".into())
         )?;
 
-        // Calculate final state hash
-        let mut hasher = Sha256::new();
-        for share in &self.state {
-            hasher.update(share);
-        }
-        let final_state_hash = hasher.finalize().into();
+        // The last transcript entry commits to the final state and the whole chain
+        let final_state_hash = self.transcript.last().copied().unwrap_or(initial_state_hash);
 
-        let computation_time = self.start_time
-            .expect("start_time should be set during initialization")
-            .elapsed();
+        let timing = self.progress
+            .as_ref()
+            .expect("progress should be set during initialization")
+            .timing_record();
+        let computation_time = timing.total_elapsed;
 
         debug!(
             ?initial_state_hash,
             ?final_state_hash,
             ?computation_time,
-            current_iteration = self.current_iteration,
+            current_iteration = self.current_iteration(),
             "Generating VDF proof"
         );
 
+        if let Some(log) = &self.audit {
+            log.record(AuditEvent::VdfProofGenerated {
+                initial_state_hash,
+                final_state_hash,
+                iterations: self.current_iteration() as u64,
+            })?;
+        }
+
         Ok(TemporalProof {
             initial_state_hash,
+            state_commitment: self.state_commitment.unwrap_or_default(),
+            context: self.context.clone(),
             final_state_hash,
             computation_time,
-            iteration_count: self.current_iteration,
+            iteration_count: self.current_iteration(),
+            state_hashes: self.state_hashes.clone(),
+            transcript: self.transcript.clone(),
+            timing,
+            mode: self.config.mode,
+            memory_size: self.config.proof_memory_size(),
+            memory_digests: self.memory_digests.clone(),
         })
     }
     
@@ -228,24 +789,44 @@ impl TemporalVDF {
             return Ok(false);
         }
 
+        if proof.context != self.context || self.context.bind(&proof.state_commitment) != initial_hash {
+            warn!(domain = ?proof.context.domain(), "Proof context mismatch");
+            return Ok(false);
+        }
+
         debug!(
-            expected = CYCLE_LENGTH,
+            expected = self.config.iterations,
             actual = proof.iteration_count,
             "Checking iteration count"
         );
 
         // Verify iteration count
-        if proof.iteration_count != CYCLE_LENGTH {
+        if proof.iteration_count != self.config.iterations {
             warn!("Iteration count mismatch");
             return Ok(false);
         }
 
-        // Get current final state hash
-        let mut hasher = Sha256::new();
-        for share in &self.state {
-            hasher.update(share);
+        if proof.mode != self.config.mode || proof.memory_size != self.config.proof_memory_size() {
+            warn!(
+                mode = ?proof.mode,
+                memory_size = proof.memory_size,
+                "Iteration mode or memory parameter mismatch"
+            );
+            return Ok(false);
+        }
+
+        if proof.memory_digests != self.memory_digests {
+            warn!("Memory-hard digest mismatch");
+            return Ok(false);
+        }
+
+        if proof.state_hashes != self.state_hashes || proof.transcript != self.transcript {
+            warn!("Transcript mismatch");
+            return Ok(false);
         }
-        let current_hash: [u8; 32] = hasher.finalize().into();
+
+        // Get current final state hash
+        let current_hash = self.transcript.last().copied().unwrap_or(initial_hash);
 
         debug!(
             current = ?current_hash,
@@ -266,9 +847,24 @@ impl TemporalVDF {
         Ok(true)
     }
 
+    /// Total computation time, including time spent before a resume
+    pub fn elapsed(&self) -> Duration {
+        self.progress.as_ref().map_or(Duration::ZERO, VDFState::elapsed)
+    }
+
+    /// Public commitment to the initial state, for handing to third-party verifiers
+    pub fn input_commitment(&self) -> Option<[u8; 32]> {
+        self.initial_hash
+    }
+
+    /// Domain and challenge the computation is bound to
+    pub fn context(&self) -> &ProofContext {
+        &self.context
+    }
+
     /// Check if computation is complete
     pub fn is_complete(&self) -> bool {
-        self.current_iteration == CYCLE_LENGTH
+        !self.state.is_empty() && self.progress.as_ref().is_some_and(VDFState::is_complete)
     }
 
     /// Get the current state if iteration is complete
@@ -276,7 +872,7 @@ impl TemporalVDF {
         if !self.is_complete() {
             return Err(CryptoError::InvalidState(
                 format!("Computation not complete: {}/{} iterations",
-                        self.current_iteration, CYCLE_LENGTH)
+                        self.current_iteration(), self.config.iterations)
             ));
         }
 
@@ -284,18 +880,192 @@ impl TemporalVDF {
             return Err(CryptoError::InvalidState("VDF not initialized".into()));
         }
 
-        let mut output = Vec::with_capacity(self.state[0].len());
-        for i in 0..self.state[0].len() {
-            output.push(self.state[0][i] ^ self.state[1][i] ^ self.state[2][i]);
-        }
+        let output = self.state[0].iter()
+            .zip(self.state[1].iter().zip(&self.state[2]))
+            .map(|(a, (b, c))| a ^ b ^ c)
+            .collect();
 
         Ok(output)
     }
 }
 
+impl TemporalVDF {
+    /// Writes a snapshot of the computation to disk
+    ///
+    /// The snapshot holds the state, iteration count, initial hash, state hashes, transcript,
+    /// memory-hard digests and elapsed time, followed by a SHA-256 digest of all of it. The
+    /// digest detects corruption, not tampering: anyone who can write the file can recompute
+    /// it. The state is the secret being delayed, in plain form, so the file is written
+    /// readable by the owner only, to a temporary file renamed into place.
+    pub fn checkpoint(&self, path: impl AsRef<Path>) -> CryptoResult<()> {
+        let (initial_hash, state_commitment, progress) = match (self.initial_hash, self.state_commitment, &self.progress) {
+            (Some(hash), Some(commitment), Some(progress)) => (hash, commitment, progress),
+            _ => return Err(CryptoError::InvalidState("VDF not initialized".into())),
+        };
+
+        let mut encoder = Encoder::new();
+        encoder
+            .put_raw(SNAPSHOT_MAGIC)
+            .put_u32(SNAPSHOT_VERSION)
+            .put_u64(self.config.iterations as u64)
+            .put_u32(self.config.mode as u32)
+            .put_u64(self.config.proof_memory_size() as u64)
+            .put_u64(progress.current_iteration() as u64)
+            .put_raw(&initial_hash)
+            .put_raw(&state_commitment)
+            .put_bytes(self.context.domain())
+            .put_bytes(self.context.challenge())
+            .put_duration(progress.elapsed())
+            .put_u32(self.state.len() as u32);
+        for share in &self.state {
+            encoder.put_bytes(share);
+        }
+        encoder.put_u64(self.transcript.len() as u64);
+        for (state_hash, link) in self.state_hashes.iter().zip(&self.transcript) {
+            encoder.put_raw(state_hash).put_raw(link);
+        }
+        for duration in progress.iteration_durations() {
+            encoder.put_duration(*duration);
+        }
+        encoder.put_u64(self.memory_digests.len() as u64);
+        for digest in &self.memory_digests {
+            encoder.put_raw(digest);
+        }
+
+        let path = path.as_ref();
+        utils::write_atomic_private(path, &codec::seal(encoder.finish()))?;
+
+        info!(
+            path = %path.display(),
+            iteration = progress.current_iteration(),
+            elapsed = ?progress.elapsed(),
+            "Wrote VDF checkpoint"
+        );
+        self.observers.notify(ProgressEvent::Checkpoint {
+            iteration: progress.current_iteration(),
+            path: path.to_path_buf(),
+        });
+
+        Ok(())
+    }
+
+    /// Restores a computation from a snapshot written by `checkpoint`
+    ///
+    /// Elapsed time keeps counting from the value stored in the snapshot.
+    /// Corrupted snapshots fail with `VerificationFailed`, and snapshots taken
+    /// with a different iteration count or memory setting fail with `InvalidState`.
+    /// Observers and the audit log are not part of the snapshot; attach them
+    /// again, e.g. `TemporalVDF::resume(path, config)?.with_audit_log(log)`,
+    /// or the resumed computation's proof goes unrecorded.
+    pub fn resume(path: impl AsRef<Path>, config: TemporalConfig) -> CryptoResult<Self> {
+        let path = path.as_ref();
+        let sealed = std::fs::read(path)?;
+        let payload = codec::unseal(&sealed)?;
+        let mut decoder = Decoder::new(payload);
+
+        if decoder.get_raw(SNAPSHOT_MAGIC.len())? != SNAPSHOT_MAGIC {
+            return Err(CryptoError::InvalidInput("Not a VDF snapshot".into()));
+        }
+        let version = decoder.get_u32()?;
+        if version != SNAPSHOT_VERSION {
+            return Err(CryptoError::InvalidInput(
+                format!("Unsupported snapshot version {}", version)
+            ));
+        }
+
+        let iterations = decoder.get_u64()? as usize;
+        if iterations != config.iterations {
+            return Err(CryptoError::InvalidState(format!(
+                "Snapshot was taken with {} iterations, config has {}",
+                iterations, config.iterations
+            )));
+        }
+
+        let mode = decoder.get_u32()?;
+        let memory_size = decoder.get_u64()? as usize;
+        if mode != config.mode as u32 || memory_size != config.proof_memory_size() {
+            return Err(CryptoError::InvalidState(format!(
+                "Snapshot was taken in mode {} with {} bytes of memory, config differs",
+                mode, memory_size
+            )));
+        }
+
+        let current_iteration = decoder.get_u64()? as usize;
+        let initial_hash = decoder.get_array32()?;
+        let state_commitment = decoder.get_array32()?;
+        let context = ProofContext::new(decoder.get_bytes()?, decoder.get_bytes()?);
+        if context.bind(&state_commitment) != initial_hash {
+            return Err(CryptoError::InvalidInput("Snapshot context does not match its commitment".into()));
+        }
+        let elapsed = decoder.get_duration()?;
+
+        let share_count = decoder.get_u32()?;
+        if share_count != 3 {
+            return Err(CryptoError::InvalidInput(
+                format!("Snapshot holds {} shares, expected 3", share_count)
+            ));
+        }
+        let state = (0..share_count)
+            .map(|_| decoder.get_bytes().map(<[u8]>::to_vec))
+            .collect::<CryptoResult<Vec<_>>>()?;
+        if state[0].is_empty() || state.iter().any(|share| share.len() != state[0].len()) {
+            return Err(CryptoError::InvalidInput("Snapshot shares have unequal or zero length".into()));
+        }
+
+        let transcript_len = decoder.get_u64()? as usize;
+        if transcript_len != current_iteration || current_iteration > iterations {
+            return Err(CryptoError::InvalidInput("Snapshot iteration data is inconsistent".into()));
+        }
+        let (state_hashes, transcript): (Vec<_>, Vec<_>) = (0..transcript_len)
+            .map(|_| Ok((decoder.get_array32()?, decoder.get_array32()?)))
+            .collect::<CryptoResult<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+        let iteration_durations = (0..transcript_len)
+            .map(|_| decoder.get_duration())
+            .collect::<CryptoResult<Vec<_>>>()?;
+        let digest_count = decoder.get_u64()? as usize;
+        let expected_digests = match config.mode {
+            IterationMode::Xor => 0,
+            IterationMode::MemoryHard => current_iteration,
+        };
+        if digest_count != expected_digests {
+            return Err(CryptoError::InvalidInput("Snapshot memory digests are inconsistent".into()));
+        }
+        let memory_digests = (0..digest_count)
+            .map(|_| decoder.get_array32())
+            .collect::<CryptoResult<Vec<_>>>()?;
+        decoder.finish()?;
+
+        let progress = config.new_state().restore(current_iteration, elapsed, iteration_durations)?;
+
+        info!(
+            path = %path.display(),
+            iteration = current_iteration,
+            ?elapsed,
+            "Resumed VDF from checkpoint"
+        );
+
+        Ok(Self {
+            config,
+            state,
+            progress: Some(progress),
+            initial_hash: Some(initial_hash),
+            state_commitment: Some(state_commitment),
+            context,
+            state_hashes,
+            transcript,
+            memory_digests,
+            observers: Observers::default(),
+            audit: None,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::crypto::utils::temp::TempDir;
 
     #[test]
     fn test_vdf_cycle() -> CryptoResult<()> {
@@ -375,35 +1145,43 @@ mod tests {
     fn test_state_consistency() -> CryptoResult<()> {
         let _ = tracing_subscriber::fmt::try_init();
 
-        let mut vdf = TemporalVDF::new(Default::default());
+        // Memory-hard rounds mask the state, so no two rounds hash alike
+        let config = TemporalConfig {
+            min_iteration_time: Duration::from_millis(1),
+            mode: IterationMode::MemoryHard,
+            memory_size: 4096,
+            ..Default::default()
+        };
+        let mut vdf = TemporalVDF::new(config);
         let input = b"State consistency test";
 
         vdf.initialize(input)?;
         let initial_hash = vdf.initial_hash.expect("Initial hash not set");
 
-        // Store state hashes at each iteration - now with explicit type
+        // Recompute each round's state hash from the state it leaves behind
         let mut state_hashes: Vec<[u8; 32]> = Vec::new();
         for i in 0..CYCLE_LENGTH {
-            let mut hasher = Sha256::new();
-            for share in &vdf.state {
-                hasher.update(share);
-            }
-            state_hashes.push(hasher.finalize().into());
+            vdf.iterate()?;
+            state_hashes.push(hash_bound_state(&initial_hash, &vdf.state));
 
             debug!(
                 iteration = i,
                 hash = ?state_hashes.last(),
-                "State hash before iteration"
+                "State hash after iteration"
             );
-
-            vdf.iterate()?;
         }
+        assert!(state_hashes.windows(2).all(|pair| pair[0] != pair[1]), "State hashes repeat");
 
-        // Generate and immediately verify proof
         let proof = vdf.generate_proof()?;
         assert_eq!(proof.initial_state_hash, initial_hash, "Initial hash mismatch");
-        assert_eq!(proof.final_state_hash, state_hashes[CYCLE_LENGTH - 1],
-                   "Final hash mismatch");
+        assert_eq!(proof.state_hashes, state_hashes, "State hash mismatch");
+
+        let mut previous = initial_hash;
+        for (i, (state_hash, digest)) in state_hashes.iter().zip(&proof.memory_digests).enumerate() {
+            previous = chain_link(&previous, i, state_hash, Some(digest));
+            assert_eq!(proof.transcript[i], previous, "Transcript entry {} mismatch", i);
+        }
+        assert_eq!(proof.final_state_hash, previous, "Final hash mismatch");
 
         Ok(())
     }
@@ -434,6 +1212,537 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_configurable_iterations() -> CryptoResult<()> {
+        let input = b"Configurable delay";
+
+        for iterations in [1, 3, 7, 16] {
+            let config = TemporalConfig {
+                enforce_timing: false,
+                iterations,
+                ..Default::default()
+            };
+
+            let mut vdf = TemporalVDF::new(config);
+            vdf.initialize(input)?;
+            for _ in 0..iterations {
+                assert!(!vdf.is_complete());
+                vdf.iterate()?;
+            }
+
+            assert!(vdf.is_complete());
+            assert!(vdf.iterate().is_err(), "T={} allowed an extra iteration", iterations);
+            assert_eq!(&vdf.get_output()?[..input.len()], input);
+
+            let proof = vdf.generate_proof()?;
+            assert_eq!(proof.iteration_count, iterations);
+            assert!(vdf.verify_proof(&proof)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_proof_for_other_iteration_count_rejected() -> CryptoResult<()> {
+        let config = TemporalConfig {
+            enforce_timing: false,
+            iterations: 2,
+            ..Default::default()
+        };
+
+        let mut vdf = TemporalVDF::new(config);
+        vdf.initialize(b"Test input")?;
+        vdf.iterate()?;
+        vdf.iterate()?;
+
+        let mut proof = vdf.generate_proof()?;
+        proof.iteration_count = 5;
+        assert!(!vdf.verify_proof(&proof)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_zero_iterations_rejected() {
+        let config = TemporalConfig {
+            iterations: 0,
+            ..Default::default()
+        };
+
+        let mut vdf = TemporalVDF::new(config);
+        assert!(matches!(
+            vdf.initialize(b"Test input"),
+            Err(CryptoError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_timing_enforced_on_manual_clock() -> CryptoResult<()> {
+        let clock = utils::ManualClock::new();
+        let config = TemporalConfig {
+            min_iteration_time: Duration::from_secs(60),
+            enforce_timing: true,
+            clock: Arc::new(clock.clone()),
+            ..Default::default()
+        };
+
+        let mut vdf = TemporalVDF::new(config);
+        vdf.initialize(b"Slow release")?;
+        for _ in 0..CYCLE_LENGTH {
+            vdf.iterate()?;
+        }
+
+        // Every iteration waited out its spacing on the manual clock
+        let proof = vdf.generate_proof()?;
+        assert_eq!(proof.computation_time(), Duration::from_secs(60) * CYCLE_LENGTH as u32);
+        assert_eq!(clock.offset(), proof.computation_time());
+
+        let timing = proof.timing();
+        assert!(timing.enforced);
+        assert_eq!(timing.min_iteration_time, Duration::from_secs(60));
+        assert_eq!(timing.iteration_durations.len(), CYCLE_LENGTH);
+        assert!(timing.iteration_durations.iter().all(|d| *d == Duration::from_secs(60)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reject_policy_returns_timing_violation() -> CryptoResult<()> {
+        let clock = utils::ManualClock::new();
+        let config = TemporalConfig {
+            min_iteration_time: Duration::from_secs(30),
+            enforce_timing: true,
+            timing_policy: TimingPolicy::Reject,
+            clock: Arc::new(clock.clone()),
+            ..Default::default()
+        };
+
+        let mut vdf = TemporalVDF::new(config);
+        vdf.initialize(b"Strict release")?;
+        assert!(matches!(vdf.iterate(), Err(CryptoError::TimingViolation { .. })));
+        clock.advance(Duration::from_secs(30));
+        vdf.iterate()?;
+
+        clock.advance(Duration::from_secs(10));
+        match vdf.iterate() {
+            Err(CryptoError::TimingViolation { expected, actual }) => {
+                assert_eq!(expected, Duration::from_secs(30));
+                assert_eq!(actual, Duration::from_secs(10));
+            }
+            other => panic!("expected timing violation, got {:?}", other),
+        }
+
+        // A rejected call leaves the computation untouched
+        assert_eq!(vdf.current_iteration(), 1);
+        assert_eq!(vdf.transcript.len(), 1);
+
+        clock.advance(Duration::from_secs(20));
+        vdf.iterate()?;
+        assert_eq!(vdf.current_iteration(), 2);
+        assert_eq!(clock.offset(), Duration::from_secs(60));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_to_completion() -> CryptoResult<()> {
+        let mut vdf = TemporalVDF::new(TemporalConfig {
+            enforce_timing: false,
+            iterations: 6,
+            ..Default::default()
+        });
+        assert!(vdf.run_to_completion().is_err());
+
+        vdf.initialize(b"Run it all")?;
+        vdf.run_to_completion()?;
+        assert!(vdf.is_complete());
+        assert_eq!(vdf.generate_proof()?.iteration_count(), 6);
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_matches_sync() -> CryptoResult<()> {
+        let config = TemporalConfig {
+            min_iteration_time: Duration::from_millis(5),
+            enforce_timing: true,
+            ..Default::default()
+        };
+        let input = b"Async delay";
+
+        let mut vdf = TemporalVDF::new(config);
+        assert!(matches!(vdf.iterate_async().await, Err(CryptoError::InvalidState(_))));
+
+        vdf.initialize(input)?;
+        vdf.run_to_completion_async().await?;
+        assert_eq!(&vdf.get_output()?[..input.len()], input);
+
+        let proof = vdf.generate_proof()?;
+        assert!(proof.computation_time() >= Duration::from_millis(5) * CYCLE_LENGTH as u32);
+        assert!(vdf.verify_proof(&proof)?);
+        assert!(matches!(vdf.iterate_async().await, Err(CryptoError::InvalidState(_))));
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_uses_configured_clock() -> CryptoResult<()> {
+        let clock = utils::ManualClock::new();
+        let config = TemporalConfig {
+            min_iteration_time: Duration::from_secs(60),
+            clock: Arc::new(clock.clone()),
+            ..Default::default()
+        };
+
+        let mut vdf = TemporalVDF::new(config);
+        vdf.initialize(b"Async manual clock")?;
+        vdf.run_to_completion_async().await?;
+        assert_eq!(clock.offset(), Duration::from_secs(60) * CYCLE_LENGTH as u32);
+        assert!(vdf.verify_proof(&vdf.generate_proof()?)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_checkpoint_and_resume() -> CryptoResult<()> {
+        let config = TemporalConfig {
+            min_iteration_time: Duration::from_millis(20),
+            enforce_timing: true,
+            ..Default::default()
+        };
+        let input = b"Long running delay";
+        let dir = TempDir::new("resume")?;
+        let path = dir.join("vdf.snap");
+        let context = ProofContext::new("resume-test", "nonce");
+
+        let mut vdf = TemporalVDF::new(config.clone());
+        vdf.initialize_with_context(input, context.clone())?;
+        vdf.iterate()?;
+        vdf.iterate()?;
+        let elapsed_before_checkpoint = vdf.elapsed();
+        vdf.checkpoint(&path)?;
+        drop(vdf);
+
+        let log = Arc::new(AuditLog::in_memory("resumer"));
+        let mut resumed = TemporalVDF::resume(&path, config)?.with_audit_log(Arc::clone(&log));
+        assert!(resumed.is_initialized());
+        assert!(resumed.elapsed() >= elapsed_before_checkpoint);
+        assert_eq!(resumed.context(), &context);
+
+        while !resumed.is_complete() {
+            resumed.iterate()?;
+        }
+        assert_eq!(&resumed.get_output()?[..input.len()], input);
+
+        let proof = resumed.generate_proof()?;
+        assert!(proof.computation_time >= Duration::from_millis(20) * CYCLE_LENGTH as u32);
+        assert_eq!(proof.timing().iteration_durations.len(), CYCLE_LENGTH);
+        assert!(resumed.verify_proof(&proof)?);
+        assert_eq!(log.records()?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_hard_mode() -> CryptoResult<()> {
+        let config = TemporalConfig {
+            enforce_timing: false,
+            mode: IterationMode::MemoryHard,
+            memory_size: 16 * 1024,
+            ..Default::default()
+        };
+        let input = b"Memory-hard input";
+
+        let mut vdf = TemporalVDF::new(config.clone());
+        vdf.initialize(input)?;
+        vdf.run_to_completion()?;
+
+        // The output carries every iteration's digest
+        let proof = vdf.generate_proof()?;
+        let mut output = vdf.get_output()?;
+        assert_ne!(&output[..input.len()], input);
+        for digest in proof.memory_digests() {
+            memory::apply_mask(&mut output, digest);
+        }
+        assert_eq!(&output[..input.len()], input);
+
+        assert_eq!(proof.mode(), IterationMode::MemoryHard);
+        assert_eq!(proof.memory_size(), 16 * 1024);
+        assert_eq!(proof.memory_digests().len(), CYCLE_LENGTH);
+        assert!(vdf.verify_proof(&proof)?);
+
+        let mut understated = proof.clone();
+        understated.memory_size = 1024;
+        assert!(!vdf.verify_proof(&understated)?);
+
+        // XOR mode records no memory work
+        let mut plain = TemporalVDF::new(TemporalConfig { mode: IterationMode::Xor, ..config });
+        plain.initialize(input)?;
+        plain.run_to_completion()?;
+        let proof = plain.generate_proof()?;
+        assert_eq!(proof.memory_size(), 0);
+        assert!(proof.memory_digests().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_hard_needs_memory() {
+        let config = TemporalConfig {
+            mode: IterationMode::MemoryHard,
+            memory_size: memory::MIN_MEMORY_SIZE - 1,
+            ..Default::default()
+        };
+
+        let mut vdf = TemporalVDF::new(config);
+        assert!(matches!(
+            vdf.initialize(b"Test input"),
+            Err(CryptoError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_memory_hard_checkpoint_and_resume() -> CryptoResult<()> {
+        let config = TemporalConfig {
+            enforce_timing: false,
+            mode: IterationMode::MemoryHard,
+            memory_size: 4 * 1024,
+            ..Default::default()
+        };
+        let dir = TempDir::new("memory")?;
+        let path = dir.join("vdf.snap");
+
+        let mut vdf = TemporalVDF::new(config.clone());
+        vdf.initialize(b"Resumable memory work")?;
+        vdf.iterate()?;
+        vdf.checkpoint(&path)?;
+
+        let other = TemporalConfig { memory_size: 8 * 1024, ..config.clone() };
+        assert!(matches!(TemporalVDF::resume(&path, other), Err(CryptoError::InvalidState(_))));
+
+        let mut resumed = TemporalVDF::resume(&path, config)?;
+        resumed.run_to_completion()?;
+        vdf.run_to_completion()?;
+
+        let proof = resumed.generate_proof()?;
+        assert_eq!(proof.memory_digests(), vdf.generate_proof()?.memory_digests());
+        assert_eq!(resumed.get_output()?, vdf.get_output()?);
+        assert!(resumed.verify_proof(&proof)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_observers_report_progress() -> CryptoResult<()> {
+        let clock = utils::ManualClock::new();
+        let config = TemporalConfig {
+            min_iteration_time: Duration::from_secs(60),
+            clock: Arc::new(clock.clone()),
+            ..Default::default()
+        };
+        let dir = TempDir::new("observed")?;
+        let path = dir.join("vdf.snap");
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+
+        let mut vdf = TemporalVDF::new(config);
+        vdf.add_observer(sender);
+        vdf.add_observer(move |_: &ProgressEvent| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        });
+        vdf.initialize(b"Observed delay")?;
+        vdf.iterate()?;
+        vdf.checkpoint(&path)?;
+        vdf.run_to_completion()?;
+
+        let events: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(events.len(), CYCLE_LENGTH + 2);
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), events.len());
+
+        assert_eq!(events[0], ProgressEvent::Iteration {
+            iteration: 1,
+            total: CYCLE_LENGTH,
+            elapsed: Duration::from_secs(60),
+            estimated_remaining: Duration::from_secs(60) * (CYCLE_LENGTH as u32 - 1),
+        });
+        assert_eq!(events[1], ProgressEvent::Checkpoint { iteration: 1, path });
+        assert_eq!(events[CYCLE_LENGTH].estimated_remaining(), Some(Duration::ZERO));
+        assert_eq!(events[CYCLE_LENGTH + 1], ProgressEvent::Complete {
+            total: CYCLE_LENGTH,
+            elapsed: Duration::from_secs(60) * CYCLE_LENGTH as u32,
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deadline_stops_and_leaves_vdf_resumable() -> CryptoResult<()> {
+        let clock = utils::ManualClock::new();
+        let config = TemporalConfig {
+            min_iteration_time: Duration::from_secs(60),
+            clock: Arc::new(clock.clone()),
+            ..Default::default()
+        };
+        let input = b"Runaway computation";
+
+        let mut vdf = TemporalVDF::new(config);
+        vdf.initialize(input)?;
+
+        // Two iterations fit in 150 seconds, the third would end at 180
+        let token = CancellationToken::with_clock(Arc::new(clock.clone()))
+            .with_timeout(Duration::from_secs(150));
+        match vdf.run_to_completion_with(&token) {
+            Err(CryptoError::Timeout(elapsed)) => assert_eq!(elapsed, Duration::from_secs(150)),
+            other => panic!("expected timeout, got {:?}", other),
+        }
+        assert_eq!(vdf.current_iteration(), 2);
+        assert!(!vdf.is_complete());
+
+        // A fresh budget finishes the job with the correct output
+        vdf.run_to_completion_with(&CancellationToken::with_clock(Arc::new(clock)))?;
+        assert_eq!(&vdf.get_output()?[..input.len()], input);
+        assert!(vdf.verify_proof(&vdf.generate_proof()?)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cancelled_iteration_leaves_state_untouched() -> CryptoResult<()> {
+        let config = TemporalConfig {
+            enforce_timing: false,
+            ..Default::default()
+        };
+        let mut vdf = TemporalVDF::new(config);
+        vdf.initialize(b"Cancelled")?;
+        vdf.iterate()?;
+        let transcript = vdf.transcript.clone();
+
+        let token = CancellationToken::new();
+        token.cancel();
+        assert!(matches!(vdf.iterate_with(&token), Err(CryptoError::Cancelled)));
+        assert!(matches!(vdf.run_to_completion_with(&token), Err(CryptoError::Cancelled)));
+        assert_eq!(vdf.current_iteration(), 1);
+        assert_eq!(vdf.transcript, transcript);
+
+        vdf.run_to_completion()?;
+        assert!(vdf.is_complete());
+        Ok(())
+    }
+
+    #[test]
+    fn test_proof_bound_to_context() -> CryptoResult<()> {
+        let config = TemporalConfig {
+            enforce_timing: false,
+            ..Default::default()
+        };
+        let context = ProofContext::new("study-release", "challenge-1");
+
+        let mut vdf = TemporalVDF::new(config.clone());
+        vdf.initialize_with_context(b"Bound input", context.clone())?;
+        vdf.run_to_completion()?;
+        let proof = vdf.generate_proof()?;
+        assert_eq!(proof.context(), &context);
+        assert_eq!(Some(context.bind(&proof.state_commitment())), vdf.input_commitment());
+        assert!(vdf.verify_proof(&proof)?);
+
+        // Relabelling the proof with another challenge breaks the binding
+        let mut replayed = proof.clone();
+        replayed.context = ProofContext::new("study-release", "challenge-2");
+        assert!(!vdf.verify_proof(&replayed)?);
+
+        // The same input under another context commits differently
+        let mut other = TemporalVDF::new(config);
+        other.initialize_with_context(b"Bound input", ProofContext::new("study-release", "challenge-2"))?;
+        other.run_to_completion()?;
+        assert!(!other.verify_proof(&proof)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupted_snapshot_detected() -> CryptoResult<()> {
+        let config = TemporalConfig {
+            enforce_timing: false,
+            ..Default::default()
+        };
+        let dir = TempDir::new("corrupt")?;
+        let path = dir.join("vdf.snap");
+
+        let mut vdf = TemporalVDF::new(config.clone());
+        vdf.initialize(b"Test input")?;
+        vdf.iterate()?;
+        vdf.checkpoint(&path)?;
+
+        let mut bytes = std::fs::read(&path)?;
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 1;
+        std::fs::write(&path, &bytes)?;
+        assert!(matches!(
+            TemporalVDF::resume(&path, config.clone()),
+            Err(CryptoError::VerificationFailed(_))
+        ));
+
+        std::fs::write(&path, &bytes[..middle])?;
+        assert!(TemporalVDF::resume(&path, config).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot_is_private_and_shares_checked() -> CryptoResult<()> {
+        let config = TemporalConfig {
+            enforce_timing: false,
+            ..Default::default()
+        };
+        let dir = TempDir::new("shares")?;
+        let path = dir.join("vdf.snap");
+
+        let mut vdf = TemporalVDF::new(config.clone());
+        vdf.initialize(b"Test input")?;
+        vdf.checkpoint(&path)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path)?.permissions().mode() & 0o777, 0o600);
+        }
+
+        vdf.state[1].pop();
+        vdf.checkpoint(&path)?;
+        assert!(matches!(
+            TemporalVDF::resume(&path, config),
+            Err(CryptoError::InvalidInput(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resume_with_other_iteration_count_rejected() -> CryptoResult<()> {
+        let dir = TempDir::new("mismatch")?;
+        let path = dir.join("vdf.snap");
+        let mut vdf = TemporalVDF::new(Default::default());
+        vdf.initialize(b"Test input")?;
+        vdf.checkpoint(&path)?;
+
+        let config = TemporalConfig {
+            iterations: 8,
+            ..Default::default()
+        };
+        assert!(matches!(
+            TemporalVDF::resume(&path, config),
+            Err(CryptoError::InvalidState(_))
+        ));
+
+        let missing = dir.join("missing.snap");
+        assert!(matches!(
+            TemporalVDF::resume(&missing, Default::default()),
+            Err(CryptoError::IoError(_))
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn test_incomplete_state() {
         let mut vdf = TemporalVDF::new(Default::default());
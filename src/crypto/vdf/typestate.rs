@@ -0,0 +1,244 @@
+//! Compile-time lifecycle wrapper for the temporal VDF
+//! Location: src/crypto/vdf/typestate.rs
+//!
+//! `Vdf<Uninitialized>` can only be initialized, `Vdf<Running>` can only be
+//! iterated, and only `Vdf<Complete>` exposes the output and proof. Calling an
+//! operation on the wrong stage is a type error instead of an `InvalidState`.
+
+use super::context::ProofContext;
+use super::progress::ProgressObserver;
+use super::temporal::{TemporalConfig, TemporalProof, TemporalVDF};
+use crate::error::{CryptoError, CryptoResult};
+use std::marker::PhantomData;
+
+/// Stage of a VDF that has not seen its input yet
+#[derive(Debug)]
+pub struct Uninitialized;
+
+/// Stage of a VDF with iterations left to perform
+#[derive(Debug)]
+pub struct Running;
+
+/// Stage of a VDF that has performed all its iterations
+#[derive(Debug)]
+pub struct Complete;
+
+/// Temporal VDF whose lifecycle stage is part of its type
+#[derive(Debug)]
+pub struct Vdf<Stage> {
+    inner: TemporalVDF,
+    stage: PhantomData<Stage>,
+}
+
+/// Result of iterating a running VDF
+#[derive(Debug)]
+pub enum Step {
+    /// More iterations are required
+    Running(Vdf<Running>),
+    /// The last iteration has been performed
+    Complete(Vdf<Complete>),
+}
+
+/// A failed iteration, handing the still-running VDF back
+///
+/// Failures such as `TimingViolation` leave the computation intact, so the
+/// caller can retry with the returned VDF.
+#[derive(Debug)]
+pub struct IterateError {
+    pub error: CryptoError,
+    pub vdf: Box<Vdf<Running>>,
+}
+
+impl From<IterateError> for CryptoError {
+    fn from(err: IterateError) -> Self {
+        err.error
+    }
+}
+
+/// A VDF of any stage, for converting from the dynamic API
+#[derive(Debug)]
+pub enum Lifecycle {
+    Uninitialized(Vdf<Uninitialized>),
+    Running(Vdf<Running>),
+    Complete(Vdf<Complete>),
+}
+
+impl From<TemporalVDF> for Lifecycle {
+    fn from(inner: TemporalVDF) -> Self {
+        if inner.is_complete() {
+            Lifecycle::Complete(Vdf::wrap(inner))
+        } else if inner.is_initialized() {
+            Lifecycle::Running(Vdf::wrap(inner))
+        } else {
+            Lifecycle::Uninitialized(Vdf::wrap(inner))
+        }
+    }
+}
+
+impl<Stage> Vdf<Stage> {
+    fn wrap(inner: TemporalVDF) -> Self {
+        Self { inner, stage: PhantomData }
+    }
+
+    /// Configuration of the underlying VDF
+    pub fn config(&self) -> &TemporalConfig {
+        &self.inner.config
+    }
+
+    /// Number of iterations completed so far
+    pub fn current_iteration(&self) -> usize {
+        self.inner.current_iteration()
+    }
+
+    /// Registers an observer notified of iterations, checkpoints and completion
+    pub fn add_observer(&mut self, observer: impl ProgressObserver + 'static) {
+        self.inner.add_observer(observer);
+    }
+
+    /// Borrows the underlying dynamic VDF
+    pub fn as_inner(&self) -> &TemporalVDF {
+        &self.inner
+    }
+
+    /// Returns the underlying dynamic VDF
+    pub fn into_inner(self) -> TemporalVDF {
+        self.inner
+    }
+}
+
+impl Vdf<Uninitialized> {
+    pub fn new(config: TemporalConfig) -> Self {
+        Self::wrap(TemporalVDF::new(config))
+    }
+
+    /// Initializes the VDF with its input
+    pub fn initialize(mut self, input: &[u8]) -> CryptoResult<Vdf<Running>> {
+        self.inner.initialize(input)?;
+        Ok(Vdf::wrap(self.inner))
+    }
+
+    /// Initializes the VDF with its input, bound to a domain and challenge
+    pub fn initialize_with_context(mut self, input: &[u8], context: ProofContext) -> CryptoResult<Vdf<Running>> {
+        self.inner.initialize_with_context(input, context)?;
+        Ok(Vdf::wrap(self.inner))
+    }
+}
+
+impl Vdf<Running> {
+    /// Performs one iteration
+    pub fn iterate(mut self) -> Result<Step, IterateError> {
+        match self.inner.iterate() {
+            Ok(()) if self.inner.is_complete() => Ok(Step::Complete(Vdf::wrap(self.inner))),
+            Ok(()) => Ok(Step::Running(self)),
+            Err(error) => Err(IterateError { error, vdf: Box::new(self) }),
+        }
+    }
+
+    /// Iterates until every iteration has been performed
+    pub fn run_to_completion(mut self) -> CryptoResult<Vdf<Complete>> {
+        loop {
+            match self.iterate()? {
+                Step::Running(vdf) => self = vdf,
+                Step::Complete(vdf) => return Ok(vdf),
+            }
+        }
+    }
+}
+
+impl Vdf<Complete> {
+    /// Gets the output of the computation
+    pub fn get_output(&self) -> CryptoResult<Vec<u8>> {
+        self.inner.get_output()
+    }
+
+    /// Generates proof of computation
+    pub fn generate_proof(&self) -> CryptoResult<TemporalProof> {
+        self.inner.generate_proof()
+    }
+
+    /// Verifies a proof against this computation
+    pub fn verify_proof(&self, proof: &TemporalProof) -> CryptoResult<bool> {
+        self.inner.verify_proof(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::utils::ManualClock;
+    use crate::crypto::vdf::temporal::{TimingPolicy, CYCLE_LENGTH};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn fast_config() -> TemporalConfig {
+        TemporalConfig {
+            enforce_timing: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_lifecycle() -> CryptoResult<()> {
+        let input = b"Typed lifecycle";
+        let mut vdf = Vdf::new(fast_config()).initialize(input)?;
+
+        let mut steps = 0;
+        let complete = loop {
+            steps += 1;
+            match vdf.iterate()? {
+                Step::Running(next) => vdf = next,
+                Step::Complete(done) => break done,
+            }
+        };
+
+        assert_eq!(steps, CYCLE_LENGTH);
+        assert_eq!(&complete.get_output()?[..input.len()], input);
+        let proof = complete.generate_proof()?;
+        assert!(complete.verify_proof(&proof)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_iteration_returns_vdf() -> CryptoResult<()> {
+        let clock = ManualClock::new();
+        let config = TemporalConfig {
+            min_iteration_time: Duration::from_secs(5),
+            timing_policy: TimingPolicy::Reject,
+            clock: Arc::new(clock.clone()),
+            ..Default::default()
+        };
+
+        let vdf = Vdf::new(config).initialize(b"Strict")?;
+        clock.advance(Duration::from_secs(5));
+        let vdf = match vdf.iterate()? {
+            Step::Running(vdf) => vdf,
+            Step::Complete(_) => panic!("one iteration cannot complete the default cycle"),
+        };
+
+        let err = vdf.iterate().expect_err("second iteration is too early");
+        assert!(matches!(err.error, CryptoError::TimingViolation { .. }));
+
+        clock.advance(Duration::from_secs(5));
+        let vdf = *err.vdf;
+        assert_eq!(vdf.current_iteration(), 1);
+        assert!(vdf.iterate().is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_dynamic_vdf() -> CryptoResult<()> {
+        let mut dynamic = TemporalVDF::new(fast_config());
+        assert!(matches!(Lifecycle::from(TemporalVDF::new(fast_config())), Lifecycle::Uninitialized(_)));
+
+        dynamic.initialize(b"Dynamic")?;
+        dynamic.iterate()?;
+        let running = match Lifecycle::from(dynamic) {
+            Lifecycle::Running(vdf) => vdf,
+            other => panic!("expected running VDF, got {:?}", other),
+        };
+
+        let complete = running.run_to_completion()?;
+        assert!(matches!(Lifecycle::from(complete.into_inner()), Lifecycle::Complete(_)));
+        Ok(())
+    }
+}
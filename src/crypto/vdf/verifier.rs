@@ -0,0 +1,565 @@
+//! Stateless third-party verification of temporal VDF proofs
+//! Location: src/crypto/vdf/verifier.rs
+
+use super::context::ProofContext;
+use super::memory;
+use super::temporal::{chain_link, hash_bound_state, IterationMode, TemporalConfig, TemporalProof, CYCLE_LENGTH};
+use tracing::{debug, warn};
+
+/// Outcome of checking a proof against public inputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// The proof is consistent with the public inputs
+    Accepted,
+    /// The proof was rejected for the given reason
+    Rejected(String),
+}
+
+impl Verdict {
+    /// Returns whether the proof was accepted
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, Verdict::Accepted)
+    }
+}
+
+/// Verifies temporal proofs using only public inputs
+///
+/// Unlike `TemporalVDF::verify_proof`, this needs neither the VDF instance
+/// nor its state: an auditor holds the input commitment published at
+/// initialization, the claimed output commitment and the proof transcript.
+#[derive(Debug, Clone)]
+pub struct TemporalVerifier {
+    /// Number of iterations a valid proof must cover
+    expected_iterations: usize,
+    /// Iteration mode a valid proof must have run in
+    mode: IterationMode,
+    /// Working memory a valid proof must have used, zero in XOR mode
+    memory_size: usize,
+    /// Domain and challenge a valid proof must be bound to
+    context: ProofContext,
+    /// Number of memory-hard iterations to recompute, all of them if unset
+    spot_checks: Option<usize>,
+}
+
+impl Default for TemporalVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TemporalVerifier {
+    /// Creates a verifier expecting a full XOR cycle
+    pub fn new() -> Self {
+        Self::with_iterations(CYCLE_LENGTH)
+    }
+
+    /// Creates a verifier expecting exactly `iterations` rounds
+    pub fn with_iterations(iterations: usize) -> Self {
+        Self {
+            expected_iterations: iterations,
+            mode: IterationMode::Xor,
+            memory_size: 0,
+            context: ProofContext::default(),
+            spot_checks: None,
+        }
+    }
+
+    /// Requires proofs to carry memory-hard work over `memory_size` bytes
+    pub fn memory_hard(mut self, memory_size: usize) -> Self {
+        self.mode = IterationMode::MemoryHard;
+        self.memory_size = memory_size;
+        self
+    }
+
+    /// Creates a verifier matching the iterations and mode of a VDF configuration
+    pub fn for_config(config: &TemporalConfig) -> Self {
+        Self {
+            expected_iterations: config.iterations,
+            mode: config.mode,
+            memory_size: config.proof_memory_size(),
+            context: ProofContext::default(),
+            spot_checks: None,
+        }
+    }
+
+    /// Requires proofs to be bound to the given domain and challenge
+    pub fn with_context(mut self, context: ProofContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Recomputes only `count` randomly chosen memory-hard iterations
+    ///
+    /// By default every iteration's memory work is redone, which costs the
+    /// verifier as much memory and time as the prover spent outside its
+    /// waits. Each digest is seeded from the previous transcript entry, so it
+    /// can be checked on its own; a prover that skipped a fraction `f` of the
+    /// work escapes `count` spot checks with probability about `(1 - f)^count`.
+    pub fn with_spot_checks(mut self, count: usize) -> Self {
+        self.spot_checks = Some(count);
+        self
+    }
+
+    /// Iterations whose memory work is recomputed
+    fn memory_checks(&self, iterations: usize) -> Vec<usize> {
+        match self.spot_checks {
+            Some(count) if count < iterations => {
+                rand::seq::index::sample(&mut rand::thread_rng(), iterations, count).into_vec()
+            }
+            _ => (0..iterations).collect(),
+        }
+    }
+
+    /// Computes the output commitment `proof` must end in for claimed output bytes
+    ///
+    /// Each XOR round replaces every share with the XOR of all three, so a
+    /// completed state is three copies of the output. It is hashed under the
+    /// proof's initial state hash and chained onto the second-to-last
+    /// transcript entry like the prover's was.
+    ///
+    /// This ties an XOR proof to its output bytes, not to any sequential work:
+    /// anyone holding the input can compute the same commitment directly.
+    pub fn commit_output(proof: &TemporalProof, output: &[u8]) -> [u8; 32] {
+        let share = output.to_vec();
+        let state_hash = hash_bound_state(&proof.initial_state_hash, &[share.clone(), share.clone(), share]);
+        let digest = |n: usize| proof.memory_digests.get(n - 1);
+        match proof.transcript.len() {
+            0 => proof.initial_state_hash,
+            1 => chain_link(&proof.initial_state_hash, 0, &state_hash, digest(1)),
+            n => chain_link(&proof.transcript[n - 2], n - 1, &state_hash, digest(n)),
+        }
+    }
+
+    /// Checks a proof against the input and output commitments
+    ///
+    /// An accepted XOR proof only shows its transcript is consistent with the
+    /// input: the XOR round reaches a fixed point after the first iteration,
+    /// so anyone can rebuild a valid transcript without waiting. It is not a
+    /// proof of elapsed time or work.
+    ///
+    /// Memory-hard proofs are checked by redoing the memory work of every
+    /// iteration, which costs as much as the prover's work without the waits,
+    /// unless `with_spot_checks` limits it to a sample.
+    pub fn verify(
+        &self,
+        input_commitment: &[u8; 32],
+        output_commitment: &[u8; 32],
+        proof: &TemporalProof,
+    ) -> Verdict {
+        debug!(
+            iterations = proof.iteration_count,
+            transcript_len = proof.transcript.len(),
+            "Verifying temporal proof from public inputs"
+        );
+
+        if &proof.initial_state_hash != input_commitment {
+            return reject("initial state hash does not match input commitment");
+        }
+
+        if proof.context != self.context {
+            return reject("proof is bound to a different domain or challenge");
+        }
+
+        if self.context.bind(&proof.state_commitment) != proof.initial_state_hash {
+            return reject("initial state hash is not bound to the proof context");
+        }
+
+        if &proof.final_state_hash != output_commitment {
+            return reject("final state hash does not match output commitment");
+        }
+
+        if proof.iteration_count != self.expected_iterations {
+            return reject(format!(
+                "expected {} iterations, proof claims {}",
+                self.expected_iterations, proof.iteration_count
+            ));
+        }
+
+        if proof.transcript.len() != proof.iteration_count || proof.state_hashes.len() != proof.iteration_count {
+            return reject(format!(
+                "transcript has {} entries and {} state hashes for {} iterations",
+                proof.transcript.len(), proof.state_hashes.len(), proof.iteration_count
+            ));
+        }
+
+        if proof.timing.iteration_durations.len() != proof.iteration_count {
+            return reject(format!(
+                "timing record has {} entries for {} iterations",
+                proof.timing.iteration_durations.len(), proof.iteration_count
+            ));
+        }
+
+        if proof.mode != self.mode || proof.memory_size != self.memory_size {
+            return reject(format!(
+                "expected {:?} mode with {} bytes of memory, proof claims {:?} with {}",
+                self.mode, self.memory_size, proof.mode, proof.memory_size
+            ));
+        }
+
+        let digests: Vec<Option<&[u8; 32]>> = match self.mode {
+            IterationMode::Xor if !proof.memory_digests.is_empty() => {
+                return reject("XOR proof carries memory digests");
+            }
+            IterationMode::Xor => vec![None; proof.iteration_count],
+            IterationMode::MemoryHard if proof.memory_digests.len() != proof.iteration_count => {
+                return reject(format!(
+                    "proof has {} memory digests for {} iterations",
+                    proof.memory_digests.len(), proof.iteration_count
+                ));
+            }
+            IterationMode::MemoryHard => proof.memory_digests.iter().map(Some).collect(),
+        };
+
+        // Every entry must chain from the committed initial state
+        let mut previous = proof.initial_state_hash;
+        for (iteration, (state_hash, link)) in proof.state_hashes.iter().zip(&proof.transcript).enumerate() {
+            if chain_link(&previous, iteration, state_hash, digests[iteration]) != *link {
+                return reject(format!("transcript entry {} does not chain from the initial state", iteration));
+            }
+            previous = *link;
+        }
+
+        if proof.transcript.last() != Some(&proof.final_state_hash) {
+            return reject("transcript does not end in the final state");
+        }
+
+        match self.mode {
+            // After the first round the state is a fixpoint of the XOR round,
+            // so every later state hash must repeat the first one.
+            IterationMode::Xor => {
+                if proof.state_hashes.windows(2).any(|pair| pair[0] != pair[1]) {
+                    return reject("state hashes are not consistent with the XOR round");
+                }
+            }
+            IterationMode::MemoryHard => {
+                for iteration in self.memory_checks(proof.iteration_count) {
+                    let previous = match iteration {
+                        0 => &proof.initial_state_hash,
+                        _ => &proof.transcript[iteration - 1],
+                    };
+                    let seed = memory::iteration_seed(previous, iteration);
+                    if memory::memory_hard_digest(&seed, self.memory_size) != proof.memory_digests[iteration] {
+                        return reject(format!("memory-hard digest {} does not match the transcript", iteration));
+                    }
+                }
+            }
+        }
+
+        Verdict::Accepted
+    }
+}
+
+fn reject(reason: impl Into<String>) -> Verdict {
+    let reason = reason.into();
+    warn!(%reason, "Temporal proof rejected");
+    Verdict::Rejected(reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::vdf::temporal::TemporalVDF;
+    use crate::error::{CryptoError, CryptoResult};
+    use std::time::Duration;
+
+    fn completed_vdf(input: &[u8]) -> CryptoResult<TemporalVDF> {
+        let config = TemporalConfig {
+            min_iteration_time: Duration::from_millis(1),
+            enforce_timing: false,
+            ..Default::default()
+        };
+
+        let mut vdf = TemporalVDF::new(config);
+        vdf.initialize(input)?;
+        for _ in 0..CYCLE_LENGTH {
+            vdf.iterate()?;
+        }
+        Ok(vdf)
+    }
+
+    #[test]
+    fn test_verify_from_public_inputs() -> CryptoResult<()> {
+        let vdf = completed_vdf(b"Audited input")?;
+        let input_commitment = vdf.input_commitment().expect("VDF is initialized");
+        let proof = vdf.generate_proof()?;
+        drop(vdf);
+
+        let output_commitment = proof.final_state_hash();
+        let verdict = TemporalVerifier::new().verify(&input_commitment, &output_commitment, &proof);
+        assert_eq!(verdict, Verdict::Accepted);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_decoded_proof() -> CryptoResult<()> {
+        let vdf = completed_vdf(b"Shipped proof")?;
+        let input_commitment = vdf.input_commitment().expect("VDF is initialized");
+        let proof = vdf.generate_proof()?;
+
+        let encoded = proof.encode();
+        let decoded = TemporalProof::decode(&encoded)?;
+        assert_eq!(decoded.encode(), encoded);
+        assert_eq!(decoded.timing(), proof.timing());
+        let output_commitment = TemporalVerifier::commit_output(&decoded, &vdf.get_output()?);
+        assert!(TemporalVerifier::new().verify(&input_commitment, &output_commitment, &decoded).is_accepted());
+
+        assert!(TemporalProof::decode(&encoded[..encoded.len() - 1]).is_err());
+        let mut trailing = encoded.clone();
+        trailing.push(0);
+        assert!(TemporalProof::decode(&trailing).is_err());
+        let mut wrong_magic = encoded;
+        wrong_magic[0] ^= 1;
+        assert!(matches!(TemporalProof::decode(&wrong_magic), Err(CryptoError::InvalidInput(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_output_matches_proof() -> CryptoResult<()> {
+        let vdf = completed_vdf(b"Claimed output")?;
+        let proof = vdf.generate_proof()?;
+        let output = vdf.get_output()?;
+
+        assert_eq!(TemporalVerifier::commit_output(&proof, &output), proof.final_state_hash());
+
+        let mut wrong = output;
+        wrong[0] ^= 1;
+        assert_ne!(TemporalVerifier::commit_output(&proof, &wrong), proof.final_state_hash());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_mismatched_commitments() -> CryptoResult<()> {
+        let vdf = completed_vdf(b"Audited input")?;
+        let input_commitment = vdf.input_commitment().expect("VDF is initialized");
+        let proof = vdf.generate_proof()?;
+        let verifier = TemporalVerifier::new();
+
+        let wrong = [0u8; 32];
+        assert!(!verifier.verify(&wrong, &proof.final_state_hash(), &proof).is_accepted());
+        assert!(!verifier.verify(&input_commitment, &wrong, &proof).is_accepted());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_tampered_transcript() -> CryptoResult<()> {
+        let vdf = completed_vdf(b"Audited input")?;
+        let input_commitment = vdf.input_commitment().expect("VDF is initialized");
+        let verifier = TemporalVerifier::new();
+
+        let mut truncated = vdf.generate_proof()?;
+        truncated.transcript.pop();
+        assert!(!verifier
+            .verify(&input_commitment, &truncated.final_state_hash(), &truncated)
+            .is_accepted());
+
+        let mut altered = vdf.generate_proof()?;
+        altered.transcript[0][0] ^= 1;
+        assert!(!verifier
+            .verify(&input_commitment, &altered.final_state_hash(), &altered)
+            .is_accepted());
+
+        let mut restated = vdf.generate_proof()?;
+        restated.state_hashes[2][0] ^= 1;
+        assert!(!verifier
+            .verify(&input_commitment, &restated.final_state_hash(), &restated)
+            .is_accepted());
+
+        let mut untimed = vdf.generate_proof()?;
+        untimed.timing.iteration_durations.clear();
+        assert!(!verifier
+            .verify(&input_commitment, &untimed.final_state_hash(), &untimed)
+            .is_accepted());
+
+        let mut short = vdf.generate_proof()?;
+        short.iteration_count = 1;
+        short.state_hashes.truncate(1);
+        short.transcript.truncate(1);
+        assert!(!verifier
+            .verify(&input_commitment, &short.final_state_hash(), &short)
+            .is_accepted());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_inconsistent_transcript() -> CryptoResult<()> {
+        let vdf = completed_vdf(b"Audited input")?;
+        let input_commitment = vdf.input_commitment().expect("VDF is initialized");
+        let verifier = TemporalVerifier::new();
+
+        // Only checks the transcript is consistent with the input; an XOR
+        // transcript recomputed from the input would still be accepted.
+
+        // Copies of an arbitrary hash, with the commitments made to match
+        let mut forged = vdf.generate_proof()?;
+        let hash = [0x5a; 32];
+        forged.state_hashes = vec![hash; CYCLE_LENGTH];
+        forged.transcript = vec![hash; CYCLE_LENGTH];
+        forged.final_state_hash = hash;
+        assert!(!verifier.verify(&input_commitment, &hash, &forged).is_accepted());
+
+        // A genuine transcript from another input does not chain from this one
+        let other = completed_vdf(b"Audited input")?.generate_proof()?;
+        let mut spliced = vdf.generate_proof()?;
+        spliced.state_hashes = other.state_hashes.clone();
+        spliced.transcript = other.transcript.clone();
+        spliced.final_state_hash = other.final_state_hash;
+        assert!(!verifier.verify(&input_commitment, &other.final_state_hash, &spliced).is_accepted());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verifier_honors_configured_iterations() -> CryptoResult<()> {
+        let config = TemporalConfig {
+            enforce_timing: false,
+            iterations: 9,
+            ..Default::default()
+        };
+
+        let mut vdf = TemporalVDF::new(config.clone());
+        vdf.initialize(b"Longer delay")?;
+        for _ in 0..config.iterations {
+            vdf.iterate()?;
+        }
+        let input_commitment = vdf.input_commitment().expect("VDF is initialized");
+        let proof = vdf.generate_proof()?;
+
+        let verdict = TemporalVerifier::for_config(&config)
+            .verify(&input_commitment, &proof.final_state_hash(), &proof);
+        assert!(verdict.is_accepted());
+
+        let verdict = TemporalVerifier::new()
+            .verify(&input_commitment, &proof.final_state_hash(), &proof);
+        assert!(!verdict.is_accepted());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_proof_for_other_context() -> CryptoResult<()> {
+        let config = TemporalConfig {
+            enforce_timing: false,
+            ..Default::default()
+        };
+        let context = ProofContext::with_random_challenge("audit");
+
+        let mut vdf = TemporalVDF::new(config.clone());
+        vdf.initialize_with_context(b"Challenged input", context.clone())?;
+        vdf.run_to_completion()?;
+        let input_commitment = vdf.input_commitment().expect("VDF is initialized");
+        let proof = vdf.generate_proof()?;
+        let output_commitment = proof.final_state_hash();
+
+        let verifier = TemporalVerifier::for_config(&config).with_context(context);
+        assert!(verifier.verify(&input_commitment, &output_commitment, &proof).is_accepted());
+
+        // Another challenge, another domain, or the default context all reject it
+        for other in [
+            TemporalVerifier::for_config(&config).with_context(ProofContext::with_random_challenge("audit")),
+            TemporalVerifier::for_config(&config).with_context(ProofContext::new("billing", proof.context().challenge())),
+            TemporalVerifier::for_config(&config),
+        ] {
+            assert!(!other.verify(&input_commitment, &output_commitment, &proof).is_accepted());
+        }
+
+        // Rebinding the commitment and relinking the transcript does not move the
+        // state hashes, so the output no longer matches
+        let output = vdf.get_output()?;
+        let fresh = ProofContext::with_random_challenge("audit");
+        let mut rebound = proof.clone();
+        rebound.context = fresh.clone();
+        rebound.initial_state_hash = fresh.bind(&proof.state_commitment());
+        let mut previous = rebound.initial_state_hash;
+        rebound.transcript = rebound.state_hashes.iter()
+            .enumerate()
+            .map(|(iteration, state_hash)| {
+                previous = chain_link(&previous, iteration, state_hash, None);
+                previous
+            })
+            .collect();
+        rebound.final_state_hash = previous;
+        let verdict = TemporalVerifier::for_config(&config)
+            .with_context(fresh)
+            .verify(&rebound.initial_state_hash, &TemporalVerifier::commit_output(&rebound, &output), &rebound);
+        assert!(!verdict.is_accepted());
+
+        // Swapping in the verifier's context without recomputing the commitment fails too
+        let fresh = ProofContext::with_random_challenge("audit");
+        let mut relabelled = proof;
+        relabelled.context = fresh.clone();
+        let verdict = TemporalVerifier::for_config(&config)
+            .with_context(fresh)
+            .verify(&input_commitment, &output_commitment, &relabelled);
+        assert!(!verdict.is_accepted());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verifies_memory_hard_proof() -> CryptoResult<()> {
+        let config = TemporalConfig {
+            enforce_timing: false,
+            mode: IterationMode::MemoryHard,
+            memory_size: 16 * 1024,
+            ..Default::default()
+        };
+
+        let mut vdf = TemporalVDF::new(config.clone());
+        vdf.initialize(b"Memory-hard delay")?;
+        vdf.run_to_completion()?;
+        let input_commitment = vdf.input_commitment().expect("VDF is initialized");
+        let proof = vdf.generate_proof()?;
+        let output_commitment = proof.final_state_hash();
+
+        let verifier = TemporalVerifier::for_config(&config);
+        assert!(verifier.verify(&input_commitment, &output_commitment, &proof).is_accepted());
+        assert!(TemporalVerifier::new()
+            .memory_hard(16 * 1024)
+            .verify(&input_commitment, &output_commitment, &proof)
+            .is_accepted());
+
+        // A different memory parameter or a plain XOR verifier rejects it
+        let smaller = TemporalVerifier::new().memory_hard(8 * 1024);
+        assert!(!smaller.verify(&input_commitment, &output_commitment, &proof).is_accepted());
+        assert!(!TemporalVerifier::new().verify(&input_commitment, &output_commitment, &proof).is_accepted());
+
+        // Claiming less memory than was used is caught by recomputation
+        let mut understated = proof.clone();
+        understated.memory_size = 8 * 1024;
+        assert!(!smaller.verify(&input_commitment, &output_commitment, &understated).is_accepted());
+
+        let mut forged = proof.clone();
+        forged.memory_digests[1][0] ^= 1;
+        assert!(!verifier.verify(&input_commitment, &output_commitment, &forged).is_accepted());
+
+        // The output carries the memory work, and spot checks accept an honest proof
+        assert_eq!(TemporalVerifier::commit_output(&proof, &vdf.get_output()?), output_commitment);
+        assert!(verifier.clone()
+            .with_spot_checks(2)
+            .verify(&input_commitment, &output_commitment, &proof)
+            .is_accepted());
+
+        // Made-up digests with a consistently relinked transcript fail recomputation
+        let mut skipped = proof;
+        let mut previous = skipped.initial_state_hash;
+        for iteration in 0..skipped.iteration_count {
+            skipped.memory_digests[iteration] = [iteration as u8; 32];
+            previous = chain_link(
+                &previous,
+                iteration,
+                &skipped.state_hashes[iteration],
+                Some(&skipped.memory_digests[iteration]),
+            );
+            skipped.transcript[iteration] = previous;
+        }
+        skipped.final_state_hash = previous;
+        assert!(!verifier.verify(&input_commitment, &previous, &skipped).is_accepted());
+        assert!(!verifier.with_spot_checks(1).verify(&input_commitment, &previous, &skipped).is_accepted());
+
+        Ok(())
+    }
+}
@@ -21,11 +21,23 @@ pub enum CryptoError {
     #[error("Verification failed: {0}")]
     VerificationFailed(String),
 
+    #[error("Authentication failed for party {party}: {reason}")]
+    AuthenticationFailed {
+        party: u8,
+        reason: String,
+    },
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
+    #[error("Replay detected: {0}")]
+    ReplayDetected(String),
+
     #[error("Operation timeout after {0:?}")]
     Timeout(Duration),
+
+    #[error("Operation cancelled")]
+    Cancelled,
 }
 
 pub type CryptoResult<T> = Result<T, CryptoError>;
\ No newline at end of file
@@ -4,7 +4,11 @@
 pub mod crypto;
 pub mod error;
 
-use tracing_subscriber;
+#[cfg(any(test, feature = "sim"))]
+pub mod sim;
+
+#[cfg(feature = "net")]
+pub mod net;
 
 /// Initialize the library with default settings
 pub fn init() {
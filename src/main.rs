@@ -1,9 +1,8 @@
-mod crypto;
-mod error;
-
 use rand::Rng;
-use sha2::{Sha256, Digest};
-use chrono::prelude::*;
+use mcafee::crypto::auth::{self, PartyToken, ThreePartyAuthentication};
+use mcafee::crypto::kdf::KeyDerivation;
+use mcafee::crypto::kex::{GroupKeyExchange, SessionKey};
+use mcafee::error::CryptoResult;
 
 // Helper function to XOR two byte slices
 fn xor_bytes(a: &[u8], b: &[u8]) -> Vec<u8> {
@@ -26,7 +25,7 @@ impl ThreePartySecretSharing {
     }
 
     fn pad_secret(&self, secret: &str) -> Vec<u8> {
-        let mut secret_bytes = secret.as_bytes().to_vec();
+        let secret_bytes = secret.as_bytes().to_vec();
         let original_length = secret_bytes.len();
 
         // We'll prepend the length byte instead of appending it
@@ -82,77 +81,25 @@ impl ThreePartySecretSharing {
     }
 }
 
-struct SecureThreeWayKeyExchange {
-    iteration_count: usize,
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
 }
 
-impl SecureThreeWayKeyExchange {
-    fn new() -> Self {
-        SecureThreeWayKeyExchange { iteration_count: 4 }
-    }
-
-    fn generate_key_share(&self, length: usize) -> Vec<u8> {
-        let mut rng = rand::thread_rng();
-        (0..length).map(|_| rng.gen::<u8>()).collect()
-    }
-
-    fn perform_key_exchange(&self, share_a: &Vec<u8>, share_b: &Vec<u8>, share_c: &Vec<u8>) -> String {
-        let (mut a, mut b, mut c) = (share_a.clone(), share_b.clone(), share_c.clone());
-
-        for _ in 0..self.iteration_count {
-            let temp_b = xor_bytes(&a, &b);
-            let temp_c = xor_bytes(&temp_b, &c);
-            let temp_a = xor_bytes(&temp_c, &a);
-            a = temp_a;
-            b = temp_b;
-            c = temp_c;
-        }
-
-        b = xor_bytes(&a, &b);
-        c = xor_bytes(&b, &c);
-
-        let mut hasher = Sha256::new();
-        hasher.update([a, b, c].concat());
-        format!("{:x}", hasher.finalize())
-    }
-}
-
-struct ThreePartyAuthentication {
-    token_length: usize,
-}
-
-impl ThreePartyAuthentication {
-    fn new() -> Self {
-        ThreePartyAuthentication { token_length: 32 }
-    }
-
-    fn generate_auth_tokens(&self) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
-        let mut rng = rand::thread_rng();
-        (
-            (0..self.token_length).map(|_| rng.gen::<u8>()).collect(),
-            (0..self.token_length).map(|_| rng.gen::<u8>()).collect(),
-            (0..self.token_length).map(|_| rng.gen::<u8>()).collect(),
-        )
-    }
-
-    fn create_auth_challenge(&self, message: &str, token_a: &[u8], token_b: &[u8], token_c: &[u8]) -> (Vec<Vec<u8>>, String, String) {
-        let timestamp = Utc::now().to_string();
-        let data = format!("{}{}", message, timestamp);
-
-        let mut sharing = ThreePartySecretSharing::new();
-        let auth_parts = sharing.distribute_secret(&data);
-
-        let combined = auth_parts.0.iter()
-            .chain(auth_parts.1.iter())
-            .chain(auth_parts.2.iter())
-            .cloned()
-            .collect::<Vec<u8>>();
-        let mut hasher = Sha256::new();
-        hasher.update(&combined);
-        let verification_hash = format!("{:x}", hasher.finalize());
-
-        (vec![auth_parts.0, auth_parts.1, auth_parts.2], verification_hash, timestamp)
-    }
+fn run_key_exchange() -> CryptoResult<SessionKey> {
+    let mut parties = (0..3u8).map(GroupKeyExchange::new).collect::<CryptoResult<Vec<_>>>()?;
+
+    let round1: Vec<_> = parties.iter().map(GroupKeyExchange::round1).collect();
+    let round2 = parties.iter_mut()
+        .map(|party| party.round2(&round1))
+        .collect::<CryptoResult<Vec<_>>>()?;
+    let confirmations = parties.iter_mut()
+        .map(|party| party.confirm(&round2))
+        .collect::<CryptoResult<Vec<_>>>()?;
+
+    let keys = parties.into_iter()
+        .map(|party| party.finish(&confirmations))
+        .collect::<CryptoResult<Vec<_>>>()?;
+    Ok(keys[0].clone())
 }
 
 fn main() {
@@ -171,27 +118,31 @@ fn main() {
 
     // Key Exchange Example
     println!("2. Three-Way Key Exchange Demonstration");
-    let exchange = SecureThreeWayKeyExchange::new();
-    let share_a = exchange.generate_key_share(32);
-    let share_b = exchange.generate_key_share(32);
-    let share_c = exchange.generate_key_share(32);
-    let shared_key = exchange.perform_key_exchange(&share_a, &share_b, &share_c);
-    println!("Generated shared key: {}", shared_key);
+    let shared_key = run_key_exchange().expect("key exchange should succeed");
+    println!("Agreed session key fingerprint: {}", to_hex(&shared_key.fingerprint()));
+    let keys = KeyDerivation::from_session_key(&shared_key)
+        .derive_keys("demo")
+        .expect("demo context is a valid label");
+    println!("Derived session identifier: {}", to_hex(&keys.identifier));
     println!("\n{}\n", "=".repeat(50));
 
     // Three-Party Authentication Example
     println!("3. Three-Party Authentication Demonstration");
-    let auth = ThreePartyAuthentication::new();
-    let (token_a, token_b, token_c) = auth.generate_auth_tokens();
+    let tokens = [PartyToken::generate(), PartyToken::generate(), PartyToken::generate()];
+    let auth = ThreePartyAuthentication::new(tokens.clone());
     let message = "Request for access to secure resource";
-    let (auth_parts, verification_hash, timestamp) = auth.create_auth_challenge(
-        message,
-        &token_a,
-        &token_b,
-        &token_c
-    );
+    let challenge = auth.issue(message);
+    let responses = tokens.iter()
+        .enumerate()
+        .map(|(party, token)| auth::respond(&challenge, party as u8, token))
+        .collect::<CryptoResult<Vec<_>>>()
+        .expect("parties should answer the challenge");
 
     println!("Authentication message: {}", message);
-    println!("Verification hash: {}", verification_hash);
-    println!("Timestamp: {}", timestamp);
+    println!("Challenge nonce: {}", to_hex(challenge.nonce()));
+    println!("Timestamp: {}", challenge.issued_at());
+    match auth.verify(&challenge, &responses) {
+        Ok(()) => println!("All {} parties authenticated", responses.len()),
+        Err(err) => println!("Authentication failed: {}", err),
+    }
 }
\ No newline at end of file
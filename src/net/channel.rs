@@ -0,0 +1,183 @@
+//! Noise-encrypted, mutually authenticated message channel over TCP
+//! Location: src/net/channel.rs
+//!
+//! Both sides run `Noise_XX_25519_ChaChaPoly_SHA256` with long-term static
+//! keys and each checks the other's static key against what it expects
+//! before any protocol message is exchanged. Messages are split into Noise
+//! records, each sent as a big-endian u16 length and ciphertext.
+
+use super::Identity;
+use crate::crypto::protocol::Message;
+use crate::error::{CryptoError, CryptoResult};
+use snow::{HandshakeState, TransportState};
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+use tracing::debug;
+
+/// Noise protocol name
+pub(crate) const NOISE_PARAMS: &str = "Noise_XX_25519_ChaChaPoly_SHA256";
+/// Prologue binding both sides to this protocol version
+const PROLOGUE: &[u8] = b"mcafee/net/v1";
+/// Largest Noise record
+const MAX_RECORD: usize = 65535;
+/// Authentication tag added to every record
+const TAG_LEN: usize = 16;
+/// Largest plaintext carried by one record
+const MAX_CHUNK: usize = MAX_RECORD - TAG_LEN;
+/// Largest encoded message accepted from a peer
+pub const MAX_MESSAGE_LEN: usize = 64 * 1024 * 1024;
+
+/// Default time to wait for a peer before failing with an IO error
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Encrypted channel to an authenticated peer
+#[derive(Debug)]
+pub struct SecureChannel {
+    stream: TcpStream,
+    transport: TransportState,
+    remote_key: [u8; 32],
+}
+
+impl SecureChannel {
+    /// Runs the handshake as initiator, requiring the responder to hold `expected_remote`
+    pub fn connect(stream: TcpStream, identity: &Identity, expected_remote: &[u8; 32]) -> CryptoResult<Self> {
+        let mut channel = Self::handshake(stream, identity, true)?;
+        if &channel.remote_key != expected_remote {
+            channel.close();
+            return Err(CryptoError::VerificationFailed("Peer presented an unexpected static key".into()));
+        }
+        Ok(channel)
+    }
+
+    /// Runs the handshake as responder; the caller decides whether to trust `remote_key`
+    pub fn accept(stream: TcpStream, identity: &Identity) -> CryptoResult<Self> {
+        Self::handshake(stream, identity, false)
+    }
+
+    /// Peer's authenticated static public key
+    pub fn remote_key(&self) -> &[u8; 32] {
+        &self.remote_key
+    }
+
+    /// Encrypts and sends a protocol message
+    pub fn send(&mut self, message: &Message) -> CryptoResult<()> {
+        let encoded = message.encode();
+        self.write_record(&(encoded.len() as u64).to_be_bytes())?;
+        for chunk in encoded.chunks(MAX_CHUNK) {
+            self.write_record(chunk)?;
+        }
+        self.stream.flush()?;
+        Ok(())
+    }
+
+    /// Receives and decrypts the next protocol message, or `None` if the peer closed cleanly
+    pub fn receive(&mut self) -> CryptoResult<Option<Message>> {
+        let header = match self.read_record() {
+            Ok(header) => header,
+            Err(CryptoError::IoError(err)) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let len = <[u8; 8]>::try_from(header.as_slice())
+            .map(u64::from_be_bytes)
+            .map_err(|_| invalid_data("malformed message header"))? as usize;
+        if len > MAX_MESSAGE_LEN {
+            return Err(invalid_data("message exceeds size limit").into());
+        }
+
+        // Grow with the records actually received, not the peer's claimed length
+        let mut encoded = Vec::new();
+        while encoded.len() < len {
+            let record = self.read_record()?;
+            if record.is_empty() {
+                return Err(invalid_data("empty message record").into());
+            }
+            encoded.extend_from_slice(&record);
+        }
+        if encoded.len() != len {
+            return Err(invalid_data("message length mismatch").into());
+        }
+        Message::decode(&encoded).map(Some)
+    }
+
+    /// Shuts the connection down in both directions
+    pub fn close(&mut self) {
+        let _ = self.stream.shutdown(std::net::Shutdown::Both);
+    }
+
+    fn handshake(mut stream: TcpStream, identity: &Identity, initiator: bool) -> CryptoResult<Self> {
+        stream.set_read_timeout(Some(DEFAULT_TIMEOUT))?;
+        stream.set_write_timeout(Some(DEFAULT_TIMEOUT))?;
+        stream.set_nodelay(true)?;
+
+        let builder = snow::Builder::new(NOISE_PARAMS.parse().map_err(noise_error)?)
+            .prologue(PROLOGUE)
+            .local_private_key(identity.private_key());
+        let mut state = if initiator {
+            builder.build_initiator()
+        } else {
+            builder.build_responder()
+        }.map_err(noise_error)?;
+
+        // XX: → e; ← e, ee, s, es; → s, se
+        let mut buf = vec![0u8; MAX_RECORD];
+        for step in 0..3 {
+            let ours = (step % 2 == 0) == initiator;
+            if ours {
+                let len = state.write_message(&[], &mut buf).map_err(noise_error)?;
+                write_frame(&mut stream, &buf[..len])?;
+            } else {
+                let frame = read_frame(&mut stream)?;
+                state.read_message(&frame, &mut buf).map_err(noise_error)?;
+            }
+        }
+
+        let remote_key = remote_static(&state)?;
+        let transport = state.into_transport_mode().map_err(noise_error)?;
+        debug!(initiator, peer = ?stream.peer_addr().ok(), "Noise handshake complete");
+        Ok(Self { stream, transport, remote_key })
+    }
+
+    fn write_record(&mut self, plaintext: &[u8]) -> CryptoResult<()> {
+        let mut buf = vec![0u8; plaintext.len() + TAG_LEN];
+        let len = self.transport.write_message(plaintext, &mut buf).map_err(noise_error)?;
+        write_frame(&mut self.stream, &buf[..len])
+    }
+
+    fn read_record(&mut self) -> CryptoResult<Vec<u8>> {
+        let frame = read_frame(&mut self.stream)?;
+        let mut buf = vec![0u8; frame.len()];
+        let len = self.transport.read_message(&frame, &mut buf).map_err(noise_error)?;
+        buf.truncate(len);
+        Ok(buf)
+    }
+}
+
+fn remote_static(state: &HandshakeState) -> CryptoResult<[u8; 32]> {
+    state.get_remote_static()
+        .and_then(|key| key.try_into().ok())
+        .ok_or_else(|| CryptoError::VerificationFailed("Peer did not present a static key".into()))
+}
+
+fn write_frame(stream: &mut TcpStream, frame: &[u8]) -> CryptoResult<()> {
+    stream.write_all(&(frame.len() as u16).to_be_bytes())?;
+    stream.write_all(frame)?;
+    Ok(())
+}
+
+fn read_frame(stream: &mut TcpStream) -> CryptoResult<Vec<u8>> {
+    let mut len = [0u8; 2];
+    stream.read_exact(&mut len)?;
+    let mut frame = vec![0u8; u16::from_be_bytes(len) as usize];
+    stream.read_exact(&mut frame)?;
+    Ok(frame)
+}
+
+fn invalid_data(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason.to_string())
+}
+
+/// Handshake and decryption failures mean the bytes on the wire were bad
+fn noise_error(err: snow::Error) -> CryptoError {
+    invalid_data(&format!("noise: {}", err)).into()
+}
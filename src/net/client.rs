@@ -0,0 +1,72 @@
+//! Dealer and combiner clients talking to custodian servers
+//! Location: src/net/client.rs
+
+use super::{channel::SecureChannel, Identity};
+use crate::crypto::protocol::{DeliveryAck, Message, ReconstructRequest, ShareDelivery, ShareRelease};
+use crate::error::{CryptoError, CryptoResult};
+use std::net::{TcpStream, ToSocketAddrs};
+use tracing::debug;
+
+/// Opens an authenticated channel to the custodian holding `custodian_key`
+fn connect(addr: impl ToSocketAddrs, identity: &Identity, custodian_key: &[u8; 32]) -> CryptoResult<SecureChannel> {
+    SecureChannel::connect(TcpStream::connect(addr)?, identity, custodian_key)
+}
+
+/// Sends a message and waits for the single reply
+fn exchange(channel: &mut SecureChannel, message: &Message) -> CryptoResult<Message> {
+    channel.send(message)?;
+    channel.receive()?.ok_or_else(|| CryptoError::IoError(std::io::Error::new(
+        std::io::ErrorKind::UnexpectedEof,
+        "custodian closed the connection without replying",
+    )))
+}
+
+/// Dealer's connection to one custodian
+#[derive(Debug)]
+pub struct DealerClient {
+    channel: SecureChannel,
+}
+
+impl DealerClient {
+    pub fn connect(addr: impl ToSocketAddrs, identity: &Identity, custodian_key: &[u8; 32]) -> CryptoResult<Self> {
+        Ok(Self { channel: connect(addr, identity, custodian_key)? })
+    }
+
+    /// Delivers a share and waits for the custodian's acknowledgement
+    pub fn deliver(&mut self, delivery: ShareDelivery) -> CryptoResult<DeliveryAck> {
+        let expected = (delivery.secret_id, delivery.share.id());
+        match exchange(&mut self.channel, &delivery.into())? {
+            Message::DeliveryAck(ack) if (ack.secret_id, ack.share_id) == expected => {
+                debug!(secret = %ack.secret_id, custodian = ack.share_id, "Delivery acknowledged over TCP");
+                Ok(ack)
+            }
+            other => Err(CryptoError::VerificationFailed(
+                format!("Unexpected {} in reply to a delivery", other.kind())
+            )),
+        }
+    }
+}
+
+/// Combiner's connection to one custodian
+#[derive(Debug)]
+pub struct CombinerClient {
+    channel: SecureChannel,
+}
+
+impl CombinerClient {
+    pub fn connect(addr: impl ToSocketAddrs, identity: &Identity, custodian_key: &[u8; 32]) -> CryptoResult<Self> {
+        Ok(Self { channel: connect(addr, identity, custodian_key)? })
+    }
+
+    /// Asks the custodian to release its share
+    pub fn release(&mut self, request: ReconstructRequest) -> CryptoResult<ShareRelease> {
+        match exchange(&mut self.channel, &request.into())? {
+            Message::ShareRelease(release) if release.secret_id == request.secret_id && release.nonce == request.nonce => {
+                Ok(release)
+            }
+            other => Err(CryptoError::VerificationFailed(
+                format!("Unexpected {} in reply to a release request", other.kind())
+            )),
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! TCP transport for share distribution, behind the `net` feature
+//! Location: src/net/mod.rs
+//!
+//! A `CustodianServer` wraps a protocol `Custodian`; the dealer and combiner
+//! reach it with `DealerClient` and `CombinerClient`. Every connection is a
+//! Noise channel on which both ends prove their long-term static key, so a
+//! custodian only talks to peers it was told to trust and vice versa.
+
+pub mod channel;
+pub mod client;
+pub mod server;
+
+pub use channel::SecureChannel;
+pub use client::{CombinerClient, DealerClient};
+pub use server::CustodianServer;
+
+use crate::error::{CryptoError, CryptoResult};
+use std::fmt;
+
+/// Long-term X25519 static keypair identifying a node
+#[derive(Clone)]
+pub struct Identity {
+    private: Vec<u8>,
+    public: [u8; 32],
+}
+
+impl Identity {
+    /// Generates a fresh keypair
+    pub fn generate() -> CryptoResult<Self> {
+        let keypair = snow::Builder::new(channel::NOISE_PARAMS.parse().expect("valid noise params"))
+            .generate_keypair()
+            .map_err(|e| CryptoError::InvalidState(format!("Key generation failed: {}", e)))?;
+        Self::from_keys(keypair.private, &keypair.public)
+    }
+
+    /// Restores a keypair from its private and public halves
+    pub fn from_keys(private: Vec<u8>, public: &[u8]) -> CryptoResult<Self> {
+        if private.len() != 32 {
+            return Err(CryptoError::InvalidInput("Private key must be 32 bytes".into()));
+        }
+        let public = public.try_into()
+            .map_err(|_| CryptoError::InvalidInput("Public key must be 32 bytes".into()))?;
+        Ok(Self { private, public })
+    }
+
+    /// Public key to hand to peers
+    pub fn public_key(&self) -> &[u8; 32] {
+        &self.public
+    }
+
+    pub(crate) fn private_key(&self) -> &[u8] {
+        &self.private
+    }
+}
+
+impl fmt::Debug for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Identity")
+            .field("public", &self.public)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::protocol::{Actor, Combiner, Custodian, Dealer, Message, NodeId};
+    use crate::crypto::sharing::ThreePartySecretSharing;
+    use std::thread;
+
+    struct Keys {
+        dealer: Identity,
+        combiner: Identity,
+        custodians: Vec<Identity>,
+    }
+
+    fn keys() -> CryptoResult<Keys> {
+        Ok(Keys {
+            dealer: Identity::generate()?,
+            combiner: Identity::generate()?,
+            custodians: (0..3).map(|_| Identity::generate()).collect::<CryptoResult<_>>()?,
+        })
+    }
+
+    /// Starts a custodian serving `connections` connections on a background thread
+    fn spawn_custodian(
+        keys: &Keys,
+        id: u8,
+        connections: usize,
+    ) -> CryptoResult<(std::net::SocketAddr, thread::JoinHandle<CryptoResult<Custodian>>)> {
+        let mut server = CustodianServer::bind("127.0.0.1:0", keys.custodians[id as usize].clone(), Custodian::new(id))?
+            .authorize(NodeId::Dealer, *keys.dealer.public_key())
+            .authorize(NodeId::Combiner, *keys.combiner.public_key());
+        let addr = server.local_addr()?;
+        let handle = thread::spawn(move || {
+            for _ in 0..connections {
+                server.serve_one()?;
+            }
+            Ok(server.into_custodian())
+        });
+        Ok((addr, handle))
+    }
+
+    #[test]
+    fn test_deliver_and_release_over_tcp() -> CryptoResult<()> {
+        let keys = keys()?;
+        let servers = (0..3u8)
+            .map(|id| spawn_custodian(&keys, id, 2))
+            .collect::<CryptoResult<Vec<_>>>()?;
+
+        let mut dealer = Dealer::new(ThreePartySecretSharing::default());
+        let secret = vec![0x5a; 200_000];
+        let (id, deliveries) = dealer.deal(&secret)?;
+        for envelope in deliveries {
+            let (NodeId::Custodian(c), Message::ShareDelivery(delivery)) = (envelope.to, envelope.message) else {
+                panic!("dealer sent an unexpected envelope");
+            };
+            let mut client = DealerClient::connect(servers[c as usize].0, &keys.dealer, keys.custodians[c as usize].public_key())?;
+            let ack = client.deliver(delivery)?;
+            dealer.handle(NodeId::Custodian(c), ack.into())?;
+        }
+        assert!(dealer.is_delivered(&id));
+
+        let mut combiner = Combiner::new(ThreePartySecretSharing::default());
+        for envelope in combiner.request(id) {
+            let (NodeId::Custodian(c), Message::ReconstructRequest(request)) = (envelope.to, envelope.message) else {
+                panic!("combiner sent an unexpected envelope");
+            };
+            let mut client = CombinerClient::connect(servers[c as usize].0, &keys.combiner, keys.custodians[c as usize].public_key())?;
+            let release = client.release(request)?;
+            combiner.handle(NodeId::Custodian(c), release.into())?;
+        }
+        assert_eq!(combiner.take_secret(&id), Some(secret));
+
+        for (_, handle) in servers {
+            assert!(handle.join().expect("server thread")?.holds(&id));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_dealer_key_cannot_request_release() -> CryptoResult<()> {
+        let keys = keys()?;
+        let (addr, handle) = spawn_custodian(&keys, 0, 2)?;
+
+        let mut dealer = Dealer::new(ThreePartySecretSharing::default());
+        let (id, deliveries) = dealer.deal(b"custodied secret")?;
+        let delivery = deliveries.into_iter()
+            .find_map(|envelope| match (envelope.to, envelope.message) {
+                (NodeId::Custodian(0), Message::ShareDelivery(delivery)) => Some(delivery),
+                _ => None,
+            })
+            .expect("dealer delivers share 0");
+        DealerClient::connect(addr, &keys.dealer, keys.custodians[0].public_key())?.deliver(delivery)?;
+
+        // The dealer's key speaks as the dealer, whatever client it connects with
+        let mut client = CombinerClient::connect(addr, &keys.dealer, keys.custodians[0].public_key())?;
+        let request = crate::crypto::protocol::ReconstructRequest { secret_id: id, nonce: [3; 32] };
+        assert!(client.release(request).is_err());
+        assert!(matches!(handle.join().expect("server thread"), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unauthenticated_peers_rejected() -> CryptoResult<()> {
+        let keys = keys()?;
+
+        // An unknown client is turned away by the custodian
+        let (addr, handle) = spawn_custodian(&keys, 0, 1)?;
+        let stranger = Identity::generate()?;
+        let mut client = DealerClient::connect(addr, &stranger, keys.custodians[0].public_key())?;
+        let delivery = crate::crypto::protocol::ShareDelivery {
+            secret_id: crate::crypto::protocol::SecretId::random(),
+            share: crate::crypto::sharing::Share::new(vec![1; 16], 0),
+        };
+        assert!(client.deliver(delivery).is_err());
+        assert!(matches!(handle.join().expect("server thread"), Err(CryptoError::VerificationFailed(_))));
+
+        // A client refuses a server presenting the wrong key
+        let (addr, handle) = spawn_custodian(&keys, 1, 1)?;
+        let result = DealerClient::connect(addr, &keys.dealer, keys.custodians[2].public_key());
+        assert!(matches!(result, Err(CryptoError::VerificationFailed(_))));
+        let _ = handle.join();
+
+        // Nothing listening is an IO error
+        let closed = std::net::TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+        assert!(matches!(
+            DealerClient::connect(closed, &keys.dealer, keys.custodians[0].public_key()),
+            Err(CryptoError::IoError(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+//! Custodian server answering deliveries and release requests over TCP
+//! Location: src/net/server.rs
+
+use super::{channel::SecureChannel, Identity};
+use crate::crypto::protocol::{Actor, Custodian, NodeId};
+use crate::error::{CryptoError, CryptoResult};
+use std::collections::HashMap;
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+use tracing::{info, warn};
+
+/// Serves one custodian to the dealer and combiner over Noise channels
+#[derive(Debug)]
+pub struct CustodianServer {
+    listener: TcpListener,
+    identity: Identity,
+    custodian: Custodian,
+    /// Static keys allowed to connect, and the role each one speaks as
+    peers: HashMap<[u8; 32], NodeId>,
+}
+
+impl CustodianServer {
+    /// Listens on `addr` for the custodian's authorized peers
+    pub fn bind(addr: impl ToSocketAddrs, identity: Identity, custodian: Custodian) -> CryptoResult<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            identity,
+            custodian,
+            peers: HashMap::new(),
+        })
+    }
+
+    /// Authorizes a peer's static key to connect as `node`
+    pub fn authorize(mut self, node: NodeId, public_key: [u8; 32]) -> Self {
+        self.peers.insert(public_key, node);
+        self
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> CryptoResult<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Custodian state, e.g. for inspecting held shares
+    pub fn custodian(&self) -> &Custodian {
+        &self.custodian
+    }
+
+    /// Gives back the custodian once serving is done
+    pub fn into_custodian(self) -> Custodian {
+        self.custodian
+    }
+
+    /// Accepts one connection and serves it until the peer disconnects
+    ///
+    /// Messages are handled as coming from the role the peer's key was
+    /// authorized for, so the custodian refuses a release request from the
+    /// dealer's key and a delivery from the combiner's. Errors from
+    /// individual messages end that connection but leave the custodian's
+    /// state intact.
+    pub fn serve_one(&mut self) -> CryptoResult<()> {
+        let (stream, addr) = self.listener.accept()?;
+        let mut channel = SecureChannel::accept(stream, &self.identity)?;
+
+        let Some(&peer) = self.peers.get(channel.remote_key()) else {
+            warn!(%addr, "Rejected connection from unknown key");
+            channel.close();
+            return Err(CryptoError::VerificationFailed("Peer key is not authorized".into()));
+        };
+        info!(%addr, %peer, custodian = self.custodian.share_id(), "Peer connected");
+
+        while let Some(message) = channel.receive()? {
+            for envelope in self.custodian.handle(peer, message)? {
+                if envelope.to != peer {
+                    return Err(CryptoError::InvalidState(format!("Cannot route reply to {}", envelope.to)));
+                }
+                channel.send(&envelope.message)?;
+            }
+        }
+
+        info!(%addr, %peer, "Peer disconnected");
+        Ok(())
+    }
+}
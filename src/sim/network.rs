@@ -0,0 +1,317 @@
+//! Seeded virtual network with fault injection and transcripts
+//! Location: src/sim/network.rs
+
+use super::{SimMessage, SimNode};
+use crate::crypto::protocol::{Envelope, Message, NodeId};
+use crate::error::{CryptoError, CryptoResult};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeMap, HashSet};
+use tracing::{debug, trace};
+
+/// Faults applied to every message sent
+#[derive(Debug, Clone)]
+pub struct FaultPlan {
+    /// Probability a message is lost
+    pub drop_rate: f64,
+    /// Probability a message is delivered twice
+    pub duplicate_rate: f64,
+    /// Fewest ticks between send and delivery
+    pub min_delay: u64,
+    /// Most ticks between send and delivery; a spread reorders messages
+    pub max_delay: u64,
+}
+
+impl FaultPlan {
+    /// Checks that both rates are probabilities
+    pub fn validate(&self) -> CryptoResult<()> {
+        for (name, rate) in [("drop", self.drop_rate), ("duplicate", self.duplicate_rate)] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(CryptoError::InvalidInput(
+                    format!("Fault {} rate {} is not between 0 and 1", name, rate)
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for FaultPlan {
+    fn default() -> Self {
+        Self {
+            drop_rate: 0.0,
+            duplicate_rate: 0.0,
+            min_delay: 1,
+            max_delay: 1,
+        }
+    }
+}
+
+/// What happened to a message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// Queued for delivery at the given tick
+    Sent { deliver_at: u64 },
+    /// Lost in transit
+    Dropped,
+    /// A second copy was queued
+    Duplicated { deliver_at: u64 },
+    /// Handed to the recipient
+    Delivered,
+    /// The recipient is crashed or unknown
+    Undeliverable,
+    /// The recipient returned an error
+    Rejected(String),
+}
+
+/// One transcript line
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub tick: u64,
+    pub from: NodeId,
+    pub to: NodeId,
+    pub kind: &'static str,
+    pub event: Event,
+}
+
+/// Everything the network did, in order
+#[derive(Debug, Clone, Default)]
+pub struct Transcript {
+    records: Vec<Record>,
+}
+
+impl Transcript {
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// Number of records matching a predicate
+    pub fn count(&self, predicate: impl Fn(&Record) -> bool) -> usize {
+        self.records.iter().filter(|record| predicate(record)).count()
+    }
+
+    /// Number of messages of a kind handed to their recipient
+    pub fn delivered(&self, kind: &str) -> usize {
+        self.count(|record| record.kind == kind && record.event == Event::Delivered)
+    }
+
+    /// Number of messages lost in transit
+    pub fn dropped(&self) -> usize {
+        self.count(|record| record.event == Event::Dropped)
+    }
+
+    /// Errors returned by recipients
+    pub fn rejections(&self) -> Vec<&Record> {
+        self.records.iter()
+            .filter(|record| matches!(record.event, Event::Rejected(_)))
+            .collect()
+    }
+}
+
+/// A message waiting to be delivered
+#[derive(Debug)]
+struct InFlight<M> {
+    from: NodeId,
+    to: NodeId,
+    message: M,
+}
+
+/// Deterministic virtual network
+#[derive(Debug)]
+pub struct Network<M> {
+    rng: StdRng,
+    faults: FaultPlan,
+    now: u64,
+    /// Messages keyed by delivery tick, then send order
+    queue: BTreeMap<(u64, u64), InFlight<M>>,
+    sequence: u64,
+    crashed: HashSet<NodeId>,
+    transcript: Transcript,
+}
+
+impl<M: SimMessage> Network<M> {
+    /// Creates a network whose faults are drawn from `seed`
+    pub fn new(seed: u64, faults: FaultPlan) -> CryptoResult<Self> {
+        faults.validate()?;
+        Ok(Self {
+            rng: StdRng::seed_from_u64(seed),
+            faults,
+            now: 0,
+            queue: BTreeMap::new(),
+            sequence: 0,
+            crashed: HashSet::new(),
+            transcript: Transcript::default(),
+        })
+    }
+
+    /// Current virtual time
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Messages still in flight
+    pub fn in_flight(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn transcript(&self) -> &Transcript {
+        &self.transcript
+    }
+
+    /// Stops delivering messages to a node
+    pub fn crash(&mut self, node: NodeId) {
+        debug!(%node, tick = self.now, "Node crashed");
+        self.crashed.insert(node);
+    }
+
+    /// Resumes delivering messages to a node
+    pub fn recover(&mut self, node: NodeId) {
+        debug!(%node, tick = self.now, "Node recovered");
+        self.crashed.remove(&node);
+    }
+
+    /// Sends one message, applying the fault plan
+    pub fn send(&mut self, from: NodeId, to: NodeId, message: M) {
+        let kind = message.kind();
+        if self.rng.gen_bool(self.faults.drop_rate) {
+            self.record(from, to, kind, Event::Dropped);
+            return;
+        }
+
+        if self.rng.gen_bool(self.faults.duplicate_rate) {
+            let deliver_at = self.enqueue(from, to, message.clone());
+            self.record(from, to, kind, Event::Duplicated { deliver_at });
+        }
+        let deliver_at = self.enqueue(from, to, message);
+        self.record(from, to, kind, Event::Sent { deliver_at });
+    }
+
+    /// Sends every message from one node
+    pub fn send_all(&mut self, from: NodeId, messages: impl IntoIterator<Item = (NodeId, M)>) {
+        for (to, message) in messages {
+            self.send(from, to, message);
+        }
+    }
+
+    /// Delivers messages in time order until none are left or `max_steps` is reached
+    ///
+    /// Replies are sent through the same faulty network. Returns the number
+    /// of messages taken off the queue.
+    pub fn run(&mut self, nodes: &mut [&mut dyn SimNode<M>], max_steps: usize) -> usize {
+        let mut steps = 0;
+        while steps < max_steps {
+            let Some(((tick, _), in_flight)) = self.queue.pop_first() else {
+                break;
+            };
+            steps += 1;
+            self.now = tick;
+
+            let InFlight { from, to, message } = in_flight;
+            let kind = message.kind();
+            let node = nodes.iter_mut().find(|node| node.id() == to);
+            let node = match node {
+                Some(node) if !self.crashed.contains(&to) => node,
+                _ => {
+                    self.record(from, to, kind, Event::Undeliverable);
+                    continue;
+                }
+            };
+
+            self.record(from, to, kind, Event::Delivered);
+            match node.receive(from, message) {
+                Ok(replies) => self.send_all(to, replies),
+                Err(err) => self.record(from, to, kind, Event::Rejected(err.to_string())),
+            }
+        }
+        steps
+    }
+
+    fn enqueue(&mut self, from: NodeId, to: NodeId, message: M) -> u64 {
+        let delay = self.rng.gen_range(self.faults.min_delay..=self.faults.max_delay.max(self.faults.min_delay));
+        let deliver_at = self.now + delay;
+        self.queue.insert((deliver_at, self.sequence), InFlight { from, to, message });
+        self.sequence += 1;
+        deliver_at
+    }
+
+    fn record(&mut self, from: NodeId, to: NodeId, kind: &'static str, event: Event) {
+        trace!(tick = self.now, %from, %to, kind, ?event, "Network event");
+        self.transcript.records.push(Record { tick: self.now, from, to, kind, event });
+    }
+}
+
+impl Network<Message> {
+    /// Sends envelopes produced by a protocol actor
+    pub fn send_envelopes(&mut self, from: NodeId, envelopes: Vec<Envelope>) {
+        self.send_all(from, envelopes.into_iter().map(|envelope| (envelope.to, envelope.message)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::protocol::{Combiner, Custodian, Dealer, SecretId};
+    use crate::crypto::sharing::ThreePartySecretSharing;
+
+    fn lossy() -> FaultPlan {
+        FaultPlan {
+            drop_rate: 0.3,
+            duplicate_rate: 0.2,
+            min_delay: 1,
+            max_delay: 20,
+        }
+    }
+
+    fn summary(network: &Network<Message>) -> Vec<(u64, NodeId, NodeId, &'static str, Event)> {
+        network.transcript().records().iter()
+            .map(|r| (r.tick, r.from, r.to, r.kind, r.event.clone()))
+            .collect()
+    }
+
+    fn deliver(seed: u64) -> Network<Message> {
+        let mut network = Network::new(seed, lossy()).unwrap();
+        let mut dealer = Dealer::new(ThreePartySecretSharing::default());
+        let mut custodians: Vec<_> = (0..3).map(Custodian::new).collect();
+
+        let (_, deliveries) = dealer.deal(b"deterministic").unwrap();
+        network.send_envelopes(NodeId::Dealer, deliveries);
+        let mut nodes: Vec<&mut dyn SimNode<Message>> = vec![&mut dealer];
+        nodes.extend(custodians.iter_mut().map(|c| c as &mut dyn SimNode<Message>));
+        network.run(&mut nodes, 1_000);
+        network
+    }
+
+    #[test]
+    fn test_same_seed_same_transcript() {
+        assert_eq!(summary(&deliver(7)), summary(&deliver(7)));
+        assert!((0..20).any(|seed| summary(&deliver(seed)) != summary(&deliver(7))));
+    }
+
+    #[test]
+    fn test_crashed_node_gets_nothing() {
+        let mut network = Network::new(1, FaultPlan::default()).unwrap();
+        let mut combiner = Combiner::new(ThreePartySecretSharing::default());
+        let mut custodian = Custodian::new(0);
+        network.crash(NodeId::Custodian(0));
+
+        let requests = combiner.request(SecretId::random());
+        network.send_envelopes(NodeId::Combiner, requests);
+        network.run(&mut [&mut combiner, &mut custodian], 100);
+
+        assert_eq!(network.transcript().delivered("ReconstructRequest"), 0);
+        assert_eq!(network.transcript().count(|r| r.event == Event::Undeliverable), 3);
+        assert_eq!(network.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_fault_rates_validated() {
+        for faults in [
+            FaultPlan { drop_rate: 1.5, ..Default::default() },
+            FaultPlan { duplicate_rate: -0.1, ..Default::default() },
+            FaultPlan { drop_rate: f64::NAN, ..Default::default() },
+        ] {
+            assert!(matches!(Network::<Message>::new(0, faults), Err(CryptoError::InvalidInput(_))));
+        }
+        assert!(Network::<Message>::new(0, FaultPlan { drop_rate: 1.0, ..Default::default() }).is_ok());
+    }
+}
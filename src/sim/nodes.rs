@@ -0,0 +1,319 @@
+//! Message-driven adapters for running key exchange and authentication on the simulator
+//! Location: src/sim/nodes.rs
+//!
+//! The custodians double as the three key exchange and authentication
+//! parties, so party `i` lives at `NodeId::Custodian(i)` and the verifier at
+//! `NodeId::Combiner`.
+
+use super::{SimMessage, SimNode};
+use crate::crypto::auth::{self, AuthChallenge, AuthResponse, PartyToken, ThreePartyAuthentication};
+use crate::crypto::kex::{Confirmation, GroupKeyExchange, Round1Message, Round2Message, SessionKey, PARTY_COUNT};
+use crate::crypto::protocol::NodeId;
+use crate::error::{CryptoError, CryptoResult};
+use tracing::debug;
+
+/// Key exchange messages carried by the network
+#[derive(Debug, Clone)]
+pub enum KexMessage {
+    Round1(Round1Message),
+    Round2(Round2Message),
+    Confirm(Confirmation),
+}
+
+impl KexMessage {
+    fn party(&self) -> u8 {
+        match self {
+            KexMessage::Round1(m) => m.party,
+            KexMessage::Round2(m) => m.party,
+            KexMessage::Confirm(m) => m.party,
+        }
+    }
+}
+
+impl SimMessage for KexMessage {
+    fn kind(&self) -> &'static str {
+        match self {
+            KexMessage::Round1(_) => "KexRound1",
+            KexMessage::Round2(_) => "KexRound2",
+            KexMessage::Confirm(_) => "KexConfirm",
+        }
+    }
+}
+
+/// One party of the group key agreement, advancing as round messages arrive
+#[derive(Debug)]
+pub struct KexParty {
+    party: u8,
+    exchange: Option<GroupKeyExchange>,
+    round1: Vec<Round1Message>,
+    round2: Vec<Round2Message>,
+    confirmations: Vec<Confirmation>,
+    /// Index of the next round to process: 1, 2, 3, then 4 once finished
+    next_round: u8,
+    key: Option<SessionKey>,
+}
+
+impl KexParty {
+    pub fn new(party: u8) -> CryptoResult<Self> {
+        Ok(Self {
+            party,
+            exchange: Some(GroupKeyExchange::new(party)?),
+            round1: Vec::new(),
+            round2: Vec::new(),
+            confirmations: Vec::new(),
+            next_round: 1,
+            key: None,
+        })
+    }
+
+    /// Broadcasts this party's first-round message
+    pub fn start(&mut self) -> CryptoResult<Vec<(NodeId, KexMessage)>> {
+        let message = self.exchange()?.round1();
+        self.round1.push(message);
+        Ok(self.broadcast(KexMessage::Round1(message)))
+    }
+
+    /// Agreed key, once every confirmation checked out
+    pub fn key(&self) -> Option<&SessionKey> {
+        self.key.as_ref()
+    }
+
+    fn exchange(&mut self) -> CryptoResult<&mut GroupKeyExchange> {
+        self.exchange.as_mut().ok_or_else(|| CryptoError::InvalidState("Key exchange finished".into()))
+    }
+
+    fn broadcast(&self, message: KexMessage) -> Vec<(NodeId, KexMessage)> {
+        (0..PARTY_COUNT as u8)
+            .filter(|&party| party != self.party)
+            .map(|party| (NodeId::Custodian(party), message.clone()))
+            .collect()
+    }
+
+    /// Runs every round whose messages are all in
+    fn advance(&mut self) -> CryptoResult<Vec<(NodeId, KexMessage)>> {
+        let mut outgoing = Vec::new();
+        loop {
+            match self.next_round {
+                1 if self.round1.len() == PARTY_COUNT => {
+                    let round1 = std::mem::take(&mut self.round1);
+                    let message = self.exchange()?.round2(&round1)?;
+                    self.round2.push(message);
+                    outgoing.extend(self.broadcast(KexMessage::Round2(message)));
+                }
+                2 if self.round2.len() == PARTY_COUNT => {
+                    let round2 = std::mem::take(&mut self.round2);
+                    let message = self.exchange()?.confirm(&round2)?;
+                    self.confirmations.push(message);
+                    outgoing.extend(self.broadcast(KexMessage::Confirm(message)));
+                }
+                3 if self.confirmations.len() == PARTY_COUNT => {
+                    let exchange = self.exchange.take().expect("exchange present until finished");
+                    self.key = Some(exchange.finish(&self.confirmations)?);
+                    debug!(party = self.party, "Simulated key exchange complete");
+                }
+                _ => return Ok(outgoing),
+            }
+            self.next_round += 1;
+        }
+    }
+}
+
+/// Buffers a message unless one from the same party is already held
+fn buffer<T: PartialEq>(held: &mut Vec<T>, message: T, party_of: impl Fn(&T) -> u8) -> CryptoResult<()> {
+    match held.iter().find(|m| party_of(m) == party_of(&message)) {
+        Some(existing) if *existing == message => Ok(()),
+        Some(_) => Err(CryptoError::VerificationFailed(
+            format!("Conflicting messages from party {}", party_of(&message))
+        )),
+        None => {
+            held.push(message);
+            Ok(())
+        }
+    }
+}
+
+impl SimNode<KexMessage> for KexParty {
+    fn id(&self) -> NodeId {
+        NodeId::Custodian(self.party)
+    }
+
+    fn receive(&mut self, from: NodeId, message: KexMessage) -> CryptoResult<Vec<(NodeId, KexMessage)>> {
+        if from != NodeId::Custodian(message.party()) {
+            return Err(CryptoError::VerificationFailed(format!("{} sent a message for party {}", from, message.party())));
+        }
+
+        // Late copies of finished rounds are harmless
+        let round = match message {
+            KexMessage::Round1(_) => 1,
+            KexMessage::Round2(_) => 2,
+            KexMessage::Confirm(_) => 3,
+        };
+        if round < self.next_round {
+            return Ok(Vec::new());
+        }
+
+        match message {
+            KexMessage::Round1(m) => buffer(&mut self.round1, m, |m| m.party)?,
+            KexMessage::Round2(m) => buffer(&mut self.round2, m, |m| m.party)?,
+            KexMessage::Confirm(m) => buffer(&mut self.confirmations, m, |m| m.party)?,
+        }
+        self.advance()
+    }
+}
+
+/// Authentication messages carried by the network
+#[derive(Debug, Clone)]
+pub enum AuthMessage {
+    Challenge(AuthChallenge),
+    Response(AuthResponse),
+}
+
+impl SimMessage for AuthMessage {
+    fn kind(&self) -> &'static str {
+        match self {
+            AuthMessage::Challenge(_) => "AuthChallenge",
+            AuthMessage::Response(_) => "AuthResponse",
+        }
+    }
+}
+
+/// Verifier issuing a challenge and collecting the three responses
+#[derive(Debug)]
+pub struct AuthVerifier {
+    auth: ThreePartyAuthentication,
+    challenge: Option<AuthChallenge>,
+    responses: Vec<AuthResponse>,
+    outcome: Option<CryptoResult<()>>,
+}
+
+impl AuthVerifier {
+    pub fn new(auth: ThreePartyAuthentication) -> Self {
+        Self {
+            auth,
+            challenge: None,
+            responses: Vec::new(),
+            outcome: None,
+        }
+    }
+
+    /// Issues a challenge to every party
+    pub fn start(&mut self, message: impl AsRef<[u8]>) -> Vec<(NodeId, AuthMessage)> {
+        let challenge = self.auth.issue(message);
+        self.challenge = Some(challenge.clone());
+        self.responses.clear();
+        self.outcome = None;
+
+        (0..auth::PARTY_COUNT as u8)
+            .map(|party| (NodeId::Custodian(party), AuthMessage::Challenge(challenge.clone())))
+            .collect()
+    }
+
+    /// Result of verification, once all three responses arrived
+    pub fn outcome(&self) -> Option<&CryptoResult<()>> {
+        self.outcome.as_ref()
+    }
+}
+
+impl SimNode<AuthMessage> for AuthVerifier {
+    fn id(&self) -> NodeId {
+        NodeId::Combiner
+    }
+
+    fn receive(&mut self, from: NodeId, message: AuthMessage) -> CryptoResult<Vec<(NodeId, AuthMessage)>> {
+        let AuthMessage::Response(response) = message else {
+            return Err(CryptoError::InvalidState("Verifier only accepts responses".into()));
+        };
+        if from != NodeId::Custodian(response.party) {
+            return Err(CryptoError::AuthenticationFailed {
+                party: response.party,
+                reason: format!("response sent by {}", from),
+            });
+        }
+        let challenge = self.challenge.as_ref()
+            .ok_or_else(|| CryptoError::InvalidState("No challenge issued".into()))?;
+
+        if self.outcome.is_none() && !self.responses.iter().any(|r| r.party == response.party) {
+            self.responses.push(response);
+            if self.responses.len() == auth::PARTY_COUNT {
+                self.outcome = Some(self.auth.verify(challenge, &self.responses));
+            }
+        }
+        Ok(Vec::new())
+    }
+}
+
+/// Party answering challenges with its token
+#[derive(Debug)]
+pub struct AuthParty {
+    party: u8,
+    token: PartyToken,
+}
+
+impl AuthParty {
+    pub fn new(party: u8, token: PartyToken) -> Self {
+        Self { party, token }
+    }
+}
+
+impl SimNode<AuthMessage> for AuthParty {
+    fn id(&self) -> NodeId {
+        NodeId::Custodian(self.party)
+    }
+
+    fn receive(&mut self, from: NodeId, message: AuthMessage) -> CryptoResult<Vec<(NodeId, AuthMessage)>> {
+        let AuthMessage::Challenge(challenge) = message else {
+            return Err(CryptoError::InvalidState("Party only accepts challenges".into()));
+        };
+        let response = auth::respond(&challenge, self.party, &self.token)?;
+        Ok(vec![(from, AuthMessage::Response(response))])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::{FaultPlan, Network};
+
+    #[test]
+    fn test_key_exchange_survives_reordering_and_duplicates() -> CryptoResult<()> {
+        let faults = FaultPlan { duplicate_rate: 0.5, min_delay: 1, max_delay: 50, ..Default::default() };
+        let mut network = Network::new(42, faults)?;
+        let mut parties = (0..PARTY_COUNT as u8).map(KexParty::new).collect::<CryptoResult<Vec<_>>>()?;
+
+        for party in parties.iter_mut() {
+            let id = SimNode::id(party);
+            let messages = party.start()?;
+            network.send_all(id, messages);
+        }
+        let mut nodes: Vec<&mut dyn SimNode<KexMessage>> =
+            parties.iter_mut().map(|p| p as &mut dyn SimNode<KexMessage>).collect();
+        network.run(&mut nodes, 10_000);
+
+        assert!(network.transcript().rejections().is_empty());
+        let keys: Vec<_> = parties.iter().map(|p| p.key().expect("key agreed")).collect();
+        assert!(keys.windows(2).all(|pair| pair[0] == pair[1]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_authentication_with_impostor() {
+        let tokens = [PartyToken::generate(), PartyToken::generate(), PartyToken::generate()];
+        let mut verifier = AuthVerifier::new(ThreePartyAuthentication::new(tokens.clone()));
+        let mut parties: Vec<_> = tokens.iter().cloned().enumerate()
+            .map(|(party, token)| AuthParty::new(party as u8, token))
+            .collect();
+        parties[2] = AuthParty::new(2, PartyToken::generate());
+
+        let mut network = Network::new(3, FaultPlan::default()).unwrap();
+        let challenges = verifier.start("open records");
+        network.send_all(NodeId::Combiner, challenges);
+        let mut nodes: Vec<&mut dyn SimNode<AuthMessage>> = vec![&mut verifier];
+        nodes.extend(parties.iter_mut().map(|p| p as &mut dyn SimNode<AuthMessage>));
+        network.run(&mut nodes, 100);
+
+        assert!(matches!(
+            verifier.outcome(),
+            Some(Err(CryptoError::AuthenticationFailed { party: 2, .. }))
+        ));
+    }
+}
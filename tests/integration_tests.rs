@@ -5,8 +5,10 @@ use mcafee::{
     crypto::{
         vdf::temporal::TemporalVDF,
         sharing::ThreePartySecretSharing,
+        protocol::{Combiner, Custodian, Dealer, Message, NodeId},
     },
     error::CryptoResult,
+    sim::{FaultPlan, Network, SimNode},
 };
 use std::time::Duration;
 use rand::Rng;
@@ -24,6 +26,7 @@ fn test_secret_sharing_with_vdf() -> CryptoResult<()> {
         enforce_timing: false,
         memory_size: 1024,
         verification_steps: 4,
+        ..Default::default()
     };
     let mut vdf = TemporalVDF::new(config);
 
@@ -144,6 +147,7 @@ fn test_realistic_medical_image() -> CryptoResult<()> {
         enforce_timing: false,
         memory_size: image_size,
         verification_steps: 4,
+        ..Default::default()
     };
 
     let sharing_config = mcafee::crypto::sharing::SharingConfig {
@@ -159,7 +163,6 @@ fn test_realistic_medical_image() -> CryptoResult<()> {
     // Phase 1: Split into shares
     info!("Splitting medical image...");
     let mut shares = sharing.split(&image_data)?;
-    let padded_size = shares[0].data().len();
 
     // Phase 2: Apply temporal protection
     info!("Applying temporal protection...");
@@ -191,4 +194,86 @@ fn test_realistic_medical_image() -> CryptoResult<()> {
     assert_eq!(reconstructed, image_data, "Data mismatch after reconstruction");
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Three hospitals as custodians, plus the dealer and combiner
+struct Deployment {
+    dealer: Dealer,
+    custodians: Vec<Custodian>,
+    combiner: Combiner,
+}
+
+impl Deployment {
+    fn new() -> Self {
+        Self {
+            dealer: Dealer::new(ThreePartySecretSharing::default()),
+            custodians: (0..3).map(Custodian::new).collect(),
+            combiner: Combiner::new(ThreePartySecretSharing::default()),
+        }
+    }
+
+    fn run(&mut self, network: &mut Network<Message>) {
+        let mut nodes: Vec<&mut dyn SimNode<Message>> = vec![&mut self.dealer, &mut self.combiner];
+        nodes.extend(self.custodians.iter_mut().map(|c| c as &mut dyn SimNode<Message>));
+        network.run(&mut nodes, 10_000);
+    }
+}
+
+#[test]
+fn test_distribution_over_lossy_network() -> CryptoResult<()> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let faults = FaultPlan { drop_rate: 0.4, duplicate_rate: 0.1, min_delay: 1, max_delay: 30 };
+    let mut network = Network::new(2024, faults)?;
+    let mut deployment = Deployment::new();
+    let secret = b"Cohort key for the three-hospital study";
+
+    // The dealer retransmits whatever was not acknowledged
+    let (id, deliveries) = deployment.dealer.deal(secret)?;
+    network.send_envelopes(NodeId::Dealer, deliveries);
+    for _ in 0..20 {
+        deployment.run(&mut network);
+        if deployment.dealer.is_delivered(&id) {
+            break;
+        }
+        network.send_envelopes(NodeId::Dealer, deployment.dealer.retransmit(&id));
+    }
+    assert!(deployment.dealer.is_delivered(&id));
+
+    // The combiner repeats its request to custodians it has not heard from
+    network.send_envelopes(NodeId::Combiner, deployment.combiner.request(id));
+    for _ in 0..20 {
+        deployment.run(&mut network);
+        if deployment.combiner.is_complete(&id) {
+            break;
+        }
+        network.send_envelopes(NodeId::Combiner, deployment.combiner.retransmit(&id));
+    }
+
+    assert_eq!(deployment.combiner.take_secret(&id).as_deref(), Some(&secret[..]));
+    assert!(network.transcript().dropped() > 0, "the fault plan should have lost messages");
+    Ok(())
+}
+
+#[test]
+fn test_crashed_custodian_blocks_reconstruction() -> CryptoResult<()> {
+    let mut network = Network::new(7, FaultPlan::default())?;
+    let mut deployment = Deployment::new();
+
+    let (id, deliveries) = deployment.dealer.deal(b"release only with all three")?;
+    network.send_envelopes(NodeId::Dealer, deliveries);
+    deployment.run(&mut network);
+    assert!(deployment.dealer.is_delivered(&id));
+
+    network.crash(NodeId::Custodian(1));
+    network.send_envelopes(NodeId::Combiner, deployment.combiner.request(id));
+    deployment.run(&mut network);
+    assert!(!deployment.combiner.is_complete(&id));
+    assert_eq!(deployment.combiner.missing(&id), vec![NodeId::Custodian(1)]);
+
+    network.recover(NodeId::Custodian(1));
+    network.send_envelopes(NodeId::Combiner, deployment.combiner.request(id));
+    deployment.run(&mut network);
+    assert!(deployment.combiner.is_complete(&id));
+    Ok(())
+}